@@ -12,7 +12,26 @@
 //! ## Features
 //!
 //! - `gen_match_concretes_macro!` - Generates macros for matching multiple enum instances
-//!   simultaneously, with support for 2-5 enum types.
+//!   simultaneously, with support for any number of enum types from 2 upwards. It forwards to
+//!   the `gen_match_concretes!` procedural macro, also re-exported here.
+//! - `gen_mapping_diagram!` - Builds a const mermaid diagram of the variant to concrete type
+//!   mappings of one or more enums, for architecture docs generated from code.
+//! - `gen_api_snapshot!` - Summarizes the items generated for one or more enums, for snapshot
+//!   tests catching accidental changes to a crate's public surface.
+//! - `gen_registry!` - Generates a static registry of constructors keyed by the variants of an
+//!   enum, with a `build` function turning a kind read from configuration into a boxed trait
+//!   object. It is re-exported from `concrete-type`.
+//! - `gen_visitor2!` - Generates a visitor trait generic over the concrete types of two enums
+//!   and a function dispatching on both, `Visitor2` and `accept` unless named otherwise, for
+//!   double dispatch where the nested dispatch macros can't be expanded in place. It is
+//!   re-exported from `concrete-type`.
+//! - `gen_merged_enum!` - Merges several `Concrete` enums into one kind enum dispatching to the
+//!   concrete types of all of them, with conversions from and to each input enum.
+//! - `compose_concrete!` - Does the same for enums given by path, e.g.
+//!   `compose_concrete!(AnyExchange = base::Exchange + dex::DexExchange)`, for kind enums split
+//!   across the crates of a workspace.
+//! - `assert_same_mapping!` - Fails to compile unless a kind enum and its config enum (or any
+//!   other enums) map the same variants to the same concrete types, in the same order.
 //!
 //! ## Examples
 //!
@@ -65,10 +84,20 @@
 //! // Generated macro name combines all enum names in snake_case
 //! // E.g., match_exchange_strategy_market!
 //!
-//! // For 4 or 5 enum types:
+//! // For 4, 5 or more enum types:
 //! gen_match_concretes_macro!(Exchange, Strategy, Market, Asset, TimeFrame);
+//!
+//! // Or name the generated macro explicitly
+//! gen_match_concretes_macro!(Exchange, Strategy, Market, Asset, TimeFrame => match_all);
 //! ```
 
+#[doc(inline)]
+pub use concrete_type::gen_match_concretes;
+#[doc(inline)]
+pub use concrete_type::gen_registry;
+#[doc(inline)]
+pub use concrete_type::gen_visitor2;
+
 /// A macro that generates a combined matcher macro for multiple concrete enums.
 ///
 /// This macro creates a new macro that allows you to match multiple enum instances
@@ -79,10 +108,215 @@
 ///
 /// * First argument: First enum type name
 /// * Second argument: Second enum type name
-/// * Optionally: Third, fourth, and fifth enum type names
+/// * Optionally: Any number of further enum type names
+/// * Optionally: `via path::to::macro` after an enum name, when its dispatch macro isn't
+///   reachable by its bare name
+/// * Optionally: `= Variant` after an enum name, to fix that enum to one variant
+/// * Optionally: `=> macro_name` to name the generated macro explicitly
+///
+/// By default the generated macro will be named using the snake_case of all provided enum names,
+/// joined with underscores and prefixed with "match_". Long combinations get unwieldy names, and
+/// the same enums combined in several crates collide, so a name can be given instead:
+///
+/// ```rust
+/// # use concrete_type::Concrete;
+/// # use concrete_type_rules::gen_match_concretes_macro;
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// gen_match_concretes_macro!(Exchange, Strategy => dispatch_pair);
+///
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Okx, Strategy::StrategyA);
+/// let result = dispatch_pair!(exchange, strategy; E, S => { std::any::type_name::<(E, S)>() });
+/// # assert!(result.contains("::Okx, ") && result.ends_with("::StrategyA)"));
+/// # }
+/// ```
+///
+/// The generated macro calls each enum's dispatch macro by its bare snake_case name, e.g.
+/// `exchange!`. When that macro is re-exported from a module (see `macros_mod`) or renamed,
+/// give its path with `via`. The path is resolved where the generated macro is invoked:
+///
+/// ```rust
+/// # use concrete_type::Concrete;
+/// # use concrete_type_rules::gen_match_concretes_macro;
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # #[concrete(macros_mod = "dispatch")]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// gen_match_concretes_macro!(Exchange via crate::dispatch::exchange, Strategy);
+/// # fn main() {
+/// # let result = match_exchange_strategy!(Exchange::Binance, Strategy::StrategyB; E, S => {
+/// #     std::any::type_name::<(E, S)>()
+/// # });
+/// # assert!(result.contains("::Binance, ") && result.ends_with("::StrategyB)"));
+/// # }
+/// ```
+///
+/// When one dimension is constant in a given binary, fix it to a variant. Only the remaining
+/// enums are dispatched at runtime, and the fixed concrete type is aliased directly, so the other
+/// variants of that enum are never monomorphized:
+///
+/// ```rust
+/// # use concrete_type::Concrete;
+/// # use concrete_type_rules::gen_match_concretes_macro;
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// gen_match_concretes_macro!(Exchange = Binance, Strategy);
+///
+/// # fn main() {
+/// # let strategy = Strategy::StrategyB;
+/// // Takes a `Strategy` value only, but a type parameter for both enums
+/// let result = match_exchange_binance_strategy!(strategy; E, S => {
+///     std::any::type_name::<(E, S)>()
+/// });
+/// # assert!(result.contains("::Binance, ") && result.ends_with("::StrategyB)"));
+/// # }
+/// ```
 ///
-/// The generated macro will be named using the snake_case of all provided enum names,
-/// joined with underscores and prefixed with "match_".
+/// # Memoized Dispatch
+///
+/// Every combination normally gets its own copy of the code block, which gets expensive for large
+/// spaces (e.g. 12 × 10 × 8 combinations). Prefixing the values with `@memoized` and writing the
+/// body as a closure turns it into a generic function that is type-checked once. Dispatching only
+/// selects the function pointer of the matched combination, cached per call site in a table keyed
+/// by the variants, at the cost of a dynamic call:
+///
+/// ```rust
+/// # use concrete_type::Concrete;
+/// # use concrete_type_rules::gen_match_concretes_macro;
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// # gen_match_concretes_macro!(Exchange, Strategy);
+/// # trait Fee {
+/// #     const BASIS_POINTS: u64;
+/// # }
+/// # impl Fee for Binance {
+/// #     const BASIS_POINTS: u64 = 10;
+/// # }
+/// # impl Fee for Okx {
+/// #     const BASIS_POINTS: u64 = 8;
+/// # }
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Okx, Strategy::StrategyA);
+/// let notional = 1_000_000;
+/// let fee = match_exchange_strategy!(@memoized exchange, strategy; E: Fee, S => |notional: u64| -> u64 {
+///     notional * E::BASIS_POINTS / 10_000
+/// });
+/// # assert_eq!(fee, 800);
+/// # }
+/// ```
+///
+/// Since the body is a generic function rather than a block, methods of the type parameters need
+/// a trait bound (`E: Fee`), and local variables must be passed as closure arguments: the
+/// arguments are forwarded from variables of the same name at the call site.
+///
+/// # Named Combinations
+///
+/// Prefixing the values with `@named` binds a `COMBO_NAME: &str` constant inside the block, the
+/// snake_case names of the matched variants joined by `+`, e.g. for metrics keys:
+///
+/// ```rust
+/// # use concrete_type::Concrete;
+/// # use concrete_type_rules::gen_match_concretes_macro;
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// # gen_match_concretes_macro!(Exchange, Strategy);
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Binance, Strategy::StrategyA);
+/// let key = match_exchange_strategy!(@named exchange, strategy; E, S => {
+///     COMBO_NAME // "binance+strategy_a"
+/// });
+/// # assert_eq!(key, "binance+strategy_a");
+/// # }
+/// ```
+///
+/// Nested variants are named `outer.inner`. Unlike the default form, the block is expanded once
+/// per combination of variants rather than of distinct concrete types.
+///
+/// # Combination Budget
+///
+/// Enums declaring `#[concrete(max_combinations = N)]` cap the number of block expansions of the
+/// default form. Each call site asserts at compile time that the product of the distinct concrete
+/// types of its non-fixed enums stays within the lowest cap, pointing to `@memoized` otherwise.
 ///
 /// # Generated Macro Usage
 ///
@@ -92,7 +326,12 @@
 /// * Type parameters and a code block after a semicolon
 ///
 /// Inside the code block, each type parameter is aliased to the concrete type
-/// associated with the corresponding enum variant.
+/// associated with the corresponding enum variant. Variants sharing a concrete type share an arm
+/// in their enum's dispatch macro, so the block is expanded once per combination of distinct
+/// concrete types.
+///
+/// This is a thin wrapper around the [`gen_match_concretes!`] procedural macro, which does the
+/// name pasting itself and supports any number of enums.
 ///
 /// # Examples
 ///
@@ -129,81 +368,458 @@
 /// ```
 #[macro_export]
 macro_rules! gen_match_concretes_macro {
-    // For 2 enum types
-    ($first_enum:ident, $second_enum:ident) => {
-        paste::paste! {
-            #[macro_export]
-            macro_rules! [<match_ $first_enum:snake _ $second_enum:snake>] {
-                ($first_var:expr, $second_var:expr; $first_type:ident, $second_type:ident => $code_block:block) => {
-                    [<$first_enum:snake>]!($first_var; $first_type => {
-                        [<$second_enum:snake>]!($second_var; $second_type => {
-                            $code_block
-                        })
-                    })
-                };
-            }
-        }
+    ($($input:tt)*) => {
+        $crate::gen_match_concretes!($($input)*);
     };
+}
 
-    // For 3 enum types
-    ($first_enum:ident, $second_enum:ident, $third_enum:ident) => {
-        paste::paste! {
-            #[macro_export]
-            macro_rules! [<match_ $first_enum:snake _ $second_enum:snake _ $third_enum:snake>] {
-                ($first_var:expr, $second_var:expr, $third_var:expr; $first_type:ident, $second_type:ident, $third_type:ident => $code_block:block) => {
-                    [<$first_enum:snake>]!($first_var; $first_type => {
-                        [<$second_enum:snake>]!($second_var; $second_type => {
-                            [<$third_enum:snake>]!($third_var; $third_type => {
-                                $code_block
-                            })
-                        })
-                    })
-                };
-            }
+/// A macro that builds a mermaid flowchart of the variant to concrete type mappings of one or
+/// more enums deriving `Concrete` or `ConcreteConfig`.
+///
+/// The diagram is a `&'static str` computed at compile time, so it can initialize a `const`.
+/// Concrete types shared by several enums appear as a single node.
+///
+/// # Examples
+///
+/// ```rust
+/// use concrete_type_rules::gen_mapping_diagram;
+/// # use concrete_type::Concrete;
+/// # mod exchanges {
+/// #     pub struct Binance;
+/// # }
+/// # pub struct StrategyA;
+/// # #[derive(Concrete)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::exchanges::Binance"]
+/// #     Binance,
+/// # }
+/// # #[derive(Concrete)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// # }
+///
+/// const ARCHITECTURE: &str = gen_mapping_diagram!(Exchange, Strategy);
+///
+/// // graph LR
+/// //     enum_Exchange["Exchange"]
+/// //     enum_Exchange --> variant_Exchange_Binance["Binance"]
+/// //     variant_Exchange_Binance --> type_crate__exchanges__Binance["crate::exchanges::Binance"]
+/// //     ...
+/// # fn main() {
+/// # assert!(ARCHITECTURE.starts_with(
+/// #     "graph LR\n    enum_Exchange[\"Exchange\"]\n    \
+/// #      enum_Exchange --> variant_Exchange_Binance[\"Binance\"]\n    \
+/// #      variant_Exchange_Binance --> type_crate__exchanges__Binance[\"crate::exchanges::Binance\"]\n"
+/// # ));
+/// println!("```mermaid\n{}```", ARCHITECTURE);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! gen_mapping_diagram {
+    ($($enum_type:ident),+ $(,)?) => {{
+        const PARTS: &[&str] = &["graph LR\n", $($enum_type::CONCRETE_DIAGRAM),+];
+        const LEN: usize = $crate::__private::concat_len(PARTS);
+        const BYTES: [u8; LEN] = $crate::__private::concat_bytes(PARTS);
+        $crate::__private::bytes_to_str(&BYTES)
+    }};
+}
+
+/// A macro that combines the summaries of the items generated for one or more enums deriving
+/// `Concrete` or `ConcreteConfig` with `#[concrete(api_snapshot)]`, for snapshot tests catching
+/// changes to a crate's public surface caused by the derives.
+///
+/// Each enum contributes a `# Enum` header followed by one line per generated item: the rules of
+/// its dispatch macros, the methods and constants of each impl block, and the generated types.
+/// Lines are sorted, so the summary only changes when the generated items do.
+///
+/// # Examples
+///
+/// ```rust
+/// use concrete_type_rules::gen_api_snapshot;
+/// # use concrete_type::Concrete;
+/// # pub struct Binance;
+/// # pub struct StrategyA;
+/// # #[derive(Concrete)]
+/// # #[concrete(api_snapshot)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// # }
+/// # #[derive(Concrete)]
+/// # #[concrete(api_snapshot)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// # }
+///
+/// const GENERATED_API: &str = gen_api_snapshot!(Exchange, Strategy);
+///
+/// // # Exchange
+/// // impl Exchange
+/// // ...
+/// // macro exchange! ($enum_instance: expr; $type_param: ident => $code_block: block)
+/// // ...
+/// # fn main() {
+/// assert!(GENERATED_API.contains(
+///     "impl Exchange: pub const fn variant_index(&self) -> usize\n"
+/// ));
+/// // In a test, compared with a file checked in next to it:
+/// // assert_eq!(GENERATED_API, include_str!("snapshots/generated_api.txt"));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! gen_api_snapshot {
+    ($($enum_type:ident),+ $(,)?) => {{
+        const PARTS: &[&str] = &[$(
+            "# ", ::core::stringify!($enum_type), "\n", $enum_type::CONCRETE_API
+        ),+];
+        const LEN: usize = $crate::__private::concat_len(PARTS);
+        const BYTES: [u8; LEN] = $crate::__private::concat_bytes(PARTS);
+        $crate::__private::bytes_to_str(&BYTES)
+    }};
+}
+
+/// A macro that merges several enums deriving `Concrete` into one kind enum, e.g. to select
+/// among backends split across crates in the binary combining them.
+///
+/// `gen_merged_enum!(Exchange, DexExchange => AnyExchange)` generates an `AnyExchange` enum with
+/// one variant per input enum, named and holding it, like `AnyExchange::DexExchange(DexExchange)`.
+/// Each input enum converts into the merged enum with `From`, and back with `TryFrom`, which
+/// hands back the merged value when it holds another enum.
+///
+/// The merged enum derives `Concrete` with every variant nested and flattened, so its dispatch
+/// macro (`any_exchange!`) matches every backend of every input enum at once and aliases the same
+/// concrete types as the input enums. The input enums' dispatch macros must therefore be in scope
+/// by their default snake_case names.
+///
+/// Attributes and a visibility may precede the merged enum's name:
+///
+/// ```rust
+/// use concrete_type_rules::gen_merged_enum;
+/// # use concrete_type::Concrete;
+/// # pub struct Binance;
+/// # pub struct Uniswap;
+/// # #[derive(Concrete, Clone, Copy, Debug)]
+/// # pub enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// # }
+/// # #[derive(Concrete, Clone, Copy, Debug)]
+/// # pub enum DexExchange {
+/// #     #[concrete = "crate::Uniswap"]
+/// #     Uniswap,
+/// # }
+///
+/// gen_merged_enum!(Exchange, DexExchange => #[derive(Clone, Copy, Debug)] pub AnyExchange);
+///
+/// # fn main() {
+/// let exchange = AnyExchange::from(DexExchange::Uniswap);
+/// let name = any_exchange!(exchange; E => { std::any::type_name::<E>() });
+/// # assert!(name.ends_with("::Uniswap"));
+/// assert!(DexExchange::try_from(exchange).is_ok());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! gen_merged_enum {
+    // Shared with `compose_concrete!`, which names each variant after the last segment of a path
+    // and dispatches enums of other crates with `nested_extern`
+    (
+        @emit [$(#[$attr:meta])*] [$vis:vis] $merged:ident;
+        $(($variant:ident [$($enum_type:tt)+] $nested:ident))+
+    ) => {
+        #[derive($crate::__private::Concrete)]
+        #[concrete(flatten)]
+        $(#[$attr])*
+        $vis enum $merged {
+            $(
+                #[allow(missing_docs)]
+                #[concrete($nested)]
+                $variant($($enum_type)+),
+            )+
         }
-    };
 
-    // For 4 enum types
-    ($first_enum:ident, $second_enum:ident, $third_enum:ident, $fourth_enum:ident) => {
-        paste::paste! {
-            #[macro_export]
-            macro_rules! [<match_ $first_enum:snake _ $second_enum:snake _ $third_enum:snake _ $fourth_enum:snake>] {
-                ($first_var:expr, $second_var:expr, $third_var:expr, $fourth_var:expr;
-                 $first_type:ident, $second_type:ident, $third_type:ident, $fourth_type:ident => $code_block:block) => {
-                    [<$first_enum:snake>]!($first_var; $first_type => {
-                        [<$second_enum:snake>]!($second_var; $second_type => {
-                            [<$third_enum:snake>]!($third_var; $third_type => {
-                                [<$fourth_enum:snake>]!($fourth_var; $fourth_type => {
-                                    $code_block
-                                })
-                            })
-                        })
-                    })
-                };
+        $(
+            impl ::core::convert::From<$($enum_type)+> for $merged {
+                fn from(value: $($enum_type)+) -> Self {
+                    $merged::$variant(value)
+                }
             }
-        }
+
+            impl ::core::convert::TryFrom<$merged> for $($enum_type)+ {
+                type Error = $merged;
+
+                fn try_from(value: $merged) -> ::core::result::Result<Self, $merged> {
+                    match value {
+                        $merged::$variant(value) => ::core::result::Result::Ok(value),
+                        #[allow(unreachable_patterns)]
+                        other => ::core::result::Result::Err(other),
+                    }
+                }
+            }
+        )+
+    };
+    (
+        $($enum_type:ident),+ $(,)? => $(#[$attr:meta])* $vis:vis $merged:ident
+    ) => {
+        $crate::gen_merged_enum!(
+            @emit [$(#[$attr])*] [$vis] $merged; $(($enum_type [$enum_type] nested))+
+        );
     };
+}
 
-    // For 5 enum types
-    ($first_enum:ident, $second_enum:ident, $third_enum:ident, $fourth_enum:ident, $fifth_enum:ident) => {
-        paste::paste! {
-            #[macro_export]
-            macro_rules! [<match_ $first_enum:snake _ $second_enum:snake _ $third_enum:snake _ $fourth_enum:snake _ $fifth_enum:snake>] {
-                ($first_var:expr, $second_var:expr, $third_var:expr, $fourth_var:expr, $fifth_var:expr;
-                 $first_type:ident, $second_type:ident, $third_type:ident, $fourth_type:ident, $fifth_type:ident => $code_block:block) => {
-                    [<$first_enum:snake>]!($first_var; $first_type => {
-                        [<$second_enum:snake>]!($second_var; $second_type => {
-                            [<$third_enum:snake>]!($third_var; $third_type => {
-                                [<$fourth_enum:snake>]!($fourth_var; $fourth_type => {
-                                    [<$fifth_enum:snake>]!($fifth_var; $fifth_type => {
-                                        $code_block
-                                    })
-                                })
-                            })
-                        })
-                    })
-                };
+/// A macro composing enums deriving `Concrete` from several crates into one kind enum, for
+/// workspaces where a base crate defines the selector and sibling crates contribute backends.
+///
+/// `compose_concrete!(pub AnyExchange = base::Exchange + dex::DexExchange)` generates the same
+/// merged enum as [`gen_merged_enum!`], taking the input enums by path: each variant is named
+/// after the last segment of its path, like `AnyExchange::DexExchange(dex::DexExchange)`, with
+/// conversions from and to each input enum.
+///
+/// The pattern this supports:
+///
+/// 1. The base crate defines the trait every backend implements, and its own kind enum.
+/// 2. Each sibling crate depends on the base crate, implements the trait for its backends, and
+///    derives `Concrete` on a kind enum of its own.
+/// 3. The binary crate composes them, and dispatches on the composed enum with `any_exchange!`.
+///
+/// The composed enum's macro forwards to the dispatch macros of the input enums. Those of enums
+/// given by a `crate::` path must be in scope under their own names, while those of other crates
+/// are reached through the first segment of the path, the crate exporting them at its root, so
+/// they need no import. As with any dispatch macro, the input enums must be in scope under their
+/// own names wherever the composed enum's macro is called.
+///
+/// # Examples
+///
+/// With `base` and `dex` crates, the binary crate composes `base::Exchange + dex::DexExchange`.
+/// Here they are modules of the same crate, so their enums are given by `crate::` paths:
+///
+/// ```rust
+/// # mod base {
+/// #     use concrete_type::Concrete;
+/// #     pub trait ExchangeApi {
+/// #         fn name(&self) -> &'static str;
+/// #     }
+/// #     #[derive(Default)]
+/// #     pub struct Binance;
+/// #     impl ExchangeApi for Binance {
+/// #         fn name(&self) -> &'static str {
+/// #             "binance"
+/// #         }
+/// #     }
+/// #     #[derive(Concrete, Clone, Copy, Debug)]
+/// #     pub enum Exchange {
+/// #         #[concrete = "crate::base::Binance"]
+/// #         Binance,
+/// #     }
+/// # }
+/// # mod dex {
+/// #     use concrete_type::Concrete;
+/// #     #[derive(Default)]
+/// #     pub struct Uniswap;
+/// #     impl crate::base::ExchangeApi for Uniswap {
+/// #         fn name(&self) -> &'static str {
+/// #             "uniswap"
+/// #         }
+/// #     }
+/// #     #[derive(Concrete, Clone, Copy, Debug)]
+/// #     pub enum DexExchange {
+/// #         #[concrete = "crate::dex::Uniswap"]
+/// #         Uniswap,
+/// #     }
+/// # }
+/// use base::Exchange;
+/// use concrete_type_rules::compose_concrete;
+/// use dex::DexExchange;
+///
+/// compose_concrete!(
+///     #[derive(Clone, Copy, Debug)]
+///     pub AnyExchange = crate::base::Exchange + crate::dex::DexExchange
+/// );
+///
+/// # fn main() {
+/// let exchange = AnyExchange::from(dex::DexExchange::Uniswap);
+/// let api = any_exchange!(exchange; E => { Box::new(E::default()) as Box<dyn base::ExchangeApi> });
+/// assert_eq!(api.name(), "uniswap");
+/// # let _ = Exchange::Binance;
+/// # }
+/// ```
+#[macro_export]
+macro_rules! compose_concrete {
+    ($(#[$attr:meta])* $vis:vis $merged:ident = $($paths:tt)+) => {
+        $crate::compose_concrete!(@next [$(#[$attr])*] [$vis] $merged; []; $($paths)+);
+    };
+    // Starts a path: enums of the calling crate are dispatched through their macros in scope, and
+    // enums of other crates through the macros exported at the root of their crate
+    (@next $attrs:tt $vis:tt $merged:ident; [$($done:tt)*]; crate $($rest:tt)+) => {
+        $crate::compose_concrete!(
+            @path $attrs $vis $merged; [$($done)*]; nested [crate] crate; $($rest)+
+        );
+    };
+    (@next $attrs:tt $vis:tt $merged:ident; [$($done:tt)*]; $($rest:tt)+) => {
+        $crate::compose_concrete!(
+            @path $attrs $vis $merged; [$($done)*]; nested_extern [] _; $($rest)+
+        );
+    };
+    // Accumulates the current path, remembering its last identifier to name the variant
+    (
+        @path $attrs:tt $vis:tt $merged:ident; [$($done:tt)*]; $nested:ident [$($path:tt)*]
+        $last:tt; $segment:ident $($rest:tt)*
+    ) => {
+        $crate::compose_concrete!(
+            @path $attrs $vis $merged; [$($done)*]; $nested [$($path)* $segment] $segment;
+            $($rest)*
+        );
+    };
+    (
+        @path $attrs:tt $vis:tt $merged:ident; [$($done:tt)*]; $nested:ident [$($path:tt)*]
+        $last:tt; :: $($rest:tt)*
+    ) => {
+        $crate::compose_concrete!(
+            @path $attrs $vis $merged; [$($done)*]; $nested [$($path)* ::] $last; $($rest)*
+        );
+    };
+    (
+        @path $attrs:tt $vis:tt $merged:ident; [$($done:tt)*]; $nested:ident [$($path:tt)+]
+        $last:ident; + $($rest:tt)+
+    ) => {
+        $crate::compose_concrete!(
+            @next $attrs $vis $merged; [$($done)* ($last [$($path)+] $nested)]; $($rest)+
+        );
+    };
+    (
+        @path $attrs:tt $vis:tt $merged:ident; [$($done:tt)*]; $nested:ident [$($path:tt)+]
+        $last:ident;
+    ) => {
+        $crate::gen_merged_enum!(
+            @emit $attrs $vis $merged; $($done)* ($last [$($path)+] $nested)
+        );
+    };
+}
+
+/// A macro asserting at compile time that two or more enums deriving `Concrete` or
+/// `ConcreteConfig` map the same variant names to the same concrete types, in the same order.
+///
+/// A kind enum and its config enum are kept in sync by hand; adding a variant to one but not the
+/// other, or pointing them at different concrete types, otherwise goes unnoticed until a config
+/// fails to convert at runtime. The check compares the `mapping_fingerprint` of each enum, which
+/// is generated at every level including `#[concrete(minimal)]`, so concrete type paths must be
+/// written identically. Generic config enums are named with their type arguments.
+///
+/// # Examples
+///
+/// ```rust
+/// use concrete_type_rules::assert_same_mapping;
+/// # use concrete_type::{Concrete, ConcreteConfig};
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # #[derive(Concrete)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(ConcreteConfig)]
+/// # enum ExchangeConfig {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance(String),
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+///
+/// assert_same_mapping!(Exchange, ExchangeConfig);
+/// # fn main() {}
+/// ```
+///
+/// A variant missing from one of them fails to compile:
+///
+/// ```compile_fail
+/// use concrete_type_rules::assert_same_mapping;
+/// # use concrete_type::{Concrete, ConcreteConfig};
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # #[derive(Concrete)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(ConcreteConfig)]
+/// # enum ExchangeConfig {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance(String),
+/// # }
+///
+/// assert_same_mapping!(Exchange, ExchangeConfig);
+///
+/// // error[E0080]: evaluation panicked: `Exchange` and `ExchangeConfig` map different variants
+/// //   or concrete types
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! assert_same_mapping {
+    ($first:ty $(, $rest:ty)+ $(,)?) => {
+        $(
+            const _: () = ::core::assert!(
+                <$first>::mapping_fingerprint() == <$rest>::mapping_fingerprint(),
+                ::core::concat!(
+                    "`",
+                    ::core::stringify!($first),
+                    "` and `",
+                    ::core::stringify!($rest),
+                    "` map different variants or concrete types",
+                ),
+            );
+        )+
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    //! Const helpers used by the macros of this crate. Not part of the public API.
+
+    pub use concrete_type::Concrete;
+
+    /// Returns the total length of `parts`.
+    pub const fn concat_len(parts: &[&str]) -> usize {
+        let mut len = 0;
+        let mut i = 0;
+        while i < parts.len() {
+            len += parts[i].len();
+            i += 1;
+        }
+        len
+    }
+
+    /// Concatenates `parts` into an array of exactly `N` bytes.
+    pub const fn concat_bytes<const N: usize>(parts: &[&str]) -> [u8; N] {
+        let mut bytes = [0; N];
+        let mut offset = 0;
+        let mut i = 0;
+        while i < parts.len() {
+            let part = parts[i].as_bytes();
+            let mut j = 0;
+            while j < part.len() {
+                bytes[offset] = part[j];
+                offset += 1;
+                j += 1;
             }
+            i += 1;
         }
-    };
+        assert!(
+            offset == N,
+            "concatenated length doesn't match the array length"
+        );
+        bytes
+    }
+
+    /// Converts bytes concatenated from string slices back into a string slice.
+    pub const fn bytes_to_str(bytes: &[u8]) -> &str {
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(_) => panic!("concatenated string slices are always valid UTF-8"),
+        }
+    }
 }
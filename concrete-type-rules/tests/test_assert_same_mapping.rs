@@ -0,0 +1,80 @@
+use concrete_type::{Concrete, ConcreteConfig};
+use concrete_type_rules::assert_same_mapping;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(String),
+    #[concrete = "exchanges::Okx"]
+    Okx(u32),
+}
+
+#[derive(ConcreteConfig)]
+enum TimedExchangeConfig<C> {
+    #[concrete = "exchanges::Binance"]
+    Binance(C),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(minimal)]
+enum MinimalExchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(minimal)]
+enum MinimalExchangeConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(String),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+// Compiling at all is the assertion
+assert_same_mapping!(Exchange, ExchangeConfig);
+assert_same_mapping!(Exchange, ExchangeConfig, TimedExchangeConfig<u64>,);
+// Minimal enums keep the fingerprint
+assert_same_mapping!(MinimalExchange, MinimalExchangeConfig, Exchange);
+
+#[test]
+fn test_same_mapping() {
+    let config = ExchangeConfig::Okx(1);
+    let from_config = exchange_config!(config; (E, _config) => { type_name::<E>() });
+    let from_kind = exchange!(Exchange::Okx; E => { type_name::<E>() });
+    assert_eq!(from_config, from_kind);
+
+    let config = TimedExchangeConfig::Binance(5_u64);
+    let name = timed_exchange_config!(config; (E, _config) => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Binance>());
+    let _ = ExchangeConfig::Binance(String::new());
+    let _ = TimedExchangeConfig::<u64>::Okx;
+    let _ = Exchange::Binance;
+}
+
+#[test]
+fn test_same_mapping_minimal() {
+    let config = MinimalExchangeConfig::Binance(String::new());
+    let from_config = minimal_exchange_config!(config; (E, _config) => { type_name::<E>() });
+    let from_kind = minimal_exchange!(MinimalExchange::Binance; E => { type_name::<E>() });
+    assert_eq!(from_config, from_kind);
+    let _ = (MinimalExchange::Okx, MinimalExchangeConfig::Okx);
+}
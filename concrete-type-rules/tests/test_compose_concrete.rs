@@ -0,0 +1,107 @@
+use concrete_type_rules::compose_concrete;
+use std::any::type_name;
+
+// Dispatch macros name their enum unqualified, as a binary importing the crates' enums would
+use base::Exchange;
+use dex::venues::DexExchange;
+
+trait ExchangeApi {
+    fn venue(&self) -> &'static str;
+}
+
+// Stands in for the base crate, defining the trait and its own kind enum
+mod base {
+    use super::ExchangeApi;
+    use concrete_type::Concrete;
+
+    #[derive(Default)]
+    pub struct Binance;
+    #[derive(Default)]
+    pub struct Okx;
+
+    impl ExchangeApi for Binance {
+        fn venue(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        fn venue(&self) -> &'static str {
+            "okx"
+        }
+    }
+
+    #[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+    pub enum Exchange {
+        #[concrete = "crate::base::Binance"]
+        Binance,
+        #[concrete = "crate::base::Okx"]
+        Okx,
+    }
+}
+
+// Stands in for a sibling crate contributing more backends
+mod dex {
+    pub mod venues {
+        use crate::ExchangeApi;
+        use concrete_type::Concrete;
+
+        #[derive(Default)]
+        pub struct Uniswap;
+
+        impl ExchangeApi for Uniswap {
+            fn venue(&self) -> &'static str {
+                "uniswap"
+            }
+        }
+
+        #[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+        pub enum DexExchange {
+            #[concrete = "crate::dex::venues::Uniswap"]
+            Uniswap,
+        }
+    }
+}
+
+// Enums of the calling crate are named from `crate::`, and dispatched through their macros in
+// scope. Enums of other crates are covered by the tests of `concrete-type-tests`
+compose_concrete!(
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub AnyExchange = crate::base::Exchange + crate::dex::venues::DexExchange
+);
+
+fn connect(exchange: AnyExchange) -> Box<dyn ExchangeApi> {
+    any_exchange!(exchange; E => { Box::new(E::default()) as Box<dyn ExchangeApi> })
+}
+
+#[test]
+fn test_composed_enum_dispatches_to_every_crate() {
+    let venues: Vec<_> = [
+        AnyExchange::from(base::Exchange::Binance),
+        AnyExchange::from(base::Exchange::Okx),
+        AnyExchange::from(dex::venues::DexExchange::Uniswap),
+    ]
+    .into_iter()
+    .map(|exchange| connect(exchange).venue())
+    .collect();
+    assert_eq!(venues, ["binance", "okx", "uniswap"]);
+}
+
+#[test]
+fn test_composed_enum_conversions() {
+    let exchange = AnyExchange::from(dex::venues::DexExchange::Uniswap);
+    assert_eq!(
+        exchange,
+        AnyExchange::DexExchange(dex::venues::DexExchange::Uniswap)
+    );
+    assert_eq!(
+        dex::venues::DexExchange::try_from(exchange),
+        Ok(dex::venues::DexExchange::Uniswap)
+    );
+    assert_eq!(base::Exchange::try_from(exchange), Err(exchange));
+
+    let name = exchange!(Exchange::Okx; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<base::Okx>());
+    let name = dex_exchange!(DexExchange::Uniswap; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<dex::venues::Uniswap>());
+}
@@ -0,0 +1,86 @@
+use concrete_type::{Concrete, ConcreteConfig};
+use concrete_type_rules::gen_api_snapshot;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(api_snapshot, minimal)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(api_snapshot, set)]
+enum Venue {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(api_snapshot)]
+enum VenueConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(u32),
+}
+
+const EXCHANGE_API: &str = "\
+# Exchange
+impl Exchange
+impl Exchange: pub const VARIANT_COUNT: usize
+impl Exchange: pub const fn from_index(index: usize) -> ::core::option::Option<Self>
+impl Exchange: pub const fn mapping_fingerprint() -> u64
+impl Exchange: pub const fn variant_index(&self) -> usize
+macro exchange! ($($tokens: tt) *)
+macro exchange! ($enum_instance: expr; $type_param: ident => $code_block: block where | $guard_param: ident | $guard: expr)
+macro exchange! ($enum_instance: expr; $type_param: ident => $code_block: block)
+macro exchange! ($enum_instance: expr; $type_param: ident<$($type_arg: ty), +> => $code_block: block)
+macro exchange! ($enum_instance: expr; [$($variant: ident), + $(,)?]; $type_param: ident => $code_block: block else $else_block: block)
+macro exchange! (@arm_count)
+macro exchange! (@flatten $enum_instance: expr; $type_param: ident => $code_block: block; ($($outer_pattern: tt) *) [$($arms: tt) *] [$($next_macro: tt) +] ($($next_pattern: tt) *) $($rest: tt) *)
+macro exchange! (@flatten $enum_instance: expr; $type_param: ident => $code_block: block; ($($outer_pattern: tt) *) [$($arms: tt) *])
+macro exchange! (@for_each $type_param: ident => $code_block: block)
+macro exchange! (@impls $type_param: ident; { $($item: tt) * })
+macro exchange! (@max_combinations)
+macro exchange! (@named $enum_instance: expr; $type_param: ident; [$($name: literal), *] $($suffix: literal), * => $callback: path { $($args: tt) * })
+macro exchange! (@registry $vis: vis, [$($bound: tt) +], $constructor: ident, $registry: ident, $build: ident)
+macro exchange! (@variant $variant: ident; $($tokens: tt) *)
+macro exchange! (@variant Binance; $type_param: ident => $code_block: block)
+";
+
+#[test]
+fn test_api_snapshot() {
+    const API: &str = gen_api_snapshot!(Exchange);
+    assert_eq!(API, EXCHANGE_API);
+    exchange!(Exchange::Binance; E => { let _ = std::any::type_name::<E>(); });
+}
+
+#[test]
+fn test_api_snapshot_lists_generated_items() {
+    let api = gen_api_snapshot!(Venue, VenueConfig);
+    assert!(Venue::CONCRETE_API.lines().is_sorted());
+    assert!(api.starts_with("# Venue\n"));
+    for expected in [
+        "impl Venue: pub const fn concrete_type_name(&self) -> &'static str",
+        "impl Venue: pub fn wrap_err<E>(&self, error: E) -> VenueDispatchError<E>",
+        "struct VenueSet",
+        "impl Venue: pub const BINANCE_TYPE_NAME: &'static str",
+        "impl VenueDowncastError: pub fn into_value(self) -> ::std::boxed::Box<dyn ::core::any::Any>",
+        "macro venue! (@try $enum_instance: expr; $type_param: ident => $code_block: block)",
+        "# VenueConfig",
+        "macro venue_config! ($enum_instance: expr; ($type_param: ident, $config_param: pat) => $code_block: block)",
+    ] {
+        assert!(api.contains(expected), "missing `{}` in\n{}", expected, api);
+    }
+    assert!(!api.contains("CONCRETE_API"));
+
+    venue!(Venue::Okx; V => { let _ = std::any::type_name::<V>(); });
+    let config = VenueConfig::Binance(1);
+    venue_config!(config; (V, value) => { let _ = (std::any::type_name::<V>(), value); });
+    let _ = Venue::Binance;
+}
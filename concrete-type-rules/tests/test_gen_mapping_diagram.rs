@@ -0,0 +1,54 @@
+use concrete_type::{Concrete, ConcreteConfig};
+use concrete_type_rules::gen_mapping_diagram;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+mod strategies {
+    pub struct Grid;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum StrategyConfig {
+    #[concrete = "crate::strategies::Grid"]
+    Grid(u32),
+}
+
+const ARCHITECTURE: &str = gen_mapping_diagram!(Exchange, StrategyConfig);
+
+#[test]
+fn test_gen_mapping_diagram() {
+    assert_eq!(
+        ARCHITECTURE,
+        "graph LR
+    enum_Exchange[\"Exchange\"]
+    enum_Exchange --> variant_Exchange_Binance[\"Binance\"]
+    variant_Exchange_Binance --> type_crate__exchanges__Binance[\"crate::exchanges::Binance\"]
+    enum_Exchange --> variant_Exchange_Okx[\"Okx\"]
+    variant_Exchange_Okx --> type_crate__exchanges__Okx[\"crate::exchanges::Okx\"]
+    enum_StrategyConfig[\"StrategyConfig\"]
+    enum_StrategyConfig --> variant_StrategyConfig_Grid[\"Grid\"]
+    variant_StrategyConfig_Grid --> type_crate__strategies__Grid[\"crate::strategies::Grid\"]
+"
+    );
+
+    // Usable outside of const contexts too
+    assert!(gen_mapping_diagram!(Exchange).starts_with("graph LR\n    enum_Exchange"));
+
+    for exchange in [Exchange::Binance, Exchange::Okx] {
+        assert!(exchange!(exchange; E => { std::any::type_name::<E>() }).contains("exchanges"));
+    }
+    let name =
+        strategy_config!(StrategyConfig::Grid(1); (S, _cfg) => { std::any::type_name::<S>() });
+    assert!(name.ends_with("Grid"));
+}
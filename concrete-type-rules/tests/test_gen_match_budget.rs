@@ -0,0 +1,58 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_match_concretes_macro;
+use std::any::type_name;
+
+// Three variants but two concrete types, so the block is expanded 2 × 2 times (3 × 3 with the
+// `expand-debug` feature, which stops merging the arms of shared types)
+#[derive(Concrete, Clone, Copy)]
+#[concrete(max_combinations = 9)]
+enum BuyVenue {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Binance"]
+    BinanceUs,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum SellVenue {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Binance"]
+    BinanceUs,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+mod test_types {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+gen_match_concretes_macro!(BuyVenue, SellVenue);
+gen_match_concretes_macro!(BuyVenue = Okx, SellVenue);
+
+#[test]
+fn test_combinations_within_budget() {
+    const COMBINATIONS: usize = buy_venue!(@arm_count) * sell_venue!(@arm_count);
+    assert!([4, 9].contains(&COMBINATIONS));
+    assert_eq!(buy_venue!(@max_combinations), 9);
+    assert_eq!(sell_venue!(@max_combinations), usize::MAX);
+
+    let pairs = [
+        (BuyVenue::BinanceUs, SellVenue::Okx),
+        (BuyVenue::Okx, SellVenue::Binance),
+        (BuyVenue::Binance, SellVenue::Okx),
+        (BuyVenue::Okx, SellVenue::BinanceUs),
+    ];
+    for (buy, sell) in pairs {
+        let names = match_buy_venue_sell_venue!(buy, sell; B, S => {
+            (type_name::<B>(), type_name::<S>())
+        });
+        assert_ne!(names.0, names.1);
+    }
+
+    let name = match_buy_venue_okx_sell_venue!(SellVenue::Okx; B, S => { type_name::<(B, S)>() });
+    assert_eq!(name, type_name::<(test_types::Okx, test_types::Okx)>());
+}
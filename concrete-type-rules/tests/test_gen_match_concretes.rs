@@ -0,0 +1,110 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_match_concretes;
+use std::any::type_name;
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Strategy {
+    #[concrete = "test_types::StrategyA"]
+    StrategyA,
+    #[concrete = "test_types::StrategyB"]
+    StrategyB,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum TimeFrame {
+    #[concrete = "test_types::Minute"]
+    Minute,
+    #[concrete = "test_types::Hour"]
+    Hour,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Market {
+    #[concrete = "test_types::Spot"]
+    Spot,
+    #[concrete = "test_types::Futures"]
+    Futures,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum RiskLevel {
+    #[concrete = "test_types::Low"]
+    Low,
+    #[concrete = "test_types::High"]
+    High,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Venue {
+    #[concrete = "test_types::Lit"]
+    Lit,
+    #[concrete = "test_types::Dark"]
+    Dark,
+}
+
+mod test_types {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct StrategyA;
+    pub struct StrategyB;
+    pub struct Minute;
+    pub struct Hour;
+    pub struct Spot;
+    pub struct Futures;
+    pub struct Low;
+    pub struct High;
+    pub struct Lit;
+    pub struct Dark;
+}
+
+// Beyond the five enums `gen_match_concretes_macro!` used to be limited to
+gen_match_concretes!(Exchange, Strategy, TimeFrame, Market, RiskLevel, Venue);
+gen_match_concretes!(Exchange, Strategy, TimeFrame, Market, RiskLevel, Venue => match_all);
+
+#[test]
+fn test_six_enum_match() {
+    let result = match_exchange_strategy_time_frame_market_risk_level_venue!(
+        Exchange::Okx, Strategy::StrategyA, TimeFrame::Hour, Market::Spot, RiskLevel::High, Venue::Dark;
+        E, S, T, M, R, V => { type_name::<(E, S, T, M, R, V)>() }
+    );
+
+    assert_eq!(
+        result,
+        type_name::<(
+            test_types::Okx,
+            test_types::StrategyA,
+            test_types::Hour,
+            test_types::Spot,
+            test_types::High,
+            test_types::Dark,
+        )>()
+    );
+}
+
+#[test]
+fn test_named_six_enum_match() {
+    let result = match_all!(
+        Exchange::Binance, Strategy::StrategyB, TimeFrame::Minute, Market::Futures, RiskLevel::Low, Venue::Lit;
+        E, S, T, M, R, V => { type_name::<(E, S, T, M, R, V)>() }
+    );
+
+    assert_eq!(
+        result,
+        type_name::<(
+            test_types::Binance,
+            test_types::StrategyB,
+            test_types::Minute,
+            test_types::Futures,
+            test_types::Low,
+            test_types::Lit,
+        )>()
+    );
+}
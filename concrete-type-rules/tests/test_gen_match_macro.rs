@@ -1,3 +1,6 @@
+// Fixtures for the 4 and 5 enum matchers are kept alongside their disabled tests
+#![allow(dead_code)]
+
 use concrete_type::Concrete;
 use concrete_type_rules::gen_match_concretes_macro;
 use std::marker::PhantomData;
@@ -254,6 +257,8 @@ impl TripleSystem<test_types::Okx, test_types::StrategyB, test_types::Hour> {
 // Generate the macro combinations for testing
 gen_match_concretes_macro!(Exchange, Strategy);
 gen_match_concretes_macro!(Exchange, Strategy, TimeFrame);
+gen_match_concretes_macro!(Exchange, Strategy => dispatch_pair);
+gen_match_concretes_macro!(Exchange, Strategy, TimeFrame => dispatch_triple);
 // gen_match_concretes_macro!(Exchange, Strategy, TimeFrame, Market);
 // gen_match_concretes_macro!(Exchange, Strategy, TimeFrame, Market, RiskLevel);
 
@@ -300,6 +305,19 @@ fn test_three_enum_match() {
     assert_eq!(result, "binance_strategy_a_minute");
 }
 
+#[test]
+fn test_named_match() {
+    let result = dispatch_pair!(Exchange::Okx, Strategy::StrategyA; E, S => {
+        DualSystem::<E, S>::new().name()
+    });
+    assert_eq!(result, "okx_strategy_a");
+
+    let result = dispatch_triple!(Exchange::Okx, Strategy::StrategyB, TimeFrame::Hour; E, S, T => {
+        TripleSystem::<E, S, T>::new().name()
+    });
+    assert_eq!(result, "okx_strategy_b_hour");
+}
+
 // #[test]
 // fn test_four_enum_match() {
 //     let exchange = Exchange::Binance;
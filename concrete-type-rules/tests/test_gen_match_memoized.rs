@@ -0,0 +1,82 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_match_concretes_macro;
+use std::any::type_name;
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Strategy {
+    #[concrete = "test_types::StrategyA"]
+    StrategyA,
+    #[concrete = "test_types::StrategyB"]
+    StrategyB,
+}
+
+mod test_types {
+    pub trait Fee {
+        const BASIS_POINTS: u64;
+    }
+
+    pub struct Binance;
+    pub struct Okx;
+    pub struct StrategyA;
+    pub struct StrategyB;
+
+    impl Fee for Binance {
+        const BASIS_POINTS: u64 = 10;
+    }
+
+    impl Fee for Okx {
+        const BASIS_POINTS: u64 = 8;
+    }
+}
+
+use test_types::Fee;
+
+gen_match_concretes_macro!(Exchange, Strategy);
+gen_match_concretes_macro!(Exchange = Okx, Strategy => match_okx_strategy);
+
+fn describe(exchange: Exchange, strategy: Strategy, notional: u64) -> (String, u64) {
+    match_exchange_strategy!(@memoized exchange, strategy; E: Fee, S => |notional: u64| -> (String, u64) {
+        (type_name::<(E, S)>().to_string(), notional * E::BASIS_POINTS / 10_000)
+    })
+}
+
+#[test]
+fn test_memoized_match() {
+    // Repeated calls hit the cached entry for the combination
+    for _ in 0..2 {
+        assert_eq!(
+            describe(Exchange::Binance, Strategy::StrategyB, 20_000),
+            (
+                type_name::<(test_types::Binance, test_types::StrategyB)>().to_string(),
+                20
+            )
+        );
+        assert_eq!(
+            describe(Exchange::Okx, Strategy::StrategyA, 20_000),
+            (
+                type_name::<(test_types::Okx, test_types::StrategyA)>().to_string(),
+                16
+            )
+        );
+    }
+}
+
+#[test]
+fn test_memoized_match_with_fixed_enum() {
+    let strategy = Strategy::StrategyA;
+    let name = match_okx_strategy!(@memoized strategy; E, S => || -> &'static str {
+        type_name::<(E, S)>()
+    });
+    assert_eq!(
+        name,
+        type_name::<(test_types::Okx, test_types::StrategyA)>()
+    );
+}
@@ -0,0 +1,107 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_match_concretes_macro;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+mod strategies {
+    pub struct StrategyA;
+    pub struct StrategyB;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+    // Shares its concrete type with `Okx`, but is named as itself
+    #[concrete = "exchanges::Okx"]
+    OkxTestnet,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Strategy {
+    #[concrete = "strategies::StrategyA"]
+    StrategyA,
+    #[concrete = "strategies::StrategyB"]
+    StrategyB,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Venue {
+    #[concrete = "exchanges::Binance"]
+    Direct,
+    #[concrete(nested)]
+    Routed(Exchange),
+}
+
+gen_match_concretes_macro!(Exchange, Strategy);
+gen_match_concretes_macro!(Exchange = Binance, Strategy);
+gen_match_concretes_macro!(Exchange, Strategy = StrategyB);
+gen_match_concretes_macro!(Strategy, Venue);
+
+#[test]
+fn test_named_combinations() {
+    let name = match_exchange_strategy!(@named Exchange::Binance, Strategy::StrategyA; E, S => {
+        let _ = type_name::<(E, S)>();
+        COMBO_NAME
+    });
+    assert_eq!(name, "binance+strategy_a");
+
+    let (name, types) = match_exchange_strategy!(@named Exchange::OkxTestnet, Strategy::StrategyB; E, S => {
+        (COMBO_NAME, type_name::<(E, S)>())
+    });
+    assert_eq!(name, "okx_testnet+strategy_b");
+    assert_eq!(
+        types,
+        type_name::<(exchanges::Okx, strategies::StrategyB)>()
+    );
+
+    // The name is a constant, usable in const contexts
+    let key = match_exchange_strategy!(@named Exchange::Okx, Strategy::StrategyA; E, S => {
+        const KEY: &str = COMBO_NAME;
+        let _ = type_name::<(E, S)>();
+        KEY
+    });
+    assert_eq!(key, "okx+strategy_a");
+
+    // The default form is unaffected
+    let types = match_exchange_strategy!(Exchange::Binance, Strategy::StrategyB; E, S => {
+        type_name::<(E, S)>()
+    });
+    assert_eq!(
+        types,
+        type_name::<(exchanges::Binance, strategies::StrategyB)>()
+    );
+}
+
+#[test]
+fn test_named_fixed_and_nested() {
+    let name = match_exchange_binance_strategy!(@named Strategy::StrategyB; E, S => {
+        let _ = type_name::<(E, S)>();
+        COMBO_NAME
+    });
+    assert_eq!(name, "binance+strategy_b");
+
+    let name = match_exchange_strategy_strategy_b!(@named Exchange::Okx; E, S => {
+        let _ = type_name::<(E, S)>();
+        COMBO_NAME
+    });
+    assert_eq!(name, "okx+strategy_b");
+
+    let name = match_strategy_venue!(@named Strategy::StrategyA, Venue::Routed(Exchange::OkxTestnet); S, V => {
+        let _ = type_name::<(S, V)>();
+        COMBO_NAME
+    });
+    assert_eq!(name, "strategy_a+routed.okx_testnet");
+
+    let name = match_strategy_venue!(@named Strategy::StrategyA, Venue::Direct; S, V => {
+        let _ = type_name::<(S, V)>();
+        COMBO_NAME
+    });
+    assert_eq!(name, "strategy_a+direct");
+}
@@ -0,0 +1,83 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_match_concretes_macro;
+use std::any::type_name;
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Strategy {
+    #[concrete = "test_types::StrategyA"]
+    StrategyA,
+    #[concrete = "test_types::StrategyB"]
+    StrategyB,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum TimeFrame {
+    #[concrete = "test_types::Minute"]
+    Minute,
+    #[concrete = "test_types::Hour"]
+    Hour,
+}
+
+mod test_types {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct StrategyA;
+    pub struct StrategyB;
+    pub struct Minute;
+    pub struct Hour;
+}
+
+gen_match_concretes_macro!(Exchange = Binance, Strategy);
+gen_match_concretes_macro!(Exchange, Strategy, TimeFrame = Hour => match_hourly);
+
+#[test]
+fn test_fixed_first_enum() {
+    for (strategy, expected) in [
+        (Strategy::StrategyA, type_name::<test_types::StrategyA>()),
+        (Strategy::StrategyB, type_name::<test_types::StrategyB>()),
+    ] {
+        let result = match_exchange_binance_strategy!(strategy; E, S => {
+            (type_name::<E>(), type_name::<S>())
+        });
+        assert_eq!(result, (type_name::<test_types::Binance>(), expected));
+    }
+}
+
+#[test]
+fn test_fixed_last_enum() {
+    let result = match_hourly!(Exchange::Okx, Strategy::StrategyB; E, S, T => {
+        type_name::<(E, S, T)>()
+    });
+    assert_eq!(
+        result,
+        type_name::<(test_types::Okx, test_types::StrategyB, test_types::Hour)>()
+    );
+
+    // The remaining variants are still dispatched at runtime
+    let exchange = Exchange::Binance;
+    let result = match_hourly!(exchange, Strategy::StrategyA; E, S, T => {
+        type_name::<(E, S, T)>()
+    });
+    assert_eq!(
+        result,
+        type_name::<(test_types::Binance, test_types::StrategyA, test_types::Hour)>()
+    );
+}
+
+#[test]
+fn test_fixed_enum_macro_still_dispatches() {
+    for (time_frame, expected) in [
+        (TimeFrame::Minute, type_name::<test_types::Minute>()),
+        (TimeFrame::Hour, type_name::<test_types::Hour>()),
+    ] {
+        assert_eq!(time_frame!(time_frame; T => { type_name::<T>() }), expected);
+    }
+}
@@ -0,0 +1,57 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_match_concretes_macro;
+use std::any::type_name;
+
+// Regional venues share the implementation of their parent exchange
+#[derive(Concrete, Clone, Copy)]
+enum BuyVenue {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Binance"]
+    BinanceUs,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum SellVenue {
+    #[concrete = "test_types::Binance"]
+    Binance,
+    #[concrete = "test_types::Binance"]
+    BinanceUs,
+    #[concrete = "test_types::Okx"]
+    Okx,
+}
+
+mod test_types {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+gen_match_concretes_macro!(BuyVenue, SellVenue);
+
+#[test]
+fn test_shared_concrete_types() {
+    let binance = type_name::<test_types::Binance>();
+    let okx = type_name::<test_types::Okx>();
+
+    for (venue, expected) in [
+        (BuyVenue::Binance, binance),
+        (BuyVenue::BinanceUs, binance),
+        (BuyVenue::Okx, okx),
+    ] {
+        assert_eq!(buy_venue!(venue; V => { type_name::<V>() }), expected);
+    }
+
+    for (buy, sell, expected) in [
+        (BuyVenue::BinanceUs, SellVenue::Okx, (binance, okx)),
+        (BuyVenue::Okx, SellVenue::BinanceUs, (okx, binance)),
+        (BuyVenue::Binance, SellVenue::Binance, (binance, binance)),
+        (BuyVenue::Okx, SellVenue::Okx, (okx, okx)),
+    ] {
+        let result = match_buy_venue_sell_venue!(buy, sell; B, S => {
+            (type_name::<B>(), type_name::<S>())
+        });
+        assert_eq!(result, expected);
+    }
+}
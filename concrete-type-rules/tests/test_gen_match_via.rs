@@ -0,0 +1,65 @@
+use concrete_type_rules::gen_match_concretes_macro;
+use std::any::type_name;
+
+mod exchanges {
+    use concrete_type::Concrete;
+
+    pub struct Binance;
+    pub struct Okx;
+
+    #[derive(Concrete, Clone, Copy)]
+    #[concrete(macros_mod = "dispatch")]
+    pub enum Exchange {
+        #[concrete = "crate::exchanges::Binance"]
+        Binance,
+        #[concrete = "crate::exchanges::Okx"]
+        Okx,
+    }
+}
+
+mod strategies {
+    use concrete_type::Concrete;
+
+    pub struct StrategyA;
+    pub struct StrategyB;
+
+    #[derive(Concrete, Clone, Copy)]
+    pub enum Strategy {
+        #[concrete = "crate::strategies::StrategyA"]
+        StrategyA,
+        #[concrete = "crate::strategies::StrategyB"]
+        StrategyB,
+    }
+}
+
+use exchanges::Exchange;
+use strategies::Strategy;
+
+// `exchange!` only lives in `exchanges::dispatch`, `strategy!` is exported at the crate root
+gen_match_concretes_macro!(Exchange via crate::exchanges::dispatch::exchange, Strategy);
+gen_match_concretes_macro!(Strategy, Exchange via exchanges::dispatch::exchange => strategy_exchange);
+
+#[test]
+fn test_match_via_macro_path() {
+    let result = match_exchange_strategy!(Exchange::Okx, Strategy::StrategyB; E, S => {
+        (type_name::<E>(), type_name::<S>())
+    });
+    assert_eq!(
+        result,
+        (
+            type_name::<exchanges::Okx>(),
+            type_name::<strategies::StrategyB>()
+        )
+    );
+
+    let result = strategy_exchange!(Strategy::StrategyA, Exchange::Binance; S, E => {
+        (type_name::<S>(), type_name::<E>())
+    });
+    assert_eq!(
+        result,
+        (
+            type_name::<strategies::StrategyA>(),
+            type_name::<exchanges::Binance>()
+        )
+    );
+}
@@ -0,0 +1,61 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_merged_enum;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Uniswap;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+// Stands in for an enum defined in another crate
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum DexExchange {
+    #[concrete = "crate::exchanges::Uniswap"]
+    Uniswap,
+}
+
+gen_merged_enum!(Exchange, DexExchange => #[derive(Clone, Copy, Debug, PartialEq)] AnyExchange);
+
+#[test]
+fn test_merged_enum_dispatches_to_every_backend() {
+    let names: Vec<_> = [
+        AnyExchange::from(Exchange::Binance),
+        AnyExchange::from(Exchange::Okx),
+        AnyExchange::from(DexExchange::Uniswap),
+    ]
+    .into_iter()
+    .map(|exchange| any_exchange!(exchange; E => { type_name::<E>() }))
+    .collect();
+    assert_eq!(
+        names,
+        [
+            type_name::<exchanges::Binance>(),
+            type_name::<exchanges::Okx>(),
+            type_name::<exchanges::Uniswap>(),
+        ]
+    );
+}
+
+#[test]
+fn test_merged_enum_conversions() {
+    let exchange = AnyExchange::from(DexExchange::Uniswap);
+    assert_eq!(exchange, AnyExchange::DexExchange(DexExchange::Uniswap));
+    assert_eq!(DexExchange::try_from(exchange), Ok(DexExchange::Uniswap));
+    assert_eq!(Exchange::try_from(exchange), Err(exchange));
+
+    let name = exchange!(Exchange::try_from(AnyExchange::Exchange(Exchange::Okx)).unwrap(); E => {
+        type_name::<E>()
+    });
+    assert_eq!(name, type_name::<exchanges::Okx>());
+    let name = dex_exchange!(DexExchange::Uniswap; E => { type_name::<E>() });
+    assert!(name.ends_with("Uniswap"));
+}
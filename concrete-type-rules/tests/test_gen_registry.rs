@@ -0,0 +1,132 @@
+// `dex` stands in for a backend feature that is declared but disabled
+#![allow(unexpected_cfgs)]
+
+use concrete_type::Concrete;
+use concrete_type_rules::gen_registry;
+
+trait ExchangeApi {
+    fn name(&self) -> &'static str;
+}
+
+mod exchanges {
+    use super::ExchangeApi;
+
+    pub struct Binance;
+    pub struct Okx {
+        pub spot: bool,
+    }
+    #[cfg(feature = "dex")]
+    pub struct Uniswap;
+
+    impl Binance {
+        pub fn new() -> Self {
+            Binance
+        }
+    }
+
+    impl Okx {
+        pub fn new() -> Self {
+            Okx { spot: true }
+        }
+    }
+
+    #[cfg(feature = "dex")]
+    impl Uniswap {
+        pub fn new() -> Self {
+            Uniswap
+        }
+    }
+
+    impl ExchangeApi for Binance {
+        fn name(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        fn name(&self) -> &'static str {
+            if self.spot { "okx spot" } else { "okx" }
+        }
+    }
+
+    #[cfg(feature = "dex")]
+    impl ExchangeApi for Uniswap {
+        fn name(&self) -> &'static str {
+            "uniswap"
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete(ty = "crate::exchanges::Uniswap", feature = "dex")]
+    Uniswap,
+}
+
+gen_registry!(Exchange, trait = ExchangeApi + Send, constructor = new);
+
+#[test]
+fn test_registry_lists_enabled_variants() {
+    let names: Vec<_> = REGISTRY
+        .iter()
+        .map(|(kind, construct)| (*kind, construct().name()))
+        .collect();
+    // The disabled `dex` variant is left out
+    assert_eq!(
+        names,
+        [(Exchange::Binance, "binance"), (Exchange::Okx, "okx spot")]
+    );
+}
+
+#[test]
+fn test_build() {
+    assert_eq!(build(Exchange::Binance).name(), "binance");
+    assert_eq!(build(Exchange::Okx).name(), "okx spot");
+}
+
+#[test]
+#[should_panic(expected = "`Exchange::Uniswap` requires the `dex` feature")]
+fn test_build_disabled_variant() {
+    build(Exchange::Uniswap);
+}
+
+mod facade {
+    use concrete_type::Concrete;
+
+    #[derive(Concrete, Clone, Copy)]
+    #[concrete(macros_mod = "dispatch")]
+    pub enum Venue {
+        #[concrete = "crate::exchanges::Okx"]
+        Okx,
+    }
+}
+
+mod venues {
+    use crate::ExchangeApi;
+    use crate::facade::Venue;
+    use concrete_type_rules::gen_registry;
+
+    gen_registry!(pub(crate) Venue via crate::facade::dispatch::venue, trait = ExchangeApi, constructor = new);
+}
+
+#[test]
+fn test_registry_via_path() {
+    assert_eq!(venues::REGISTRY.len(), 1);
+    assert_eq!(venues::build(facade::Venue::Okx).name(), "okx spot");
+}
+
+// A second registry next to the one of `Exchange`, told apart by its prefix
+use facade::Venue;
+
+gen_registry!(Venue via crate::facade::dispatch::venue, trait = ExchangeApi, constructor = new, prefix = venue);
+
+#[test]
+fn test_registry_prefix() {
+    assert_eq!(VENUE_REGISTRY.len(), 1);
+    assert_eq!(venue_build(Venue::Okx).name(), "okx spot");
+    assert_eq!(REGISTRY.len(), 2);
+}
@@ -0,0 +1,159 @@
+use concrete_type::Concrete;
+use concrete_type_rules::gen_visitor2;
+
+trait ExchangeApi {
+    const NAME: &'static str;
+}
+
+trait StrategyApi {
+    const NAME: &'static str;
+}
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+
+    impl super::ExchangeApi for Binance {
+        const NAME: &'static str = "binance";
+    }
+
+    impl super::ExchangeApi for Okx {
+        const NAME: &'static str = "okx";
+    }
+}
+
+mod strategies {
+    pub struct Grid;
+    pub struct Momentum;
+
+    impl super::StrategyApi for Grid {
+        const NAME: &'static str = "grid";
+    }
+
+    impl super::StrategyApi for Momentum {
+        const NAME: &'static str = "momentum";
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Strategy {
+    #[concrete = "crate::strategies::Grid"]
+    Grid,
+    #[concrete = "crate::strategies::Momentum"]
+    Momentum,
+}
+
+gen_visitor2!(Exchange: ExchangeApi, Strategy: StrategyApi);
+
+/// Records every pair it visits, as a callback stored in a struct would
+#[derive(Default)]
+struct Recorder {
+    visited: Vec<String>,
+}
+
+impl Visitor2 for Recorder {
+    type Output = usize;
+
+    fn visit<A: ExchangeApi, B: StrategyApi>(&mut self) -> usize {
+        self.visited.push(format!("{} on {}", B::NAME, A::NAME));
+        self.visited.len()
+    }
+}
+
+struct Trader {
+    exchange: Exchange,
+    strategy: Strategy,
+    on_start: Recorder,
+}
+
+#[test]
+fn test_accept_dispatches_on_both_enums() {
+    let mut trader = Trader {
+        exchange: Exchange::Okx,
+        strategy: Strategy::Grid,
+        on_start: Recorder::default(),
+    };
+    assert_eq!(
+        accept(&trader.exchange, &trader.strategy, &mut trader.on_start),
+        1
+    );
+
+    trader.exchange = Exchange::Binance;
+    trader.strategy = Strategy::Momentum;
+    assert_eq!(
+        accept(&trader.exchange, &trader.strategy, &mut trader.on_start),
+        2
+    );
+    assert_eq!(
+        trader.on_start.visited,
+        ["grid on okx", "momentum on binance"]
+    );
+}
+
+mod unbounded {
+    use crate::{Exchange, Strategy};
+    use concrete_type_rules::gen_visitor2;
+    use std::any::type_name;
+
+    gen_visitor2!(pub(crate) Exchange, Strategy);
+
+    pub(crate) struct TypeNames;
+
+    impl Visitor2 for TypeNames {
+        type Output = (&'static str, &'static str);
+
+        fn visit<A, B>(&mut self) -> Self::Output {
+            (type_name::<A>(), type_name::<B>())
+        }
+    }
+}
+
+#[test]
+fn test_unbounded_visitor() {
+    let names = unbounded::accept(
+        &Exchange::Binance,
+        &Strategy::Momentum,
+        &mut unbounded::TypeNames,
+    );
+    assert_eq!(
+        names,
+        (
+            std::any::type_name::<exchanges::Binance>(),
+            std::any::type_name::<strategies::Momentum>()
+        )
+    );
+}
+
+// Visitors over different pairs share a module when named apart
+gen_visitor2!(
+    Strategy: StrategyApi,
+    Exchange: ExchangeApi,
+    visitor = StrategyExchangeVisitor,
+    accept = visit_strategy_exchange,
+);
+
+struct Pair;
+
+impl StrategyExchangeVisitor for Pair {
+    type Output = (&'static str, &'static str);
+
+    fn visit<A: StrategyApi, B: ExchangeApi>(&mut self) -> Self::Output {
+        (A::NAME, B::NAME)
+    }
+}
+
+#[test]
+fn test_named_visitor() {
+    assert_eq!(
+        visit_strategy_exchange(&Strategy::Grid, &Exchange::Binance, &mut Pair),
+        ("grid", "binance")
+    );
+}
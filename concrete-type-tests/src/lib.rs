@@ -0,0 +1,61 @@
+//! Tests of `concrete-type` that need a crate of their own, in `tests/`:
+//!
+//! - Cargo features of the crate deriving the enums, such as `test-backend`, declared here so
+//!   they aren't published as features of `concrete-type` itself
+//! - enums defined in another crate than the one dispatching on them, defined below
+
+use concrete_type::Concrete;
+
+/// Implemented by every backend of the enums below.
+pub trait ExchangeApi {
+    fn venue(&self) -> &'static str;
+}
+
+pub mod exchanges {
+    use crate::ExchangeApi;
+
+    #[derive(Default)]
+    pub struct Binance;
+    #[derive(Default)]
+    pub struct Okx;
+    #[derive(Default)]
+    pub struct Uniswap;
+
+    impl ExchangeApi for Binance {
+        fn venue(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        fn venue(&self) -> &'static str {
+            "okx"
+        }
+    }
+
+    impl ExchangeApi for Uniswap {
+        fn venue(&self) -> &'static str {
+            "uniswap"
+        }
+    }
+}
+
+/// Kind enum of a base crate, dispatched on by the crates depending on it.
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+pub enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+pub mod dex {
+    use concrete_type::Concrete;
+
+    /// Kind enum defined in a module, whose dispatch macro is still exported at the crate root.
+    #[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+    pub enum DexExchange {
+        #[concrete = "crate::exchanges::Uniswap"]
+        Uniswap,
+    }
+}
@@ -0,0 +1,39 @@
+// The enums come from another crate, as in a binary composing the kind enums of its dependencies.
+// Only the enums are imported, as dispatch macros name their enum unqualified: the composed macro
+// reaches the dispatch macros at the root of their crate
+use concrete_type_rules::compose_concrete;
+use concrete_type_tests::Exchange;
+use concrete_type_tests::dex::DexExchange;
+
+compose_concrete!(
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    AnyExchange = concrete_type_tests::Exchange + concrete_type_tests::dex::DexExchange
+);
+
+mod caller {
+    use super::{AnyExchange, DexExchange, Exchange};
+    use concrete_type_tests::ExchangeApi;
+
+    // Called from a module that imports no macro either
+    pub fn connect(exchange: AnyExchange) -> Box<dyn ExchangeApi> {
+        let _ = (Exchange::Okx, DexExchange::Uniswap);
+        any_exchange!(exchange; E => { Box::new(E::default()) as Box<dyn ExchangeApi> })
+    }
+}
+
+#[test]
+fn test_composed_enum_dispatches_to_other_crates() {
+    let venues: Vec<_> = [
+        AnyExchange::from(Exchange::Binance),
+        AnyExchange::from(Exchange::Okx),
+        AnyExchange::from(DexExchange::Uniswap),
+    ]
+    .into_iter()
+    .map(|exchange| caller::connect(exchange).venue())
+    .collect();
+    assert_eq!(venues, ["binance", "okx", "uniswap"]);
+    assert_eq!(
+        DexExchange::try_from(AnyExchange::DexExchange(DexExchange::Uniswap)),
+        Ok(DexExchange::Uniswap)
+    );
+}
@@ -0,0 +1,210 @@
+// `test-backend` stands in for a backend feature of the crate deriving the enums. It is disabled
+// unless the tests run with `--features test-backend` (or `--all-features`)
+
+use concrete_type::{Concrete, ConcreteConfig};
+use std::any::TypeId;
+
+mod exchanges {
+    pub trait Exchange {
+        type Config
+        where
+            Self: Sized;
+
+        fn new(config: Self::Config) -> Self
+        where
+            Self: Sized;
+
+        fn name(&self) -> &'static str;
+    }
+
+    #[cfg(feature = "test-backend")]
+    pub struct Binance;
+
+    #[cfg(feature = "test-backend")]
+    impl Binance {
+        pub fn new<C>(_config: C) -> Result<Self, String> {
+            Ok(Binance)
+        }
+    }
+
+    #[cfg(feature = "test-backend")]
+    impl Exchange for Binance {
+        type Config = ();
+
+        fn new(_config: ()) -> Self {
+            Binance
+        }
+
+        fn name(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    pub struct Okx;
+
+    impl Okx {
+        pub fn new<C>(_config: C) -> Result<Self, String> {
+            Ok(Okx)
+        }
+    }
+
+    impl Exchange for Okx {
+        type Config = ();
+
+        fn new(_config: ()) -> Self {
+            Okx
+        }
+
+        fn name(&self) -> &'static str {
+            "okx"
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(factory = "crate::exchanges::Exchange")]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(feature = "test-backend")]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(feature = "test-backend")]
+    Binance(String),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(Option<String>),
+}
+
+#[cfg(not(feature = "test-backend"))]
+#[test]
+fn test_disabled_variant_dispatches_to_stub() {
+    let error = exchange!(Exchange::Binance; E => { E::new(()).err() });
+    assert_eq!(
+        error.as_deref(),
+        Some("variant `Binance` requires the `test-backend` feature")
+    );
+
+    let error = exchange!(Exchange::Okx; E => { E::new(()).err() });
+    assert_eq!(error, None);
+
+    let error = exchange_config!(ExchangeConfig::Binance("key".to_string()); (E, cfg) => {
+        E::new(cfg).err()
+    });
+    assert_eq!(
+        error.as_deref(),
+        Some("variant `Binance` requires the `test-backend` feature")
+    );
+
+    let error = exchange_config!(ExchangeConfig::Okx(None); (E, cfg) => { E::new(cfg).err() });
+    assert_eq!(error, None);
+}
+
+#[cfg(feature = "test-backend")]
+#[test]
+fn test_enabled_variant_dispatches_to_concrete_type() {
+    let error = exchange!(Exchange::Binance; E => { E::new(()).err() });
+    assert_eq!(error, None);
+
+    let error = exchange_config!(ExchangeConfig::Binance("key".to_string()); (E, cfg) => {
+        E::new(cfg).err()
+    });
+    assert_eq!(error, None);
+
+    assert_eq!(
+        Exchange::Binance.concrete_type_id(),
+        TypeId::of::<exchanges::Binance>()
+    );
+    let exchange = Exchange::Binance.create(Box::new(())).ok().unwrap();
+    assert_eq!(exchange.name(), "binance");
+}
+
+#[test]
+fn test_disabled_variant_type_id() {
+    assert_eq!(
+        Exchange::Okx.concrete_type_id(),
+        TypeId::of::<exchanges::Okx>()
+    );
+    assert!(!Exchange::Binance.same_concrete_as(&Exchange::Okx));
+    assert!(!ExchangeConfig::Binance(String::new()).same_concrete_as(&ExchangeConfig::Okx(None)));
+}
+
+#[cfg(not(feature = "test-backend"))]
+#[test]
+fn test_disabled_variant_factory_error() {
+    let error = Exchange::Binance.create(Box::new(())).err().unwrap();
+    assert_eq!(error.variant(), "Binance");
+    assert_eq!(error.required_feature(), Some("test-backend"));
+    assert_eq!(
+        error.to_string(),
+        "variant `Binance` requires the `test-backend` feature"
+    );
+
+    let exchange = Exchange::Okx.create(Box::new(())).ok().unwrap();
+    assert_eq!(exchange.name(), "okx");
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Backend {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(cargo_feature = "test-backend")]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete = "crate::exchanges::Okx"]
+    Local,
+}
+
+#[test]
+fn test_enabled_variants() {
+    if cfg!(feature = "test-backend") {
+        assert_eq!(
+            Backend::enabled_variants(),
+            [Backend::Binance, Backend::Okx, Backend::Local]
+        );
+    } else {
+        assert_eq!(Backend::enabled_variants(), [Backend::Okx, Backend::Local]);
+    }
+    assert_eq!(Backend::Binance.required_feature(), Some("test-backend"));
+    assert_eq!(Backend::Local.required_feature(), None);
+
+    let error = backend!(Backend::Binance; B => { B::new(()).err() });
+    assert_eq!(error.is_some(), !cfg!(feature = "test-backend"));
+
+    assert_eq!(
+        ExchangeConfig::Binance(String::new()).required_feature(),
+        Some("test-backend")
+    );
+    assert_eq!(ExchangeConfig::Okx(None).required_feature(), None);
+}
+
+// Generated macros are resolved against the defining crate's features whatever their flavor
+#[derive(Concrete, Clone, Copy)]
+#[concrete(macros_mod = "venue_dispatch", relative_macro)]
+enum Venue {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(feature = "test-backend")]
+    Binance,
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(feature = "test-backend")]
+    BinanceFutures,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_feature_gated_macro_flavors() {
+    let enabled = cfg!(feature = "test-backend");
+    for venue in [Venue::Binance, Venue::BinanceFutures] {
+        let error = venue_dispatch::venue!(venue; V => { V::new(()).err() });
+        assert_eq!(error.is_none(), enabled);
+        let error = venue_dispatch::venue_relative!(venue; V => { V::new(()).err() });
+        assert_eq!(error.is_none(), enabled);
+    }
+    let error = venue_dispatch::venue!(Venue::Okx; V => { V::new(()).err() });
+    assert_eq!(error, None);
+}
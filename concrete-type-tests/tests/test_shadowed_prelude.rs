@@ -0,0 +1,112 @@
+// Generated code names `core`, `std` and prelude items by absolute path, so it compiles in
+// modules shadowing them
+#![allow(dead_code, non_camel_case_types)]
+
+mod exchanges {
+    pub trait Exchange {
+        type Config
+        where
+            Self: Sized;
+
+        fn new(config: Self::Config) -> Self
+        where
+            Self: Sized;
+
+        fn name(&self) -> &'static str;
+    }
+
+    pub struct Binance;
+
+    impl Exchange for Binance {
+        type Config = ();
+
+        fn new(_config: ()) -> Self {
+            Binance
+        }
+
+        fn name(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    pub struct Okx;
+
+    impl Exchange for Okx {
+        type Config = ();
+
+        fn new(_config: ()) -> Self {
+            Okx
+        }
+
+        fn name(&self) -> &'static str {
+            "okx"
+        }
+    }
+}
+
+mod shadowed {
+    use crate::exchanges::Exchange as _;
+    use concrete_type::{Concrete, ConcreteConfig};
+
+    mod core {}
+    mod std {}
+
+    type Result<T> = ::core::result::Result<T, ()>;
+    type Option = ();
+    struct Box;
+    struct Vec;
+    struct String;
+    trait Default {}
+    trait Debug {}
+    trait Clone {}
+    trait Send {}
+    trait Iterator {}
+
+    #[derive(
+        Concrete,
+        ::core::clone::Clone,
+        ::core::marker::Copy,
+        ::core::fmt::Debug,
+        ::core::cmp::PartialEq,
+        ::core::cmp::Eq,
+        ::core::hash::Hash,
+    )]
+    #[concrete(
+        factory = "crate::exchanges::Exchange",
+        from_name,
+        handle,
+        set,
+        per_variant,
+        selftest
+    )]
+    pub enum Exchange {
+        #[concrete(ty = "crate::exchanges::Binance", priority = 0)]
+        Binance,
+        #[concrete(ty = "crate::exchanges::Okx", priority = 1)]
+        Okx,
+    }
+
+    #[derive(ConcreteConfig)]
+    #[concrete(fallback)]
+    pub enum ExchangeConfig {
+        #[concrete = "crate::exchanges::Binance"]
+        #[concrete(feature = "test-backend")]
+        Binance(u8),
+        #[concrete = "crate::exchanges::Okx"]
+        Okx,
+    }
+
+    pub fn name(exchange: Exchange) -> &'static str {
+        exchange!(exchange; E => { <E as crate::exchanges::Exchange>::new(()).name() })
+    }
+}
+
+#[test]
+fn test_derives_in_module_shadowing_prelude() {
+    use shadowed::{Exchange, ExchangeFactory};
+
+    assert_eq!(shadowed::name(Exchange::Okx), "okx");
+    assert_eq!(Exchange::from_name("Okx").map(|e| e as usize), Some(1));
+    let exchange = Exchange::Okx.create(Box::new(())).ok().unwrap();
+    assert_eq!(exchange.name(), "okx");
+}
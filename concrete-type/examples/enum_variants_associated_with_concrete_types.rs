@@ -41,7 +41,7 @@ pub mod strategies {
     pub struct StrategyB;
 }
 
-pub struct TradingSystem<Exchange, Strategy> {
+struct TradingSystem<Exchange, Strategy> {
     phantom: PhantomData<(Exchange, Strategy)>,
 }
 
@@ -0,0 +1,66 @@
+//! Forwarding of trait methods for `#[concrete_delegate]`.
+
+use quote::quote;
+use syn::spanned::Spanned;
+
+/// Returns the name of the hidden macro generated by [`concrete_delegate`](crate::concrete_delegate) for a trait.
+pub(crate) fn delegate_macro_name(trait_name: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(
+        &format!("__concrete_delegate_{}", trait_name),
+        trait_name.span(),
+    )
+}
+
+/// Generates the forwarding implementation of a trait method, as a `macro_rules!` transcriber
+/// matching on every variant of `$instance` and calling the method on the held value.
+pub(crate) fn delegate_method(
+    method: &syn::TraitItemFn,
+) -> syn::Result<Option<proc_macro2::TokenStream>> {
+    let mut sig = method.sig.clone();
+    match sig.receiver() {
+        Some(receiver) if receiver.colon_token.is_some() => {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "`concrete_delegate` only forwards `self`, `&self` and `&mut self` receivers",
+            ));
+        }
+        Some(_) => {}
+        // Associated functions can't be forwarded without a value to match on
+        None if method.default.is_some() => return Ok(None),
+        None => {
+            return Err(syn::Error::new_spanned(
+                &method.sig,
+                "`concrete_delegate` can't forward an associated function without a `self` \
+                 receiver or a default body",
+            ));
+        }
+    }
+
+    // Arguments are renamed, so patterns in the trait declaration don't need to be bindings
+    let mut args = Vec::new();
+    for (index, input) in sig.inputs.iter_mut().enumerate() {
+        if let syn::FnArg::Typed(pat_type) = input {
+            let arg = syn::Ident::new(&format!("__concrete_arg{}", index), pat_type.pat.span());
+            *pat_type.pat = syn::parse_quote! { #arg };
+            args.push(arg);
+        }
+    }
+
+    let method_name = &sig.ident;
+    let await_call = sig.asyncness.map(|_| quote! { .await });
+    let call = quote! {
+        <$ty as $trait_path>::#method_name(inner, #(#args),*) #await_call
+    };
+    let call = match sig.unsafety {
+        Some(_) => quote! { unsafe { #call } },
+        None => call,
+    };
+
+    Ok(Some(quote! {
+        #sig {
+            match self {
+                $($instance::$variant(inner) => #call,)*
+            }
+        }
+    }))
+}
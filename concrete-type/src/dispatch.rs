@@ -0,0 +1,620 @@
+//! Emission of the `macro_rules!` dispatch macros and the helpers their rules share.
+
+pub(crate) mod concrete;
+pub(crate) mod config;
+
+use quote::quote;
+
+use crate::options::{EnumOptions, VariantOptions};
+use crate::paths::concrete_path_string;
+
+/// Returns the statements binding `$type_param` to the concrete type of a variant in a dispatch
+/// macro arm, each paired with the `#[cfg]` attribute the arm is gated on.
+///
+/// Variants gated behind a feature get two arms: one binding the concrete type when the feature
+/// is enabled, and one binding a stub whose constructor fails when it is disabled.
+///
+/// With the `expand-debug` feature, each binding is followed by a line printed in debug builds,
+/// naming the dispatched variant and the call site of the dispatch macro.
+pub(crate) fn concrete_type_aliases(
+    type_name: &syn::Ident,
+    variant_name: &syn::Ident,
+    concrete_type: &syn::Path,
+    transformed_path: &proc_macro2::TokenStream,
+    feature: Option<&syn::LitStr>,
+) -> Vec<(Option<proc_macro2::TokenStream>, proc_macro2::TokenStream)> {
+    let trace = cfg!(feature = "expand-debug").then(|| {
+        let message = format!(
+            "[concrete-type] {}::{} -> {} at {{}}:{{}}",
+            type_name,
+            variant_name,
+            concrete_path_string(concrete_type)
+        );
+        // `file!` and `line!` resolve to the outermost macro call, the dispatch at the call site
+        quote! {
+            #[cfg(debug_assertions)]
+            ::std::eprintln!(#message, ::core::file!(), ::core::line!());
+        }
+    });
+    let alias = quote! {
+        type $type_param = #transformed_path;
+        #trace
+    };
+    let Some(feature) = feature else {
+        return vec![(None, alias)];
+    };
+
+    let stub_name = syn::Ident::new("__ConcreteUnsupported", variant_name.span());
+    let stub = unsupported_stub(&stub_name, variant_name, feature, quote! {});
+    vec![
+        (Some(quote! { #[cfg(feature = #feature)] }), alias),
+        (
+            Some(quote! { #[cfg(not(feature = #feature))] }),
+            quote! {
+                #stub
+                type $type_param = #stub_name;
+                #trace
+            },
+        ),
+    ]
+}
+
+/// Generates the stub standing in for the concrete type of a variant whose feature is disabled.
+///
+/// Dispatch macros declare the stub locally in each arm, so it never needs to be named by path.
+/// `attrs` are applied to both the stub and its impl.
+pub(crate) fn unsupported_stub(
+    stub_name: &syn::Ident,
+    variant_name: &syn::Ident,
+    feature: &syn::LitStr,
+    attrs: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let variant_str = variant_name.to_string();
+    quote! {
+        /// Stub for a concrete type whose feature is disabled.
+        #attrs
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        pub struct #stub_name;
+
+        #attrs
+        #[allow(dead_code)]
+        impl #stub_name {
+            /// Name of the disabled variant.
+            const VARIANT: &'static str = #variant_str;
+            /// Cargo feature that enables the variant.
+            const FEATURE: &'static str = #feature;
+
+            /// Always fails, describing the feature required by the variant.
+            fn new<C>(_config: C) -> ::core::result::Result<Self, ::std::string::String> {
+                ::core::result::Result::Err(::std::format!(
+                    "variant `{}` requires the `{}` feature",
+                    Self::VARIANT,
+                    Self::FEATURE
+                ))
+            }
+        }
+    }
+}
+
+/// Returns the name of the stub declared next to the enum for a feature-gated variant.
+pub(crate) fn unsupported_stub_name(
+    type_name: &syn::Ident,
+    variant_name: &syn::Ident,
+) -> syn::Ident {
+    syn::Ident::new(
+        &format!("__{}{}Unsupported", type_name, variant_name),
+        variant_name.span(),
+    )
+}
+
+/// Generates the stubs declared next to the enum for its feature-gated variants, used by the
+/// generated methods when the feature is disabled.
+///
+/// Unlike the stubs local to dispatch macro arms, these are shared by every method so that they
+/// report a consistent `TypeId`.
+pub(crate) fn generate_unsupported_stubs<'a>(
+    type_name: &syn::Ident,
+    variants: impl IntoIterator<Item = (&'a syn::Ident, Option<&'a syn::LitStr>)>,
+) -> proc_macro2::TokenStream {
+    let stubs = variants.into_iter().filter_map(|(variant_name, feature)| {
+        let feature = feature?;
+        let stub_name = unsupported_stub_name(type_name, variant_name);
+        let cfg = quote! { #[cfg(not(feature = #feature))] };
+        Some(unsupported_stub(&stub_name, variant_name, feature, cfg))
+    });
+    quote! { #(#stubs)* }
+}
+
+/// Builds an expression from the concrete type of a variant using `expr`.
+///
+/// For variants gated behind a feature, the expression is built from the enum's stub (see
+/// [`generate_unsupported_stubs`]) when the feature is disabled.
+pub(crate) fn concrete_type_expr(
+    type_name: &syn::Ident,
+    variant_name: &syn::Ident,
+    concrete_type: &syn::Path,
+    feature: Option<&syn::LitStr>,
+    expr: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let Some(feature) = feature else {
+        return expr(quote! { #concrete_type });
+    };
+
+    let stub_name = unsupported_stub_name(type_name, variant_name);
+    let enabled = expr(quote! { #concrete_type });
+    let disabled = expr(quote! { #stub_name });
+    quote! {
+        {
+            #[cfg(feature = #feature)]
+            let value = #enabled;
+            #[cfg(not(feature = #feature))]
+            let value = #disabled;
+            value
+        }
+    }
+}
+
+/// Builds the identifier of a generated dispatch macro, applying the enum-level naming options.
+///
+/// With `prefix_crate_name`, the name of the crate being compiled (as reported by Cargo through
+/// `CARGO_CRATE_NAME`) is prepended, e.g. `exchange` becomes `mycrate_exchange`.
+pub(crate) fn dispatch_macro_name(
+    name: &str,
+    type_name: &syn::Ident,
+    options: &EnumOptions,
+) -> syn::Result<syn::Ident> {
+    if !options.prefix_crate_name {
+        return Ok(syn::Ident::new(name, type_name.span()));
+    }
+
+    let crate_name = std::env::var("CARGO_CRATE_NAME").map_err(|_| {
+        syn::Error::new_spanned(
+            type_name,
+            "`prefix_crate_name` requires the crate to be built by Cargo (CARGO_CRATE_NAME is not set)",
+        )
+    })?;
+
+    Ok(syn::Ident::new(
+        &format!("{}_{}", crate_name, name),
+        type_name.span(),
+    ))
+}
+
+/// Renders the variants as a markdown list of their concrete types, followed by their own doc
+/// comments.
+pub(crate) fn variants_doc(variants: &[(&syn::Ident, &syn::Path, &VariantOptions)]) -> String {
+    variants
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            let item = format!(
+                "- `{}` → `{}`",
+                variant_name,
+                concrete_path_string(concrete_type)
+            );
+            match &variant_options.doc {
+                // Continuation lines are indented to stay within the list item
+                Some(doc) => format!("{}: {}", item, doc.replace('\n', "\n  ")),
+                None => item,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A generated macro emitted by [`emit_dispatch_macro`].
+pub(crate) struct DispatchMacro {
+    /// Name of the macro, e.g. `exchange`.
+    pub(crate) name: syn::Ident,
+    /// The `macro_rules!` arms.
+    pub(crate) rules: proc_macro2::TokenStream,
+    /// First sentence of the macro's documentation.
+    pub(crate) summary: String,
+    /// Expected arguments, shown in the documentation and in errors for malformed invocations.
+    pub(crate) usage: &'static str,
+}
+
+/// Emits the definitions of the generated macros of an enum from their `macro_rules!` arms.
+///
+/// A catch-all arm is appended so malformed invocations report the expected `usage` instead
+/// of "no rules expected this token".
+///
+/// By default the macro is exported at the crate root. When `macros_mod` is set, the macro is
+/// exported under a hidden name and re-exported from a module with that name instead, so it is
+/// reachable as `path::to::macros_mod::macro_name!`.
+///
+/// With `relative_macro`, a second `{macro_name}_relative` flavor is emitted alongside, with
+/// `$crate::` paths rewritten to `crate::` so they resolve in the calling crate.
+///
+/// The `#[cfg(feature = "...")]` attributes of the arms would be evaluated against the features
+/// of the calling crate, so for feature-gated variants they are resolved in the defining crate
+/// by [`resolve_features_in_defining_crate`].
+pub(crate) fn emit_dispatch_macro(
+    macros: Vec<DispatchMacro>,
+    variants: &[(&syn::Ident, &syn::Path, &VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    // The local macros resolving the features are named after the first generated macro, which
+    // is unique among the macros exported by the crate
+    let chain_name = macros
+        .first()
+        .map(|dispatch_macro| format!("__concrete_{}", dispatch_macro.name))
+        .unwrap_or_default();
+    let mut flavors = Vec::new();
+    for dispatch_macro in macros {
+        let DispatchMacro {
+            name: macro_name,
+            rules,
+            summary,
+            usage,
+        } = dispatch_macro;
+        let relative = options.relative_macro.then(|| {
+            let relative_name =
+                syn::Ident::new(&format!("{}_relative", macro_name), macro_name.span());
+            let relative_doc = format!(
+                "Unlike `{}!`, `crate::` concrete types are resolved in the calling crate rather \
+                 than the defining one, for crates re-exporting the mapped types under the same \
+                 paths.",
+                macro_name
+            );
+            (
+                relative_name,
+                caller_relative_tokens(rules.clone()),
+                summary.clone(),
+                usage,
+                Some(relative_doc),
+            )
+        });
+        flavors.push((macro_name, rules, summary, usage, None));
+        flavors.extend(relative);
+    }
+
+    let mut features: Vec<&syn::LitStr> = Vec::new();
+    for feature in variants
+        .iter()
+        .filter_map(|(_, _, variant_options)| variant_options.feature.as_ref())
+    {
+        if !features
+            .iter()
+            .any(|known| known.value() == feature.value())
+        {
+            features.push(feature);
+        }
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let mut definitions = Vec::new();
+    let mut reexports = Vec::new();
+    for (name, rules, summary, usage, flavor_doc) in flavors {
+        let error_message = format!(
+            "invalid `{}!` invocation, expected `{}!({})`",
+            name, name, usage
+        );
+        // `crate::` in the relative flavor is deliberately resolved where the macro is used
+        let allow_crate = flavor_doc.is_some().then(|| {
+            quote! { #[allow(clippy::crate_in_macro_def)] }
+        });
+        let flavor_doc = flavor_doc.map(|flavor_doc| format!("\n\n{}", flavor_doc));
+        let macro_doc = format!(
+            "{}{}\n\n\
+             Usage: `{}!({})`\n\n\
+             # Variants\n\n\
+             {}\n\n\
+             # Re-exporting\n\n\
+             Facade crates can re-export this macro with `pub use defining_crate::{};` alongside \
+             the enum. The enum must be in scope at the call site. To expand `crate::` concrete \
+             types through the facade rather than the defining crate, set \
+             `#[concrete(crate_path = \"::facade::path\")]` on the enum.",
+            summary,
+            flavor_doc.unwrap_or_default(),
+            name,
+            usage,
+            variants_doc(variants),
+            name
+        );
+        let rules = quote! {
+            #rules
+            ($($tokens:tt)*) => {
+                ::core::compile_error!(#error_message)
+            };
+        };
+
+        if options.macros_mod.is_none() {
+            definitions.push(quote! {
+                #[doc = #macro_doc]
+                #doc_hidden
+                #allow_crate
+                #[macro_export]
+                macro_rules! #name {
+                    #rules
+                }
+            });
+            continue;
+        }
+
+        let hidden_name = syn::Ident::new(&format!("__concrete_{}", name), name.span());
+        definitions.push(quote! {
+            #[doc = #macro_doc]
+            #[doc(hidden)]
+            #allow_crate
+            #[macro_export]
+            macro_rules! #hidden_name {
+                #rules
+            }
+        });
+        // Generated macros a crate never calls are not worth a warning
+        reexports.push(quote! {
+            #[doc(inline)]
+            #[allow(unused_imports)]
+            pub use #hidden_name as #name;
+        });
+    }
+
+    let macros_mod = options.macros_mod.as_ref().map(|macros_mod| {
+        quote! {
+            #[doc = "Dispatch macros generated by `concrete-type`."]
+            #doc_hidden
+            pub mod #macros_mod {
+                #(#reexports)*
+            }
+        }
+    });
+
+    let definitions =
+        resolve_features_in_defining_crate(quote! { #(#definitions)* }, &features, &chain_name);
+
+    quote! {
+        #definitions
+
+        #macros_mod
+    }
+}
+
+/// Evaluates the `feature = "..."` predicates of the `cfg` attributes in `definitions`, the
+/// generated macro definitions, against the features of the defining crate.
+///
+/// The definitions are placed in the transcriber of a local macro that takes the resolved
+/// predicates, and a chain of local macros, one per feature and each defined under opposite
+/// `cfg` attributes, hands it `all()` or `any()` for its feature in turn. The output stays
+/// linear in the number of features. Every `$` of the definitions is escaped as
+/// `$__concrete_dollar` in the transcriber, bound to the `$` token passed along the chain.
+///
+/// Without features, the definitions are returned as-is.
+pub(crate) fn resolve_features_in_defining_crate(
+    definitions: proc_macro2::TokenStream,
+    features: &[&syn::LitStr],
+    chain_name: &str,
+) -> proc_macro2::TokenStream {
+    if features.is_empty() {
+        return definitions;
+    }
+
+    let span = proc_macro2::Span::call_site();
+    let dollar = syn::Ident::new("__concrete_dollar", span);
+    let predicates: Vec<syn::Ident> = (0..features.len())
+        .map(|index| syn::Ident::new(&format!("__concrete_feature_{}", index), span))
+        .collect();
+    // `$` is escaped before the predicates are substituted, which are metavariables themselves
+    let template = resolve_feature_cfgs(escape_macro_vars(definitions, &dollar), &|feature| {
+        let index = features.iter().position(|known| known.value() == feature)?;
+        let predicate = &predicates[index];
+        Some(quote! { $($#predicate)* })
+    });
+
+    let define_name = syn::Ident::new(&format!("{}_define", chain_name), span);
+    let stage_names: Vec<syn::Ident> = (0..features.len())
+        .map(|index| syn::Ident::new(&format!("{}_feature_{}", chain_name, index), span))
+        .collect();
+    let stages = features.iter().enumerate().map(|(index, feature)| {
+        let stage_name = &stage_names[index];
+        let next_name = stage_names.get(index + 1).unwrap_or(&define_name);
+        quote! {
+            #[cfg(feature = #feature)]
+            macro_rules! #stage_name {
+                ($($resolved:tt)*) => {
+                    #next_name! { $($resolved)* [all()] }
+                };
+            }
+            #[cfg(not(feature = #feature))]
+            macro_rules! #stage_name {
+                ($($resolved:tt)*) => {
+                    #next_name! { $($resolved)* [any()] }
+                };
+            }
+        }
+    });
+    let first_stage = &stage_names[0];
+
+    quote! {
+        macro_rules! #define_name {
+            ($#dollar:tt #([$($#predicates:tt)*])*) => {
+                #template
+            };
+        }
+        #(#stages)*
+        #first_stage! { $ }
+    }
+}
+
+/// Escapes every `$` in `tokens` as the macro variable `$dollar`, so the tokens can be emitted
+/// unchanged by a macro transcriber binding `dollar` to a `$` token.
+pub(crate) fn escape_macro_vars(
+    tokens: proc_macro2::TokenStream,
+    dollar: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    let mut escaped = Vec::new();
+    for token in tokens {
+        match token {
+            proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '$' => {
+                escaped.push(proc_macro2::TokenTree::Punct(punct));
+                escaped.push(proc_macro2::TokenTree::Ident(dollar.clone()));
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut escaped_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    escape_macro_vars(group.stream(), dollar),
+                );
+                escaped_group.set_span(group.span());
+                escaped.push(proc_macro2::TokenTree::Group(escaped_group));
+            }
+            token => escaped.push(token),
+        }
+    }
+    escaped.into_iter().collect()
+}
+
+/// Rewrites the `feature = "..."` predicates of the `cfg` attributes in `tokens` to the
+/// predicate returned by `resolve` for the feature, so that they no longer depend on the
+/// features of the crate the tokens are expanded in.
+///
+/// Predicates on features for which `resolve` returns `None` are kept as-is.
+pub(crate) fn resolve_feature_cfgs(
+    tokens: proc_macro2::TokenStream,
+    resolve: &dyn Fn(&str) -> Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let mut resolved = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            proc_macro2::TokenTree::Ident(ident) if ident == "cfg" => {
+                resolved.push(proc_macro2::TokenTree::Ident(ident));
+                // `cfg!(...)` is left alone, only `cfg(...)` attributes are resolved
+                let predicate = match tokens.peek() {
+                    Some(proc_macro2::TokenTree::Group(group))
+                        if group.delimiter() == proc_macro2::Delimiter::Parenthesis =>
+                    {
+                        let mut predicate = proc_macro2::Group::new(
+                            group.delimiter(),
+                            resolve_cfg_predicate(group.stream(), resolve),
+                        );
+                        predicate.set_span(group.span());
+                        predicate
+                    }
+                    _ => continue,
+                };
+                tokens.next();
+                resolved.push(proc_macro2::TokenTree::Group(predicate));
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut resolved_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    resolve_feature_cfgs(group.stream(), resolve),
+                );
+                resolved_group.set_span(group.span());
+                resolved.push(proc_macro2::TokenTree::Group(resolved_group));
+            }
+            token => resolved.push(token),
+        }
+    }
+    resolved.into_iter().collect()
+}
+
+/// Resolves the `feature = "..."` predicates within a `cfg` predicate, see
+/// [`resolve_feature_cfgs`].
+pub(crate) fn resolve_cfg_predicate(
+    tokens: proc_macro2::TokenStream,
+    resolve: &dyn Fn(&str) -> Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let tokens: Vec<proc_macro2::TokenTree> = tokens.into_iter().collect();
+    let mut resolved = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        let resolved_predicate = match &tokens[index..] {
+            [
+                proc_macro2::TokenTree::Ident(ident),
+                proc_macro2::TokenTree::Punct(punct),
+                proc_macro2::TokenTree::Literal(literal),
+                ..,
+            ] if ident == "feature" && punct.as_char() == '=' => {
+                match syn::Lit::new(literal.clone()) {
+                    syn::Lit::Str(feature) => resolve(&feature.value()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        if let Some(resolved_predicate) = resolved_predicate {
+            resolved.extend(resolved_predicate);
+            index += 3;
+            continue;
+        }
+
+        resolved.push(match &tokens[index] {
+            proc_macro2::TokenTree::Group(group) => {
+                let mut resolved_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    resolve_cfg_predicate(group.stream(), resolve),
+                );
+                resolved_group.set_span(group.span());
+                proc_macro2::TokenTree::Group(resolved_group)
+            }
+            token => token.clone(),
+        });
+        index += 1;
+    }
+    resolved.into_iter().collect()
+}
+
+/// Rewrites every `$crate` in `tokens` to `crate`, so paths resolve in the crate invoking the
+/// generated macro instead of the defining crate.
+pub(crate) fn caller_relative_tokens(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut relative = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            proc_macro2::TokenTree::Punct(punct)
+                if punct.as_char() == '$'
+                    && matches!(tokens.peek(), Some(proc_macro2::TokenTree::Ident(ident)) if ident == "crate") =>
+            {
+                // The `crate` identifier is kept as-is, dropping the `$`
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut relative_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    caller_relative_tokens(group.stream()),
+                );
+                relative_group.set_span(group.span());
+                relative.push(proc_macro2::TokenTree::Group(relative_group));
+            }
+            token => relative.push(token),
+        }
+    }
+    relative.into_iter().collect()
+}
+
+/// Replaces the macro variables in `tokens` for which `replace` returns tokens, e.g. to bind
+/// `$type_param` under another name when expanding the same arms twice in one macro rule.
+pub(crate) fn replace_macro_vars(
+    tokens: proc_macro2::TokenStream,
+    replace: &dyn Fn(&syn::Ident) -> Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let mut replaced = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '$' => {
+                let replacement = match tokens.peek() {
+                    Some(proc_macro2::TokenTree::Ident(ident)) => replace(ident),
+                    _ => None,
+                };
+                match replacement {
+                    Some(replacement) => {
+                        tokens.next();
+                        replaced.extend(replacement);
+                    }
+                    None => replaced.push(proc_macro2::TokenTree::Punct(punct)),
+                }
+            }
+            proc_macro2::TokenTree::Group(group) => {
+                let mut replaced_group = proc_macro2::Group::new(
+                    group.delimiter(),
+                    replace_macro_vars(group.stream(), replace),
+                );
+                replaced_group.set_span(group.span());
+                replaced.push(proc_macro2::TokenTree::Group(replaced_group));
+            }
+            token => replaced.push(token),
+        }
+    }
+    replaced.into_iter().collect()
+}
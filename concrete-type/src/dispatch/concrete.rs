@@ -0,0 +1,769 @@
+//! Dispatch macros of `Concrete` enums.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+
+use crate::dispatch::{
+    DispatchMacro, concrete_type_aliases, dispatch_macro_name, emit_dispatch_macro,
+};
+use crate::options::{EnumOptions, VariantOptions};
+use crate::paths::{
+    concrete_path_string, macro_crate_root, transform_path_for_macro, transform_path_with_type_args,
+};
+
+/// Generates the dispatch macro of a `Concrete` enum, along with its `for_each_{enum}!` macro
+/// with `#[concrete(for_each)]`.
+pub(crate) fn generate_concrete_macros(
+    type_name: &syn::Ident,
+    macro_name: &syn::Ident,
+    macro_name_str: &str,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    all_unit: bool,
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    // Generate match arms for the macro_rules! version. Variants sharing a concrete type (and
+    // feature gate) share an or-pattern arm, so the block is expanded once per concrete type. This
+    // compounds in combined matchers, which nest one dispatch macro per enum
+    let crate_root = macro_crate_root(options);
+    let feature_of =
+        |variant_options: &VariantOptions| variant_options.feature.as_ref().map(syn::LitStr::value);
+    let mut shared_arms: Vec<(&syn::Path, &VariantOptions, Vec<&syn::Ident>)> = Vec::new();
+    // Nested variants hand their inner enum to its own dispatch macro, by its default name unless
+    // the variant names the macro's path, or the crate exporting it with `nested_extern`
+    let mut nested_arms: Vec<(&syn::Ident, proc_macro2::TokenStream)> = Vec::new();
+    for (variant_name, concrete_type, variant_options) in variant_mappings {
+        if variant_options.nested {
+            let inner_macro = match &variant_options.nested_macro {
+                Some(nested_macro) => transform_path_for_macro(nested_macro, &crate_root),
+                None => {
+                    let inner_name = &concrete_type.segments.last().unwrap().ident;
+                    let inner_macro = syn::Ident::new(
+                        &inner_name.to_string().to_case(Case::Snake),
+                        inner_name.span(),
+                    );
+                    if variant_options.nested_extern {
+                        let inner_crate = &concrete_type.segments.first().unwrap().ident;
+                        quote! { #inner_crate::#inner_macro }
+                    } else {
+                        quote! { #inner_macro }
+                    }
+                }
+            };
+            nested_arms.push((variant_name, inner_macro));
+            continue;
+        }
+        let shared_arm = shared_arms
+            .iter_mut()
+            .find(|(shared_type, shared_options, _)| {
+                concrete_path_string(shared_type) == concrete_path_string(concrete_type)
+                    && feature_of(shared_options) == feature_of(variant_options)
+            });
+        match shared_arm {
+            Some((_, _, variant_names)) => variant_names.push(variant_name),
+            None => shared_arms.push((concrete_type, variant_options, vec![variant_name])),
+        }
+    }
+    // Arms may be wrapped in the pattern of an outer enum's variant, when flattened into it
+    let wrap_pattern = |outer_pattern: Option<&proc_macro2::TokenStream>,
+                        pattern: proc_macro2::TokenStream| {
+        match outer_pattern {
+            Some(outer_pattern) => quote! { #outer_pattern(#pattern) },
+            None => pattern,
+        }
+    };
+    // With `#[concrete(tagged)]`, blocks dispatched for a variant can call `.tagged()` on any
+    // value, pairing it with the variant in an `{Enum}Tagged`. The method comes from a trait local
+    // to the block, as items declared by the macro are visible to the caller's code
+    let tagged_name = syn::Ident::new(&format!("{}Tagged", type_name), type_name.span());
+    let tag_items = |variant_name: &syn::Ident| {
+        options.tagged.then(|| {
+            quote! {
+                #[allow(dead_code)]
+                trait __ConcreteTagged: ::core::marker::Sized {
+                    fn tagged(self) -> #tagged_name<Self>;
+                }
+                impl<__T> __ConcreteTagged for __T {
+                    fn tagged(self) -> #tagged_name<Self> {
+                        #tagged_name(self, #type_name::#variant_name)
+                    }
+                }
+            }
+        })
+    };
+    // `type_args` are the generic arguments supplied at the call site with `Type<Args> => ...`.
+    // With `expand-debug` or `tagged`, each variant gets its own arm so it can be named
+    let shared_match_arms = |outer_pattern: Option<&proc_macro2::TokenStream>,
+                             type_args: Option<&proc_macro2::TokenStream>,
+                             body: &proc_macro2::TokenStream| {
+        shared_arms
+            .iter()
+            .flat_map(|(concrete_type, variant_options, variant_names)| {
+                let transformed_path = match type_args {
+                    Some(type_args) => {
+                        transform_path_with_type_args(concrete_type, &crate_root, type_args)
+                    }
+                    None => transform_path_for_macro(concrete_type, &crate_root),
+                };
+                let arm_variants: Vec<&[&syn::Ident]> =
+                    if cfg!(feature = "expand-debug") || options.tagged {
+                        variant_names.chunks(1).collect()
+                    } else {
+                        vec![variant_names]
+                    };
+                arm_variants
+                    .into_iter()
+                    .flat_map(|variant_names| {
+                        concrete_type_aliases(
+                            type_name,
+                            variant_names[0],
+                            concrete_type,
+                            &transformed_path,
+                            variant_options.feature.as_ref(),
+                        )
+                        .into_iter()
+                        .map(|(cfg, alias)| {
+                            let pattern = wrap_pattern(
+                                outer_pattern,
+                                quote! { #(#type_name::#variant_names)|* },
+                            );
+                            let tag_items = tag_items(variant_names[0]);
+                            quote! {
+                                #cfg
+                                #pattern => {
+                                    #alias
+                                    #tag_items
+                                    #body
+                                }
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let nested_match_arms = |outer_pattern: Option<&proc_macro2::TokenStream>,
+                             type_args: Option<&proc_macro2::TokenStream>,
+                             body: &proc_macro2::TokenStream| {
+        let type_args = type_args.map(|type_args| quote! { <#type_args> });
+        nested_arms
+            .iter()
+            .map(|(variant_name, inner_macro)| {
+                let pattern =
+                    wrap_pattern(outer_pattern, quote! { #type_name::#variant_name(inner) });
+                quote! {
+                    #pattern => {
+                        #inner_macro!(inner; $type_param #type_args => { #body })
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    let macro_match_arms = |type_args: Option<&proc_macro2::TokenStream>,
+                            body: proc_macro2::TokenStream| {
+        let mut arms = shared_match_arms(None, type_args, &body);
+        arms.extend(nested_match_arms(None, type_args, &body));
+        arms
+    };
+
+    // With `#[concrete(flatten)]`, the default form chains through the `@flatten` rule of each
+    // inner enum's macro, which adds arms matching its own variants inside the outer variant's
+    // pattern, so the whole cross product is dispatched by a single match
+    // The expression dispatching a value, also used by the failover rule on each variant it tries
+    let dispatch = |instance: proc_macro2::TokenStream| {
+        if options.flatten {
+            let Some(((first_variant, first_macro), rest)) = nested_arms.split_first() else {
+                return Err(syn::Error::new_spanned(
+                    type_name,
+                    "`flatten` requires at least one `#[concrete(nested)]` variant",
+                ));
+            };
+            let direct_arms = shared_match_arms(None, None, &quote! { $code_block });
+            let rest = rest.iter().map(|(variant_name, inner_macro)| {
+                quote! { [#inner_macro] (#type_name::#variant_name) }
+            });
+            Ok(quote! {
+                #first_macro!(
+                    @flatten #instance; $type_param => $code_block;
+                    (#type_name::#first_variant) [#(#direct_arms,)*] #(#rest)*
+                )
+            })
+        } else {
+            let default_arms = macro_match_arms(None, quote! { $code_block });
+            Ok(quote! {
+                match #instance {
+                    #(#default_arms),*
+                }
+            })
+        }
+    };
+    let default_rule = dispatch(quote! { $enum_instance })?;
+
+    // Tries the block against each variant in turn until one succeeds, collecting the errors
+    let failover_rule = if options.failover {
+        let failover_error =
+            syn::Ident::new(&format!("{}FailoverError", type_name), type_name.span());
+        let try_variant = dispatch(quote! { &variant })?;
+        Some(quote! {
+            (@failover $variants:expr; $type_param:ident => $code_block:block) => {{
+                let mut errors = ::std::vec::Vec::new();
+                let mut success = ::core::option::Option::None;
+                for variant in $variants {
+                    let variant: #type_name = variant;
+                    match #try_variant {
+                        ::core::result::Result::Ok(value) => {
+                            success = ::core::option::Option::Some(value);
+                            break;
+                        }
+                        ::core::result::Result::Err(error) => errors.push((variant, error)),
+                    }
+                }
+                match success {
+                    ::core::option::Option::Some(value) => ::core::result::Result::Ok(value),
+                    ::core::option::Option::None => {
+                        ::core::result::Result::Err(#failover_error::new(errors))
+                    }
+                }
+            }};
+        })
+    } else {
+        None
+    };
+
+    // Runs a block returning a `Result`, wrapping its error with the variant it was raised for
+    let try_rule = if options.minimal {
+        None
+    } else {
+        let try_variant = dispatch(quote! { variant })?;
+        Some(quote! {
+            (@try $enum_instance:expr; $type_param:ident => $code_block:block) => {{
+                let variant: &#type_name = &$enum_instance;
+                ::core::result::Result::map_err(#try_variant, |error| variant.wrap_err(error))
+            }};
+        })
+    };
+
+    // With `Type<Args> => ...`, the arguments are appended to the generic arguments of each
+    // concrete type, for mapped types that are only partially applied
+    let turbofish_arms = macro_match_arms(Some(&quote! { $($type_arg),+ }), quote! { $code_block });
+
+    // Rules used when this enum is flattened into an outer one, adding arms for its variants
+    // wrapped in `$outer_pattern` to the accumulated `$arms`
+    let outer_pattern = quote! { $($outer_pattern)* };
+    let mut flatten_arms = shared_match_arms(Some(&outer_pattern), None, &quote! { $code_block });
+    flatten_arms.extend(nested_match_arms(
+        Some(&outer_pattern),
+        None,
+        &quote! { $code_block },
+    ));
+
+    // With a `where |value| guard` clause, the guard is checked inside the arm, with the concrete
+    // type already aliased, and the macro evaluates to an `Option`
+    let guarded_arms = macro_match_arms(
+        None,
+        quote! {
+            let $guard_param = value;
+            if $guard {
+                ::core::option::Option::Some($code_block)
+            } else {
+                ::core::option::Option::None
+            }
+        },
+    );
+
+    // Blocks aliasing the concrete type of a variant named at compile time, without a match
+    let variant_blocks: Vec<_> = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            if variant_options.nested {
+                let message = format!(
+                    "`{}::{}` is a nested variant, its concrete type is only known from a value",
+                    type_name, variant_name
+                );
+                return (*variant_name, quote! { ::core::compile_error!(#message) });
+            }
+            let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+            let bindings = concrete_type_aliases(
+                type_name,
+                variant_name,
+                concrete_type,
+                &transformed_path,
+                variant_options.feature.as_ref(),
+            )
+            .into_iter()
+            .map(|(cfg, alias)| {
+                let tag_items = tag_items(variant_name);
+                quote! {
+                    #cfg
+                    let value = {
+                        #alias
+                        #tag_items
+                        $code_block
+                    };
+                }
+            });
+            let block = quote! {{
+                #(#bindings)*
+                value
+            }};
+            (*variant_name, block)
+        })
+        .collect();
+
+    // Items instantiated once per distinct concrete type by `#[concrete_impls]`, each in its own
+    // anonymous const so the type parameter's alias doesn't clash. Disabled variants are skipped
+    let impls_items = shared_arms
+        .iter()
+        .map(|(concrete_type, variant_options, _)| {
+            let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+            let cfg = variant_options
+                .feature
+                .as_ref()
+                .map(|feature| quote! { #[cfg(feature = #feature)] });
+            quote! {
+                #cfg
+                const _: () = {
+                    type $type_param = #transformed_path;
+                    $($item)*
+                };
+            }
+        })
+        .chain(nested_arms.iter().map(|(_, inner_macro)| {
+            quote! {
+                #inner_macro!(@impls $type_param; { $($item)* });
+            }
+        }))
+        .collect::<Vec<_>>();
+
+    // Used by combined matchers that fix some of their enums, e.g. `Exchange = Binance`
+    let variant_arms = variant_blocks.iter().map(|(variant_name, block)| {
+        quote! {
+            (@variant #variant_name; $type_param:ident => $code_block:block) => { #block };
+        }
+    });
+
+    // A subset of variants is dispatched through a local macro holding one rule per variant, so
+    // only the listed variants expand the block and everything else goes to the else block
+    let subset_rules = variant_blocks.iter().map(|(variant_name, block)| {
+        quote! {
+            (#variant_name) => { #block };
+        }
+    });
+    let unknown_variant_prefix = format!("`{}` has no variant `", type_name);
+
+    // Used by combined matchers to bound the number of combinations they expand the block into:
+    // the number of arms the default form expands it into, and the enum's limit
+    let shared_arm_count = if cfg!(feature = "expand-debug") {
+        shared_arms
+            .iter()
+            .map(|(_, _, variant_names)| variant_names.len())
+            .sum()
+    } else {
+        shared_arms.len()
+    };
+    let nested_arm_counts = nested_arms
+        .iter()
+        .map(|(_, inner_macro)| quote! { + #inner_macro!(@arm_count) });
+    let max_combinations = match &options.max_combinations {
+        Some(max_combinations) => {
+            let max_combinations = max_combinations.base10_parse::<usize>()?;
+            quote! { #max_combinations }
+        }
+        None => quote! { ::core::primitive::usize::MAX },
+    };
+
+    // Used by `gen_registry!`, pairing every enabled variant with a constructor of its concrete
+    // type. Disabled variants are left out of the registry, and building one panics
+    let registry_rule = if all_unit && !options.partial {
+        let entries =
+            variant_mappings
+                .iter()
+                .map(|(variant_name, concrete_type, variant_options)| {
+                    let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+                    let cfg = variant_options
+                        .feature
+                        .as_ref()
+                        .map(|feature| quote! { #[cfg(feature = #feature)] });
+                    quote! {
+                        #cfg
+                        (#type_name::#variant_name, {
+                            fn construct() -> ::std::boxed::Box<dyn $($bound)+> {
+                                ::std::boxed::Box::new(<#transformed_path>::$constructor())
+                            }
+                            construct
+                        })
+                    }
+                });
+        let build_arms =
+            variant_mappings
+                .iter()
+                .map(|(variant_name, concrete_type, variant_options)| {
+                    let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+                    let Some(feature) = &variant_options.feature else {
+                        return quote! {
+                            #type_name::#variant_name => {
+                                ::std::boxed::Box::new(<#transformed_path>::$constructor())
+                            }
+                        };
+                    };
+                    let message = format!(
+                        "`{}::{}` requires the `{}` feature",
+                        type_name,
+                        variant_name,
+                        feature.value()
+                    );
+                    quote! {
+                        #[cfg(feature = #feature)]
+                        #type_name::#variant_name => {
+                            ::std::boxed::Box::new(<#transformed_path>::$constructor())
+                        }
+                        #[cfg(not(feature = #feature))]
+                        #type_name::#variant_name => ::core::panic!(#message)
+                    }
+                });
+        let registry_doc = format!(
+            "Constructors of the concrete type of every enabled `{}` variant, in declaration order.",
+            type_name
+        );
+        let build_doc = format!(
+            "Builds the concrete type of a `{}` variant.\n\n\
+             # Panics\n\n\
+             If the variant's feature is disabled.",
+            type_name
+        );
+        quote! {
+            (
+                @registry $vis:vis, [$($bound:tt)+], $constructor:ident, $registry:ident,
+                $build:ident
+            ) => {
+                #[doc = #registry_doc]
+                $vis static $registry: &[
+                    (#type_name, fn() -> ::std::boxed::Box<dyn $($bound)+>)
+                ] = &[
+                    #(#entries),*
+                ];
+
+                #[doc = #build_doc]
+                $vis fn $build(kind: #type_name) -> ::std::boxed::Box<dyn $($bound)+> {
+                    match kind {
+                        #(#build_arms),*
+                    }
+                }
+            };
+        }
+    } else {
+        let message = if options.partial {
+            format!(
+                "`gen_registry!` can't construct the partially applied types of `{}`",
+                type_name
+            )
+        } else {
+            format!(
+                "`gen_registry!` requires every variant of `{}` to be a unit variant",
+                type_name
+            )
+        };
+        quote! {
+            (@registry $($tokens:tt)*) => {
+                ::core::compile_error!(#message);
+            };
+        }
+    };
+
+    // Blocks run in turn for every variant, nested enums iterating their own variants. Disabled
+    // variants are skipped, and partially applied types can't be aliased without their arguments
+    let each_blocks = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            if let Some((_, inner_macro)) = nested_arms
+                .iter()
+                .find(|(nested_name, _)| nested_name == variant_name)
+            {
+                return quote! {
+                    #inner_macro!(@for_each $type_param => $code_block);
+                };
+            }
+            let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+            let cfg = variant_options
+                .feature
+                .as_ref()
+                .map(|feature| quote! { #[cfg(feature = #feature)] });
+            let tag_items = tag_items(variant_name);
+            quote! {
+                #cfg
+                {
+                    type $type_param = #transformed_path;
+                    #tag_items
+                    $code_block;
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let for_each_rule = (!options.partial).then(|| {
+        quote! {
+            (@for_each $type_param:ident => $code_block:block) => {{
+                #(#each_blocks)*
+            }};
+        }
+    });
+    let for_each_macro = if !options.for_each {
+        None
+    } else {
+        let for_each_name =
+            dispatch_macro_name(&format!("for_each_{}", macro_name_str), type_name, options)?;
+        Some(DispatchMacro {
+            name: for_each_name,
+            rules: quote! {
+                ($type_param:ident => $code_block:block) => {{
+                    #(#each_blocks)*
+                }};
+            },
+            summary: format!(
+                "Runs a block once per `{}` variant, aliasing the variant's concrete type, e.g. to \
+                 register every backend at startup.",
+                type_name
+            ),
+            usage: "Type => { ... }",
+        })
+    };
+
+    // Used by combined matchers binding a name per combination. Each variant appends the suffix
+    // literals and its snake_case name to the names collected so far and hands them to the
+    // callback macro, within the arm aliasing its concrete type. Nested variants append their own
+    // name and let the inner enum append the rest after a `.`
+    let named_arms = variant_mappings
+        .iter()
+        .flat_map(|(variant_name, concrete_type, variant_options)| {
+            let name = variant_name.to_string().to_case(Case::Snake);
+            if let Some((_, inner_macro)) = nested_arms
+                .iter()
+                .find(|(nested_name, _)| nested_name == variant_name)
+            {
+                return vec![quote! {
+                    #type_name::#variant_name(inner) => {
+                        #inner_macro!(
+                            @named inner; $type_param; [$($name,)* $($suffix,)* #name] "." =>
+                            $callback { $($args)* }
+                        )
+                    }
+                }];
+            }
+            let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+            concrete_type_aliases(
+                type_name,
+                variant_name,
+                concrete_type,
+                &transformed_path,
+                variant_options.feature.as_ref(),
+            )
+            .into_iter()
+            .map(|(cfg, alias)| {
+                let tag_items = tag_items(variant_name);
+                quote! {
+                    #cfg
+                    #type_name::#variant_name => {
+                        #alias
+                        #tag_items
+                        $callback! { $($args)* [$($name,)* $($suffix,)* #name] }
+                    }
+                }
+            })
+            .collect()
+        })
+        .collect::<Vec<_>>();
+    let named_rule = (!options.partial).then(|| {
+        quote! {
+            (
+                @named $enum_instance:expr; $type_param:ident; [$($name:literal),*]
+                $($suffix:literal),* => $callback:path { $($args:tt)* }
+            ) => {
+                match $enum_instance {
+                    #(#named_arms),*
+                }
+            };
+        }
+    });
+
+    // With `#[concrete_all = "..."]` variants, the `@all` and `@fold` forms run the block once per
+    // concrete type of the matched variant's cluster, in the order listed, handing each value to
+    // `statement`. Other variants run it once, nested ones through their inner enum's default form
+    let has_clusters = variant_mappings
+        .iter()
+        .any(|(_, _, variant_options)| !variant_options.cluster.is_empty());
+    let cluster_arms =
+        |statement: &dyn Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream| {
+            variant_mappings
+                .iter()
+                .map(|(variant_name, concrete_type, variant_options)| {
+                    if let Some((_, inner_macro)) = nested_arms
+                        .iter()
+                        .find(|(nested_name, _)| nested_name == variant_name)
+                    {
+                        let statement = statement(quote! {
+                            #inner_macro!(inner; $type_param => $code_block)
+                        });
+                        return quote! {
+                            #type_name::#variant_name(inner) => { #statement }
+                        };
+                    }
+                    let concrete_types = if variant_options.cluster.is_empty() {
+                        std::slice::from_ref(concrete_type)
+                    } else {
+                        &variant_options.cluster[..]
+                    };
+                    let statements = concrete_types.iter().flat_map(|concrete_type| {
+                        let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+                        concrete_type_aliases(
+                            type_name,
+                            variant_name,
+                            concrete_type,
+                            &transformed_path,
+                            variant_options.feature.as_ref(),
+                        )
+                        .into_iter()
+                        .map(|(cfg, alias)| {
+                            let tag_items = tag_items(variant_name);
+                            let statement = statement(quote! {{
+                                #alias
+                                #tag_items
+                                $code_block
+                            }});
+                            quote! { #cfg #statement }
+                        })
+                        .collect::<Vec<_>>()
+                    });
+                    quote! {
+                        #type_name::#variant_name => { #(#statements)* }
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+    let cluster_rules = has_clusters.then(|| {
+        let all_arms = cluster_arms(&|value| quote! { results.push(#value); });
+        let fold_arms = cluster_arms(&|value| quote! { $acc = #value; });
+        quote! {
+            (@all $enum_instance:expr; $type_param:ident => $code_block:block) => {{
+                let mut results = ::std::vec::Vec::new();
+                match $enum_instance {
+                    #(#all_arms),*
+                }
+                results
+            }};
+            (
+                @fold $enum_instance:expr, $init:expr;
+                ($acc:ident, $type_param:ident) => $code_block:block
+            ) => {{
+                let mut $acc = $init;
+                match $enum_instance {
+                    #(#fold_arms),*
+                }
+                $acc
+            }};
+        }
+    });
+
+    // Generate a top-level macro with the snake_case name of the enum
+    let dispatch_macro = DispatchMacro {
+        name: macro_name.clone(),
+        rules: quote! {
+            ($enum_instance:expr; $type_param:ident => $code_block:block) => {
+                #default_rule
+            };
+            #for_each_rule
+            #named_rule
+            #cluster_rules
+            #failover_rule
+            #try_rule
+            (
+                $enum_instance:expr; $type_param:ident < $($type_arg:ty),+ > => $code_block:block
+            ) => {
+                match $enum_instance {
+                    #(#turbofish_arms),*
+                }
+            };
+            (
+                $enum_instance:expr; $type_param:ident => $code_block:block
+                where |$guard_param:ident| $guard:expr
+            ) => {{
+                let value = &$enum_instance;
+                match value {
+                    #(#guarded_arms),*
+                }
+            }};
+            (
+                $enum_instance:expr; [$($variant:ident),+ $(,)?];
+                $type_param:ident => $code_block:block else $else_block:block
+            ) => {{
+                macro_rules! __concrete_variant {
+                    #(#subset_rules)*
+                    ($unknown:ident) => {
+                        ::core::compile_error!(::core::concat!(
+                            #unknown_variant_prefix,
+                            ::core::stringify!($unknown),
+                            "`"
+                        ))
+                    };
+                }
+                #[allow(unreachable_patterns)]
+                match $enum_instance {
+                    $(#type_name::$variant { .. } => __concrete_variant!($variant),)+
+                    _ => $else_block
+                }
+            }};
+            (
+                @flatten $enum_instance:expr; $type_param:ident => $code_block:block;
+                ($($outer_pattern:tt)*) [$($arms:tt)*]
+            ) => {
+                match $enum_instance {
+                    $($arms)*
+                    #(#flatten_arms,)*
+                }
+            };
+            (
+                @flatten $enum_instance:expr; $type_param:ident => $code_block:block;
+                ($($outer_pattern:tt)*) [$($arms:tt)*]
+                [$($next_macro:tt)+] ($($next_pattern:tt)*) $($rest:tt)*
+            ) => {
+                $($next_macro)+!(
+                    @flatten $enum_instance; $type_param => $code_block;
+                    ($($next_pattern)*) [$($arms)* #(#flatten_arms,)*] $($rest)*
+                )
+            };
+            #(#variant_arms)*
+            #registry_rule
+            (@impls $type_param:ident; { $($item:tt)* }) => {
+                #(#impls_items)*
+            };
+            (@variant $variant:ident; $($tokens:tt)*) => {
+                ::core::compile_error!(::core::concat!(
+                    #unknown_variant_prefix,
+                    ::core::stringify!($variant),
+                    "`"
+                ))
+            };
+            (@arm_count) => {
+                #shared_arm_count #(#nested_arm_counts)*
+            };
+            (@max_combinations) => {
+                #max_combinations
+            };
+        },
+        summary: format!(
+            "Dispatches on a `{}` value, aliasing the concrete type of the matched variant.",
+            type_name
+        ),
+        usage: "value; Type => { ... }",
+    };
+    Ok(emit_dispatch_macro(
+        std::iter::once(dispatch_macro)
+            .chain(for_each_macro)
+            .collect(),
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, variant_options)| {
+                (*variant_name, concrete_type, variant_options)
+            })
+            .collect::<Vec<_>>(),
+        options,
+    ))
+}
@@ -0,0 +1,356 @@
+//! Dispatch macros of `ConcreteConfig` enums.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::dispatch::{
+    DispatchMacro, concrete_type_aliases, dispatch_macro_name, emit_dispatch_macro,
+    replace_macro_vars,
+};
+use crate::generate::config::type_mentions_generics;
+use crate::options::{ConfigField, EnumOptions, VariantOptions};
+use crate::paths::{macro_crate_root, source_string, transform_path_for_macro, transform_type};
+
+/// Generates the dispatch macro of a `ConcreteConfig` enum, along with its
+/// `compare_{enum}_configs!` macro with `#[concrete(compare)]` and its
+/// `fallback_{enum}_configs!` macro with `#[concrete(fallback)]`.
+pub(crate) fn generate_config_macros(
+    input: &DeriveInput,
+    base_name: &str,
+    macro_name: syn::Ident,
+    variant_mappings: &[(&syn::Ident, syn::Path, ConfigField, VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let type_name = &input.ident;
+
+    // Generate match arms for the macro_rules! version. `else_block` is the code run when an
+    // `Option` config is `None`; without it, the config defaults via `Default`. `checked` arms
+    // evaluate to a `Result`, failing when an erased config isn't of the declared type instead of
+    // panicking
+    let crate_root = macro_crate_root(options);
+    let mismatch_name = syn::Ident::new(&format!("{}TypeMismatch", type_name), type_name.span());
+    // Unboxes a `Box` config, or reborrows its content when the enum is matched by reference
+    let unbox = quote! {
+        {
+            trait Unbox {
+                type Config;
+                fn unbox(self) -> Self::Config;
+            }
+            impl<T> Unbox for ::std::boxed::Box<T> {
+                type Config = T;
+                fn unbox(self) -> T {
+                    *self
+                }
+            }
+            impl<'a, T> Unbox for &'a ::std::boxed::Box<T> {
+                type Config = &'a T;
+                fn unbox(self) -> &'a T {
+                    &**self
+                }
+            }
+            impl<'a, T> Unbox for &'a mut ::std::boxed::Box<T> {
+                type Config = &'a mut T;
+                fn unbox(self) -> &'a mut T {
+                    &mut **self
+                }
+            }
+            Unbox::unbox(config)
+        }
+    };
+    let macro_match_arms = |else_block: Option<proc_macro2::TokenStream>, checked: bool| {
+        let code_block = if checked {
+            quote! { ::core::result::Result::Ok($code_block) }
+        } else {
+            quote! { $code_block }
+        };
+        variant_mappings
+            .iter()
+            .flat_map(|(variant_name, concrete_type, config_field, variant_options)| {
+                let transformed_path = transform_path_for_macro(concrete_type, &crate_root);
+                concrete_type_aliases(
+                    type_name,
+                    variant_name,
+                    concrete_type,
+                    &transformed_path,
+                    variant_options.feature.as_ref(),
+                )
+                    .into_iter()
+                    .map(|(cfg, alias)| match config_field {
+                        ConfigField::Plain(_) | ConfigField::Borrowed(_) => quote! {
+                            #cfg
+                            #type_name::#variant_name(config) => {
+                                #alias
+                                let $config_param = config;
+                                #code_block
+                            }
+                        },
+                        ConfigField::Boxed(_) => quote! {
+                            #cfg
+                            #type_name::#variant_name(config) => {
+                                #alias
+                                let $config_param = #unbox;
+                                #code_block
+                            }
+                        },
+                        ConfigField::Erased(_) => {
+                            let config_type = variant_options
+                                .config
+                                .as_ref()
+                                .expect("erased configs declare their type");
+                            let expected = source_string(quote! { #config_type });
+                            let config_type = transform_type(config_type, &crate_root);
+                            let downcast = quote! {
+                                <::std::boxed::Box<dyn ::core::any::Any>>::downcast::<#config_type>(config)
+                            };
+                            let variant_str = variant_name.to_string();
+                            let bound = if checked {
+                                quote! {
+                                    match #downcast {
+                                        ::core::result::Result::Ok(config) => {
+                                            let $config_param = *config;
+                                            #code_block
+                                        }
+                                        ::core::result::Result::Err(_) => {
+                                            ::core::result::Result::Err(
+                                                #mismatch_name::new(#variant_str, #expected),
+                                            )
+                                        }
+                                    }
+                                }
+                            } else {
+                                let message = format!(
+                                    "`{}::{}` holds a config that isn't a `{}`",
+                                    type_name, variant_name, expected
+                                );
+                                quote! {
+                                    let $config_param = *#downcast
+                                        .unwrap_or_else(|_| ::core::panic!(#message));
+                                    #code_block
+                                }
+                            };
+                            quote! {
+                                #cfg
+                                #type_name::#variant_name(config) => {
+                                    #alias
+                                    #bound
+                                }
+                            }
+                        }
+                        ConfigField::Optional(inner) => {
+                            let none_body = match &else_block {
+                                // The concrete type alias isn't available in the else block
+                                Some(else_block) => else_block.clone(),
+                                // Enum type parameters can't be named at the call site, so
+                                // configs using them rely on inference from the code block
+                                None if type_mentions_generics(inner, &input.generics) => quote! {
+                                    #alias
+                                    let $config_param = ::core::default::Default::default();
+                                    #code_block
+                                },
+                                None => {
+                                    let inner = transform_type(inner, &crate_root);
+                                    quote! {
+                                        #alias
+                                        let $config_param = <#inner as ::core::default::Default>::default();
+                                        #code_block
+                                    }
+                                }
+                            };
+                            quote! {
+                                #cfg
+                                #type_name::#variant_name(::core::option::Option::Some(config)) => {
+                                    #alias
+                                    let $config_param = config;
+                                    #code_block
+                                },
+                                #cfg
+                                #type_name::#variant_name(::core::option::Option::None) => {
+                                    #none_body
+                                }
+                            }
+                        }
+                        ConfigField::Unit => quote! {
+                            #cfg
+                            #type_name::#variant_name => {
+                                #alias
+                                let $config_param = (); // Use unit type
+                                #code_block
+                            }
+                        },
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    };
+    let default_arms = macro_match_arms(None, false);
+
+    // Enums with `Option` configs also accept an `else` block for the `None` case
+    let has_optional = variant_mappings
+        .iter()
+        .any(|(_, _, config_field, _)| matches!(config_field, ConfigField::Optional(_)));
+    let else_rule = has_optional.then(|| {
+        let else_arms = macro_match_arms(Some(quote! { $else_block }), false);
+        quote! {
+            ($enum_instance:expr; ($type_param:ident, $config_param:pat) => $code_block:block else $else_block:block) => {
+                match $enum_instance {
+                    #(#else_arms),*
+                }
+            };
+        }
+    });
+
+    // Erased configs can also be dispatched without panicking on a config of the wrong type
+    let has_erased = variant_mappings
+        .iter()
+        .any(|(_, _, config_field, _)| matches!(config_field, ConfigField::Erased(_)));
+    let checked_rule = has_erased.then(|| {
+        let checked_arms = macro_match_arms(None, true);
+        quote! {
+            (@checked $enum_instance:expr; ($type_param:ident, $config_param:pat) => $code_block:block) => {
+                match $enum_instance {
+                    #(#checked_arms),*
+                }
+            };
+        }
+    });
+
+    // With #[concrete(compare)], two values are compared by nesting the default arms, each bound to its own names. The
+    // macro doesn't call the dispatch macro, which may not be reachable by name from the caller
+    let compare_macro = if options.compare {
+        let compare_name = dispatch_macro_name(
+            &format!("compare_{}_configs", base_name.to_case(Case::Snake)),
+            type_name,
+            options,
+        )?;
+        let bind_arms =
+            |type_param: &str, config_param: &str, code_block: proc_macro2::TokenStream| {
+                let arms = quote! { #(#default_arms),* };
+                replace_macro_vars(arms, &|ident| {
+                    let renamed = if ident == "type_param" {
+                        type_param
+                    } else if ident == "config_param" {
+                        config_param
+                    } else if ident == "code_block" {
+                        return Some(code_block.clone());
+                    } else {
+                        return None;
+                    };
+                    let renamed = syn::Ident::new(renamed, ident.span());
+                    Some(quote! { $#renamed })
+                })
+            };
+        let second_arms = bind_arms("second_type", "second_config", quote! { $code_block });
+        let first_arms = bind_arms(
+            "first_type",
+            "first_config",
+            quote! {
+                match $second {
+                    #second_arms
+                }
+            },
+        );
+        Some(DispatchMacro {
+            name: compare_name,
+            rules: quote! {
+                (
+                    $first:expr, $second:expr;
+                    ($first_type:ident, $first_config:pat), ($second_type:ident, $second_config:pat)
+                    => $code_block:block
+                ) => {
+                    match $first {
+                        #first_arms
+                    }
+                };
+            },
+            summary: format!(
+                "Dispatches on two `{}` values at once, aliasing the concrete types and binding the \
+                 configs of both matched variants, e.g. to reconcile two backend configurations.",
+                type_name
+            ),
+            usage: "first, second; (FirstType, first_config), (SecondType, second_config) => { ... }",
+        })
+    } else {
+        None
+    };
+
+    // With #[concrete(fallback)], a block returning a `Result` is run against the primary value,
+    // and against the secondary one only if that fails
+    let fallback_macro = if options.fallback {
+        let fallback_name = dispatch_macro_name(
+            &format!("fallback_{}_configs", base_name.to_case(Case::Snake)),
+            type_name,
+            options,
+        )?;
+        let fallback_type = syn::Ident::new(&format!("{}Fallback", type_name), type_name.span());
+        let fallback_error =
+            syn::Ident::new(&format!("{}FallbackError", type_name), type_name.span());
+        Some(DispatchMacro {
+            name: fallback_name,
+            rules: quote! {
+                (
+                    $primary:expr, $secondary:expr;
+                    ($type_param:ident, $config_param:pat) => $code_block:block
+                ) => {
+                    match match $primary { #(#default_arms),* } {
+                        ::core::result::Result::Ok(value) => {
+                            ::core::result::Result::Ok(#fallback_type::Primary(value))
+                        }
+                        ::core::result::Result::Err(primary_error) => {
+                            match match $secondary { #(#default_arms),* } {
+                                ::core::result::Result::Ok(value) => {
+                                    ::core::result::Result::Ok(#fallback_type::Secondary(value))
+                                }
+                                ::core::result::Result::Err(secondary_error) => {
+                                    ::core::result::Result::Err(
+                                        #fallback_error::new(primary_error, secondary_error),
+                                    )
+                                }
+                            }
+                        }
+                    }
+                };
+            },
+            summary: format!(
+                "Dispatches on a primary `{}` value and, if the block returns an error, on a \
+                 secondary one, e.g. for hot/warm backend failover.",
+                type_name
+            ),
+            usage: "primary, secondary; (Type, config) => { ... }",
+        })
+    } else {
+        None
+    };
+
+    // Generate a top-level macro with the snake_case name of the enum + "_config"
+    let dispatch_macro = DispatchMacro {
+        name: macro_name,
+        rules: quote! {
+            ($enum_instance:expr; ($type_param:ident, $config_param:pat) => $code_block:block) => {
+                match $enum_instance {
+                    #(#default_arms),*
+                }
+            };
+            #else_rule
+            #checked_rule
+        },
+        summary: format!(
+            "Dispatches on a `{}` value, aliasing the concrete type of the matched variant.",
+            type_name
+        ),
+        usage: "value; (Type, config) => { ... }",
+    };
+    Ok(emit_dispatch_macro(
+        std::iter::once(dispatch_macro)
+            .chain(compare_macro)
+            .chain(fallback_macro)
+            .collect(),
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _, variant_options)| {
+                (*variant_name, concrete_type, variant_options)
+            })
+            .collect::<Vec<_>>(),
+        options,
+    ))
+}
@@ -0,0 +1,14 @@
+//! Generators of the items requested with enum options, grouped by feature.
+
+pub(crate) mod checks;
+pub(crate) mod config;
+pub(crate) mod describe;
+pub(crate) mod encoding;
+pub(crate) mod errors;
+pub(crate) mod factory;
+pub(crate) mod methods;
+pub(crate) mod names;
+pub(crate) mod selection;
+pub(crate) mod sets;
+pub(crate) mod subset;
+pub(crate) mod types;
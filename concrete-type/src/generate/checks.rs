@@ -0,0 +1,283 @@
+//! Generated self-tests and health checks.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+
+use crate::dispatch::concrete_type_expr;
+use crate::generate::names::variant_names_with_aliases;
+use crate::options::{EnumOptions, VariantOptions};
+
+/// Generates a `#[cfg(test)]` module named `{enum}_selftest`, checking that the methods generated
+/// for an enum of unit variants agree with each other and with the mapping: variant indices, tags
+/// and names round-trip, and each variant reports the `TypeId` of its concrete type.
+///
+/// The module imports the enum from its parent, so the enum must be declared at module level.
+pub(crate) fn generate_selftest(
+    type_name: &syn::Ident,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let module_name = syn::Ident::new(
+        &format!("{}_selftest", type_name.to_string().to_case(Case::Snake)),
+        type_name.span(),
+    );
+    let variant_names: Vec<_> = variant_mappings
+        .iter()
+        .map(|(variant_name, _, _)| *variant_name)
+        .collect();
+    let indices = 0..variant_names.len();
+
+    let tags_test = variant_mappings
+        .iter()
+        .any(|(_, _, variant_options)| variant_options.tag.is_some())
+        .then(|| {
+            let tagged =
+                variant_mappings
+                    .iter()
+                    .filter_map(|(variant_name, _, variant_options)| {
+                        let tag = variant_options.tag.as_ref()?;
+                        Some(quote! {
+                            ::core::assert!(::core::matches!(
+                                #type_name::from_tag(#tag),
+                                ::core::option::Option::Some(#type_name::#variant_name)
+                            ));
+                        })
+                    });
+            quote! {
+                #[test]
+                fn tags() {
+                    let mut tags: ::std::vec::Vec<u64> = [#(#type_name::#variant_names),*]
+                        .iter()
+                        .filter_map(#type_name::tag)
+                        .collect();
+                    let tag_count = tags.len();
+                    tags.sort_unstable();
+                    tags.dedup();
+                    ::core::assert_eq!(tags.len(), tag_count, "duplicate tags");
+                    #(#tagged)*
+                }
+            }
+        });
+
+    let names_test = if options.from_name {
+        let names = variant_names_with_aliases(
+            variant_mappings
+                .iter()
+                .map(|(variant_name, _, variant_options)| (*variant_name, variant_options)),
+        )?;
+        let lookups = names.iter().map(|(name, variant_name)| {
+            quote! {
+                ::core::assert!(::core::matches!(
+                    #type_name::from_name(#name),
+                    ::core::option::Option::Some(#type_name::#variant_name)
+                ));
+                ::core::assert!(::core::matches!(
+                    #name.parse::<#type_name>(),
+                    ::core::result::Result::Ok(#type_name::#variant_name)
+                ));
+            }
+        });
+        Some(quote! {
+            #[test]
+            fn names() {
+                #(#lookups)*
+            }
+        })
+    } else {
+        None
+    };
+
+    // `TypeId`s need complete concrete types
+    let concrete_types_test = (!options.partial).then(|| {
+        let type_ids =
+            variant_mappings
+                .iter()
+                .map(|(variant_name, concrete_type, variant_options)| {
+                    let type_id = concrete_type_expr(
+                        type_name,
+                        variant_name,
+                        concrete_type,
+                        variant_options.feature.as_ref(),
+                        |ty| quote! { ::std::any::TypeId::of::<#ty>() },
+                    );
+                    quote! {
+                        ::core::assert_eq!(#type_name::#variant_name.concrete_type_id(), #type_id);
+                    }
+                });
+        quote! {
+            #[test]
+            fn concrete_types() {
+                #(#type_ids)*
+                for (variant, _, type_id) in #type_name::iter_with_types() {
+                    ::core::assert_eq!(variant.concrete_type_id(), type_id);
+                }
+            }
+        }
+    });
+
+    let handles_test = options.handle.then(|| {
+        let variant_strs = variant_names
+            .iter()
+            .map(|variant_name| variant_name.to_string());
+        quote! {
+            #[test]
+            fn handles() {
+                #(
+                    let handle = #type_name::#variant_names.handle();
+                    ::core::assert_eq!(handle.name, #variant_strs);
+                    ::core::assert!(::core::matches!(handle.kind(), #type_name::#variant_names));
+                )*
+            }
+        }
+    });
+
+    let priorities_test = variant_mappings
+        .iter()
+        .any(|(_, _, variant_options)| variant_options.priority.is_some())
+        .then(|| {
+            quote! {
+                #[test]
+                fn priorities() {
+                    let mut indices: ::std::vec::Vec<usize> = #type_name::iter_by_priority()
+                        .map(|variant| variant.variant_index())
+                        .collect();
+                    indices.sort_unstable();
+                    ::core::assert_eq!(
+                        indices,
+                        (0..#type_name::VARIANT_COUNT).collect::<::std::vec::Vec<_>>()
+                    );
+                }
+            }
+        });
+
+    Ok(quote! {
+        #[cfg(test)]
+        mod #module_name {
+            use super::*;
+
+            #[test]
+            fn variant_indices() {
+                #(
+                    ::core::assert_eq!(#type_name::#variant_names.variant_index(), #indices);
+                    ::core::assert!(::core::matches!(
+                        #type_name::from_index(#indices),
+                        ::core::option::Option::Some(#type_name::#variant_names)
+                    ));
+                )*
+                ::core::assert!(#type_name::from_index(#type_name::VARIANT_COUNT).is_none());
+            }
+
+            #tags_test
+
+            #names_test
+
+            #concrete_types_test
+
+            #handles_test
+
+            #priorities_test
+        }
+    })
+}
+
+/// Generates the `{Enum}Probe` trait and the `check_all` method running a probe against the
+/// concrete type of every variant concurrently, for an enum of unit variants.
+///
+/// Variants whose feature is disabled are skipped. The probes are polled together by the returned
+/// future, so `check_all` works on any executor without spawning tasks.
+pub(crate) fn generate_check_all(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let probe_name = syn::Ident::new(&format!("{}Probe", type_name), type_name.span());
+    let bound = match &options.probe_bound {
+        Some(bound) => quote! { #bound + 'static },
+        None => quote! { 'static },
+    };
+    let doc_hidden = options.doc_hidden_attr();
+    let probe_doc = format!(
+        "A probe run against the concrete type of every [`{}`] variant by [`{}::check_all`].",
+        type_name, type_name
+    );
+
+    let checks = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            let check = quote! {
+                checks.push((
+                    Self::#variant_name,
+                    ::std::boxed::Box::pin(probe.probe::<#concrete_type>(Self::#variant_name)),
+                ));
+            };
+            match &variant_options.feature {
+                None => check,
+                Some(feature) => quote! {
+                    #[cfg(feature = #feature)]
+                    #check
+                },
+            }
+        });
+
+    quote! {
+        #[doc = #probe_doc]
+        #doc_hidden
+        #vis trait #probe_name {
+            /// Error reported by a failed probe.
+            type Error;
+
+            /// Checks the concrete type `T` of `variant`, e.g. that its backend is reachable.
+            fn probe<T: #bound>(
+                &self,
+                variant: #type_name,
+            ) -> impl ::std::future::Future<Output = ::core::result::Result<(), Self::Error>>;
+        }
+
+        impl #type_name {
+            /// Runs `probe` against the concrete type of every variant concurrently, returning
+            /// each variant with its result once all probes have completed.
+            #doc_hidden
+            pub async fn check_all<P: #probe_name>(
+                probe: &P,
+            ) -> ::std::vec::Vec<(Self, ::core::result::Result<(), P::Error>)> {
+                #[allow(clippy::type_complexity)]
+                let mut checks: ::std::vec::Vec<(
+                    Self,
+                    ::std::pin::Pin<::std::boxed::Box<dyn ::std::future::Future<Output = ::core::result::Result<(), P::Error>> + '_>>,
+                )> = ::std::vec::Vec::new();
+                #(#checks)*
+
+                let mut results: ::std::vec::Vec<
+                    ::core::option::Option<::core::result::Result<(), P::Error>>,
+                > =
+                    checks.iter().map(|_| ::core::option::Option::None).collect();
+                ::std::future::poll_fn(|cx| {
+                    let mut pending = false;
+                    for ((_, check), result) in checks.iter_mut().zip(results.iter_mut()) {
+                        if result.is_none() {
+                            match ::std::future::Future::poll(check.as_mut(), cx) {
+                                ::std::task::Poll::Ready(outcome) => {
+                                    *result = ::core::option::Option::Some(outcome)
+                                }
+                                ::std::task::Poll::Pending => pending = true,
+                            }
+                        }
+                    }
+                    if pending {
+                        ::std::task::Poll::Pending
+                    } else {
+                        ::std::task::Poll::Ready(())
+                    }
+                })
+                .await;
+
+                checks
+                    .into_iter()
+                    .zip(results)
+                    .map(|((variant, _), result)| (variant, result.unwrap()))
+                    .collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Items generated for `ConcreteConfig` enums from their configs.
+
+use quote::quote;
+use syn::{DeriveInput, Fields};
+
+use crate::options::{ConfigField, EnumOptions, VariantOptions};
+
+/// Generates the `#[concrete(either)]` items for a config enum with exactly two variants:
+/// `as_either`, `as_either_mut` and `into_either` returning the config of the variant as an
+/// `either::Either`, `From<Either<..>>` building the enum back, and `either` dispatching to one of
+/// two closures.
+///
+/// Configs are exposed with their declared field types, unit variants as `()`.
+pub(crate) fn generate_either(
+    input: &DeriveInput,
+    data_enum: &syn::DataEnum,
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let type_name = &input.ident;
+    if !cfg!(feature = "either") {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`either` requires the `either` feature of `concrete-type`",
+        ));
+    }
+    let [left, right] = match data_enum.variants.iter().collect::<Vec<_>>()[..] {
+        [left, right] => [left, right],
+        _ => {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                format!(
+                    "`either` requires exactly two variants, `{}` has {}",
+                    type_name,
+                    data_enum.variants.len()
+                ),
+            ));
+        }
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc_hidden = options.doc_hidden_attr();
+
+    let config_type = |variant: &syn::Variant| match variant.fields.iter().next() {
+        Some(field) => {
+            let ty = &field.ty;
+            quote! { #ty }
+        }
+        None => quote! { () },
+    };
+    let left_type = config_type(left);
+    let right_type = config_type(right);
+    // Patterns binding the config of each variant as `config`, or `()` for unit variants
+    let pattern = |variant: &syn::Variant| {
+        let variant_name = &variant.ident;
+        match variant.fields {
+            Fields::Unit => (quote! { #type_name::#variant_name }, quote! { () }),
+            _ => (
+                quote! { #type_name::#variant_name(config) },
+                quote! { config },
+            ),
+        }
+    };
+    let (left_pat, left_config) = pattern(left);
+    let (right_pat, right_config) = pattern(right);
+    // Unit configs are borrowed from a promoted `()`, or mutably from a leaked one, which
+    // doesn't allocate as `()` is zero-sized
+    let borrowed = |variant: &syn::Variant, config: &proc_macro2::TokenStream, mutable: bool| match (
+        &variant.fields,
+        mutable,
+    ) {
+        (Fields::Unit, false) => quote! { &() },
+        (Fields::Unit, true) => quote! { ::std::boxed::Box::leak(::std::boxed::Box::new(())) },
+        _ => config.clone(),
+    };
+    let left_ref = borrowed(left, &left_config, false);
+    let right_ref = borrowed(right, &right_config, false);
+    let left_mut = borrowed(left, &left_config, true);
+    let right_mut = borrowed(right, &right_config, true);
+    let construct = |variant: &syn::Variant| {
+        let variant_name = &variant.ident;
+        match variant.fields {
+            Fields::Unit => quote! { |()| #type_name::#variant_name },
+            _ => quote! { #type_name::#variant_name },
+        }
+    };
+    let left_construct = construct(left);
+    let right_construct = construct(right);
+    let left_name = &left.ident;
+    let right_name = &right.ident;
+    let either_doc = format!(
+        "Calls `left` with the config of a `{}` value and `right` with the config of a `{}` \
+         value, dispatching without a macro.",
+        left_name, right_name
+    );
+
+    Ok(quote! {
+        impl #impl_generics #type_name #ty_generics #where_clause {
+            /// Returns the config of the variant, the first variant's on the left and the second
+            /// variant's on the right.
+            #doc_hidden
+            pub fn as_either(&self) -> ::either::Either<&#left_type, &#right_type> {
+                match self {
+                    #left_pat => ::either::Either::Left(#left_ref),
+                    #right_pat => ::either::Either::Right(#right_ref),
+                }
+            }
+
+            /// Returns the config of the variant mutably, the first variant's on the left and the
+            /// second variant's on the right.
+            #doc_hidden
+            pub fn as_either_mut(&mut self) -> ::either::Either<&mut #left_type, &mut #right_type> {
+                match self {
+                    #left_pat => ::either::Either::Left(#left_mut),
+                    #right_pat => ::either::Either::Right(#right_mut),
+                }
+            }
+
+            /// Converts the value into the config of the variant, the first variant's on the left
+            /// and the second variant's on the right.
+            #doc_hidden
+            pub fn into_either(self) -> ::either::Either<#left_type, #right_type> {
+                match self {
+                    #left_pat => ::either::Either::Left(#left_config),
+                    #right_pat => ::either::Either::Right(#right_config),
+                }
+            }
+
+            #[doc = #either_doc]
+            #doc_hidden
+            pub fn either<T>(
+                self,
+                left: impl ::core::ops::FnOnce(#left_type) -> T,
+                right: impl ::core::ops::FnOnce(#right_type) -> T,
+            ) -> T {
+                match self {
+                    #left_pat => left(#left_config),
+                    #right_pat => right(#right_config),
+                }
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<::either::Either<#left_type, #right_type>>
+            for #type_name #ty_generics #where_clause
+        {
+            fn from(either: ::either::Either<#left_type, #right_type>) -> Self {
+                either.either(#left_construct, #right_construct)
+            }
+        }
+    })
+}
+
+/// Generates the `#[concrete(derive(...))]` implementations for a config enum.
+///
+/// Each implementation requires every config type to implement the trait. The bounds are
+/// higher-ranked so that they are not rejected as trivially false for non-generic config types:
+/// the implementation is then simply missing instead of failing to compile.
+///
+/// The configs of `secret` variants are written as `<redacted>` by `Debug` and `Serialize`, so
+/// they don't need to implement those, but they do need to implement `Clone`. `Serialize` comes with a `serialize_unredacted` method
+/// writing them out, bounded on every config type implementing `Serialize`.
+pub(crate) fn generate_forwarded_derives(
+    input: &DeriveInput,
+    data_enum: &syn::DataEnum,
+    secrets: &[&syn::Ident],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let type_name = &input.ident;
+    let type_str = type_name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let predicates = where_clause
+        .map(|where_clause| where_clause.predicates.iter().collect())
+        .unwrap_or_else(Vec::new);
+    let config_types = |redacted: bool| -> Vec<&syn::Type> {
+        data_enum
+            .variants
+            .iter()
+            .filter(|variant| !(redacted && secrets.contains(&&variant.ident)))
+            .filter_map(|variant| match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(&fields.unnamed[0].ty),
+                _ => None,
+            })
+            .collect()
+    };
+    let redacted = "<redacted>";
+
+    // Serializes every variant externally tagged, `redact` replacing the configs of secrets
+    let serialize_arms = |redact: bool| {
+        data_enum
+            .variants
+            .iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let variant_name = &variant.ident;
+                let variant_str = variant_name.to_string();
+                let index = index as u32;
+                match &variant.fields {
+                    Fields::Unit => quote! {
+                        Self::#variant_name => {
+                            serializer.serialize_unit_variant(#type_str, #index, #variant_str)
+                        }
+                    },
+                    _ if redact && secrets.contains(&variant_name) => quote! {
+                        Self::#variant_name(_) => serializer.serialize_newtype_variant(
+                            #type_str, #index, #variant_str, #redacted,
+                        )
+                    },
+                    _ => quote! {
+                        Self::#variant_name(config) => serializer.serialize_newtype_variant(
+                            #type_str, #index, #variant_str, config,
+                        )
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let impls = options.derives.iter().map(|derive_trait| {
+        let (trait_path, body) = if derive_trait == "Clone" {
+            let clone_arms = data_enum.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                match &variant.fields {
+                    Fields::Unit => quote! { Self::#variant_name => Self::#variant_name },
+                    _ => quote! {
+                        Self::#variant_name(config) => {
+                            Self::#variant_name(::std::clone::Clone::clone(config))
+                        }
+                    },
+                }
+            });
+            (
+                quote! { ::std::clone::Clone },
+                quote! {
+                    fn clone(&self) -> Self {
+                        match self {
+                            #(#clone_arms),*
+                        }
+                    }
+                },
+            )
+        } else if derive_trait == "Serialize" {
+            let serialize_arms = serialize_arms(true);
+            (
+                quote! { ::serde::Serialize },
+                quote! {
+                    fn serialize<S: ::serde::Serializer>(
+                        &self,
+                        serializer: S,
+                    ) -> ::core::result::Result<S::Ok, S::Error> {
+                        match self {
+                            #(#serialize_arms),*
+                        }
+                    }
+                },
+            )
+        } else {
+            let debug_arms = data_enum.variants.iter().map(|variant| {
+                let variant_name = &variant.ident;
+                let variant_str = variant_name.to_string();
+                match &variant.fields {
+                    Fields::Unit => quote! { Self::#variant_name => f.write_str(#variant_str) },
+                    _ if secrets.contains(&variant_name) => quote! {
+                        Self::#variant_name(_) => f
+                            .debug_tuple(#variant_str)
+                            .field(&format_args!(#redacted))
+                            .finish()
+                    },
+                    _ => quote! {
+                        Self::#variant_name(config) => {
+                            f.debug_tuple(#variant_str).field(config).finish()
+                        }
+                    },
+                }
+            });
+            (
+                quote! { ::std::fmt::Debug },
+                quote! {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        match self {
+                            #(#debug_arms),*
+                        }
+                    }
+                },
+            )
+        };
+
+        // `Clone` copies the configs of secret variants too, so only the others leave them out
+        let config_types = config_types(derive_trait != "Clone");
+        quote! {
+            impl #impl_generics #trait_path for #type_name #ty_generics
+            where
+                #(#predicates,)*
+                #(for<'__concrete> #config_types: #trait_path,)*
+            {
+                #body
+            }
+        }
+    });
+
+    // The escape hatch writing the configs of secret variants out
+    let unredacted = options
+        .derives
+        .iter()
+        .any(|derive_trait| derive_trait == "Serialize")
+        .then(|| {
+            let config_types = config_types(false);
+            let serialize_arms = serialize_arms(false);
+            let doc_hidden = options.doc_hidden_attr();
+            quote! {
+                impl #impl_generics #type_name #ty_generics
+                where
+                    #(#predicates,)*
+                    #(for<'__concrete> #config_types: ::serde::Serialize,)*
+                {
+                    /// Serializes the value like its `Serialize` implementation, but with the
+                    /// configs of `secret` variants written out instead of redacted.
+                    ///
+                    /// Also usable as `#[serde(serialize_with = "...")]` on fields holding the
+                    /// enum.
+                    #doc_hidden
+                    pub fn serialize_unredacted<S: ::serde::Serializer>(
+                        &self,
+                        serializer: S,
+                    ) -> ::core::result::Result<S::Ok, S::Error> {
+                        match self {
+                            #(#serialize_arms),*
+                        }
+                    }
+                }
+            }
+        });
+
+    quote! {
+        #(#impls)*
+        #unredacted
+    }
+}
+
+/// Returns true if `ty` mentions the lifetime `lifetime`.
+pub(crate) fn type_mentions_lifetime(ty: &syn::Type, lifetime: &syn::Lifetime) -> bool {
+    fn mentions(tokens: proc_macro2::TokenStream, lifetime: &syn::Ident) -> bool {
+        let mut after_apostrophe = false;
+        tokens.into_iter().any(|token| {
+            let found = match &token {
+                proc_macro2::TokenTree::Ident(ident) => after_apostrophe && ident == lifetime,
+                proc_macro2::TokenTree::Group(group) => mentions(group.stream(), lifetime),
+                _ => false,
+            };
+            after_apostrophe =
+                matches!(&token, proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '\'');
+            found
+        })
+    }
+
+    mentions(quote! { #ty }, &lifetime.ident)
+}
+
+/// Returns true if `ty` mentions any of the type parameters declared in `generics`.
+pub(crate) fn type_mentions_generics(ty: &syn::Type, generics: &syn::Generics) -> bool {
+    fn mentions(tokens: proc_macro2::TokenStream, params: &[&syn::Ident]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => params.contains(&&ident),
+            proc_macro2::TokenTree::Group(group) => mentions(group.stream(), params),
+            _ => false,
+        })
+    }
+
+    let params: Vec<_> = generics.type_params().map(|param| &param.ident).collect();
+    !params.is_empty() && mentions(quote! { #ty }, &params)
+}
+
+/// Generates the `config` method, returning a variant's config as `&dyn Any`.
+pub(crate) fn generate_config_method(
+    input: &DeriveInput,
+    variant_mappings: &[(&syn::Ident, syn::Path, ConfigField, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let type_name = &input.ident;
+
+    // Generate match arms for the config method
+    let config_arms = variant_mappings.iter().map(
+        |(variant_name, _concrete_type, config_field, _variant_options)| match config_field {
+            ConfigField::Plain(_) => quote! {
+                #type_name::#variant_name(config) => config
+            },
+            ConfigField::Borrowed(_) => quote! {
+                #type_name::#variant_name(config) => *config
+            },
+            ConfigField::Boxed(_) => quote! {
+                #type_name::#variant_name(config) => &**config
+            },
+            // Upcast to `dyn Any`, so the concrete config can be downcast to
+            ConfigField::Erased(_) => quote! {
+                #type_name::#variant_name(config) => {
+                    let config: &dyn ::core::any::Any = &**config;
+                    config
+                }
+            },
+            ConfigField::Optional(inner) => {
+                let config = match inner {
+                    syn::Type::Reference(_) => quote! { *config },
+                    _ => quote! { config },
+                };
+                quote! {
+                    #type_name::#variant_name(::core::option::Option::Some(config)) => #config,
+                    // Return unit type for missing config
+                    #type_name::#variant_name(::core::option::Option::None) => &()
+                }
+            }
+            ConfigField::Unit => quote! {
+                #type_name::#variant_name => &() // Return unit type for variants w/o config
+            },
+        },
+    );
+
+    // Type-erasing the config requires every type parameter to be 'static, as well as any
+    // lifetime appearing in an erased config type (references to configs are looked through)
+    let erased_types: Vec<_> = variant_mappings
+        .iter()
+        .filter_map(|(_, _, config_field, _)| config_field.erased_type())
+        .collect();
+    let static_bounds = input
+        .generics
+        .type_params()
+        .map(|param| {
+            let ident = &param.ident;
+            quote! { #ident: 'static }
+        })
+        .chain(
+            input
+                .generics
+                .lifetimes()
+                .map(|param| &param.lifetime)
+                .filter(|lifetime| {
+                    erased_types
+                        .iter()
+                        .any(|ty| type_mentions_lifetime(ty, lifetime))
+                })
+                .map(|lifetime| quote! { #lifetime: 'static }),
+        );
+    let doc_hidden = options.doc_hidden_attr();
+
+    quote! {
+        /// Returns a reference to the configuration data associated with this enum variant
+        /// Unit variants return a reference to the unit type `()`
+        #doc_hidden
+        pub fn config(&self) -> &dyn ::core::any::Any
+        where
+            #(#static_bounds),*
+        {
+            match self {
+                #(#config_arms),*
+            }
+        }
+    }
+}
@@ -0,0 +1,482 @@
+//! Descriptions of the mapping for tools and caches: the API snapshot, manifest, diagram,
+//! fingerprint and `emit_with` hooks.
+
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::options::{EnumOptions, VariantOptions};
+use crate::paths::{concrete_path_string, source_string};
+
+/// Generates the `CONCRETE_API` constant summarizing the items of a derive's expansion: the rules
+/// of the dispatch macros, the methods and constants of each impl block, and the generated types.
+///
+/// Items are listed one per line and sorted, so the summary doesn't depend on the order items are
+/// generated in, and downstream crates can snapshot it to catch changes to their public surface.
+pub(crate) fn generate_api_snapshot(
+    input: &DeriveInput,
+    expanded: &proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let file: syn::File = syn::parse2(expanded.clone())?;
+    let mut lines = Vec::new();
+    api_lines("", &file.items, &mut lines);
+    lines.sort();
+    lines.dedup();
+    let mut api = String::new();
+    for line in lines {
+        api.push_str(&line);
+        api.push('\n');
+    }
+
+    let type_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #type_name #ty_generics #where_clause {
+            /// Summary of the items generated by the derive, one per line, combined by
+            /// `concrete_type_rules::gen_api_snapshot!`.
+            #[doc(hidden)]
+            pub const CONCRETE_API: &'static str = #api;
+        }
+    })
+}
+
+/// Appends a line per item of `items` to `lines`, prefixing paths with the enclosing `module`.
+pub(crate) fn api_lines(module: &str, items: &[syn::Item], lines: &mut Vec<String>) {
+    let signature = |tokens: proc_macro2::TokenStream| api_string(tokens);
+    let cfg = |attrs: &[syn::Attribute]| {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .map(|attr| format!("{} ", signature(quote! { #attr })))
+            .collect::<String>()
+    };
+
+    for item in items {
+        match item {
+            syn::Item::Macro(item) if item.mac.path.is_ident("macro_rules") => {
+                let Some(name) = &item.ident else {
+                    continue;
+                };
+                // Rules are `(matcher) => { body };`, only the matchers make up the interface
+                let mut tokens = item.mac.tokens.clone().into_iter();
+                while let Some(matcher) = tokens.next() {
+                    if let proc_macro2::TokenTree::Group(matcher) = matcher {
+                        lines.push(format!(
+                            "{}macro {}{}! {}",
+                            cfg(&item.attrs),
+                            module,
+                            name,
+                            signature(quote! { #matcher })
+                        ));
+                    }
+                    // Skip `=> { body }` and the separating `;`
+                    tokens.by_ref().take(4).for_each(drop);
+                }
+            }
+            syn::Item::Impl(item) => {
+                let self_ty = &item.self_ty;
+                let header = match &item.trait_ {
+                    Some((_, trait_path, _)) => format!(
+                        "impl {} for {}",
+                        signature(quote! { #trait_path }),
+                        signature(quote! { #self_ty })
+                    ),
+                    None => format!("impl {}", signature(quote! { #self_ty })),
+                };
+                let header = format!("{}{}", cfg(&item.attrs), header);
+                lines.push(header.clone());
+                for impl_item in &item.items {
+                    let member = match impl_item {
+                        syn::ImplItem::Fn(method) => {
+                            let (vis, sig) = (&method.vis, &method.sig);
+                            format!("{}{}", cfg(&method.attrs), signature(quote! { #vis #sig }))
+                        }
+                        syn::ImplItem::Const(constant) => {
+                            let (vis, ident, ty) = (&constant.vis, &constant.ident, &constant.ty);
+                            format!(
+                                "{}{}",
+                                cfg(&constant.attrs),
+                                signature(quote! { #vis const #ident: #ty })
+                            )
+                        }
+                        syn::ImplItem::Type(alias) => {
+                            let (ident, ty) = (&alias.ident, &alias.ty);
+                            format!(
+                                "{}{}",
+                                cfg(&alias.attrs),
+                                signature(quote! { type #ident = #ty })
+                            )
+                        }
+                        _ => continue,
+                    };
+                    lines.push(format!("{}: {}", header, member));
+                }
+            }
+            syn::Item::Struct(item) => {
+                let (vis, ident, generics) = (&item.vis, &item.ident, &item.generics);
+                lines.push(format!(
+                    "{}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis struct #ident #generics })
+                ));
+            }
+            syn::Item::Enum(item) => {
+                let (vis, ident, generics) = (&item.vis, &item.ident, &item.generics);
+                let variants = item.variants.iter().map(|variant| &variant.ident);
+                lines.push(format!(
+                    "{}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis enum #ident #generics { #(#variants),* } })
+                ));
+            }
+            syn::Item::Trait(item) => {
+                let (vis, ident, generics) = (&item.vis, &item.ident, &item.generics);
+                let header = format!(
+                    "{}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis trait #ident #generics })
+                );
+                lines.push(header.clone());
+                for trait_item in &item.items {
+                    let member = match trait_item {
+                        syn::TraitItem::Fn(method) => {
+                            let sig = &method.sig;
+                            signature(quote! { #sig })
+                        }
+                        syn::TraitItem::Const(constant) => {
+                            let (ident, ty) = (&constant.ident, &constant.ty);
+                            signature(quote! { const #ident: #ty })
+                        }
+                        syn::TraitItem::Type(alias) => {
+                            let ident = &alias.ident;
+                            signature(quote! { type #ident })
+                        }
+                        _ => continue,
+                    };
+                    lines.push(format!("{}: {}", header, member));
+                }
+            }
+            syn::Item::Type(item) => {
+                let (vis, ident, generics, ty) = (&item.vis, &item.ident, &item.generics, &item.ty);
+                lines.push(format!(
+                    "{}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis type #ident #generics = #ty })
+                ));
+            }
+            syn::Item::Const(item) if item.ident != "_" => {
+                let (vis, ident, ty) = (&item.vis, &item.ident, &item.ty);
+                lines.push(format!(
+                    "{}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis const #ident: #ty })
+                ));
+            }
+            syn::Item::Use(item) => {
+                let (vis, tree) = (&item.vis, &item.tree);
+                lines.push(format!(
+                    "{}{}use {}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis }),
+                    module,
+                    signature(quote! { #tree })
+                ));
+            }
+            syn::Item::Mod(item) => {
+                let (vis, ident) = (&item.vis, &item.ident);
+                lines.push(format!(
+                    "{}{}",
+                    cfg(&item.attrs),
+                    signature(quote! { #vis mod #ident })
+                ));
+                if let Some((_, items)) = &item.content {
+                    api_lines(&format!("{}{}::", module, ident), items, lines);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Renders tokens on a single line with fixed spacing rules, rather than through `Display`, whose
+/// output varies between compiler versions and wraps long lines.
+pub(crate) fn api_string(tokens: proc_macro2::TokenStream) -> String {
+    use proc_macro2::{Delimiter, Spacing, TokenTree};
+
+    let mut out = String::new();
+    // Whether the next token attaches to the previous one without a space
+    let mut attach = true;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let inner = api_string(group.stream());
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                // Calls and tuple structs keep their parentheses next to the name
+                let after_word = out.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                    || (out.ends_with('>') && !out.ends_with("->") && !out.ends_with("=>"));
+                if !(attach || group.delimiter() == Delimiter::Parenthesis && after_word) {
+                    out.push(' ');
+                }
+                out.push_str(open);
+                out.push_str(&inner);
+                out.push_str(close);
+                attach = false;
+            }
+            TokenTree::Ident(ident) => {
+                if !attach {
+                    out.push(' ');
+                }
+                out.push_str(&ident.to_string());
+                attach = false;
+            }
+            TokenTree::Literal(literal) => {
+                if !attach {
+                    out.push(' ');
+                }
+                out.push_str(&literal.to_string());
+                attach = false;
+            }
+            TokenTree::Punct(punct) => {
+                let c = punct.as_char();
+                let joint = punct.spacing() == Spacing::Joint;
+                let path_separator = c == ':'
+                    && (joint || out.ends_with(':'))
+                    && !matches!(tokens.peek(), Some(TokenTree::Punct(next)) if next.as_char() == '=');
+                // Generic arguments attach to the preceding name
+                let generics = c == '<' && out.ends_with(|c: char| c.is_alphanumeric() || c == '_');
+                // A `::` that doesn't follow a name or a keyword starts an absolute path
+                let after_name = out.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                    || (out.ends_with('>') && !out.ends_with("->") && !out.ends_with("=>"));
+                let after_keyword = ["dyn", "impl", "mut", "as", "for", "in", "where"]
+                    .iter()
+                    .any(|keyword| {
+                        out.strip_suffix(keyword).is_some_and(|rest| {
+                            !rest.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+                        })
+                    });
+                let absolute_path =
+                    path_separator && joint && !attach && (!after_name || after_keyword);
+                if (!attach
+                    && !matches!(c, ',' | ';' | '.' | '?' | '>')
+                    && !path_separator
+                    && !generics)
+                    || absolute_path
+                {
+                    out.push(' ');
+                }
+                if c == ':' && !path_separator && !attach {
+                    out.pop();
+                }
+                out.push(c);
+                attach = joint
+                    || path_separator
+                    || matches!(c, '&' | '$' | '@' | '#' | '\'' | '<' | '.');
+            }
+        }
+    }
+    out
+}
+
+/// Encodes `value` as a JSON string literal, quotes included.
+pub(crate) fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Generates the `CONCRETE_MANIFEST` constant, a JSON description of the variant mappings for
+/// tooling that can't parse Rust.
+///
+/// Each entry is a variant name, its concrete type path, its config type (`ConcreteConfig` only)
+/// and its variant-level options: tag, trait, constructor, the feature it is gated behind and its
+/// doc comment.
+pub(crate) fn generate_manifest(
+    type_name: &syn::Ident,
+    entries: &[(&syn::Ident, &syn::Path, Option<&syn::Type>, &VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let json_or_null = |value: Option<String>| {
+        value.map_or_else(|| "null".to_string(), |value| json_string(&value))
+    };
+    let variants: Vec<_> = entries
+        .iter()
+        .map(|(variant_name, concrete_type, config_type, variant_options)| {
+            let config_type =
+                config_type.map(|ty| source_string(quote! { #ty }).replace("& ", "&"));
+            let tag = variant_options.tag.as_ref().map_or_else(
+                || "null".to_string(),
+                |tag| tag.base10_digits().to_string(),
+            );
+            let trait_path = variant_options.trait_path.as_ref().map(concrete_path_string);
+            let constructor = variant_options.constructor.as_ref().map(ToString::to_string);
+            format!(
+                "{{\"variant\":{},\"concrete_type\":{},\"config_type\":{},\"tag\":{},\"trait\":{},\"constructor\":{},\"feature\":{},\"doc\":{}}}",
+                json_string(&variant_name.to_string()),
+                json_string(&concrete_path_string(concrete_type)),
+                json_or_null(config_type),
+                tag,
+                json_or_null(trait_path),
+                json_or_null(constructor),
+                json_or_null(variant_options.feature.as_ref().map(syn::LitStr::value)),
+                json_or_null(variant_options.doc.clone()),
+            )
+        })
+        .collect();
+    let manifest = format!(
+        "{{\"enum\":{},\"variants\":[{}]}}",
+        json_string(&type_name.to_string()),
+        variants.join(",")
+    );
+
+    let doc_hidden = options.doc_hidden_attr();
+    quote! {
+        /// JSON description of the variant to concrete type mapping, for build tooling and code
+        /// generators.
+        #doc_hidden
+        pub const CONCRETE_MANIFEST: &'static str = #manifest;
+    }
+}
+
+/// Generates the hidden `CONCRETE_DIAGRAM` constant, holding the mermaid flowchart statements
+/// linking the enum to its variants and their concrete types.
+///
+/// The statements omit the `graph` header so that `concrete-type-rules` can combine the
+/// diagrams of several enums.
+pub(crate) fn generate_diagram(
+    type_name: &syn::Ident,
+    variant_mappings: &[(&syn::Ident, &syn::Path)],
+) -> proc_macro2::TokenStream {
+    // Node ids only allow alphanumerics, labels are quoted and entity-escaped
+    let node_id = |prefix: &str, name: &str| {
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{}_{}", prefix, name)
+    };
+    let label = |name: &str| {
+        name.replace('"', "#quot;")
+            .replace('<', "#lt;")
+            .replace('>', "#gt;")
+    };
+
+    let enum_name = type_name.to_string();
+    let enum_id = node_id("enum", &enum_name);
+    let mut diagram = format!("    {}[\"{}\"]\n", enum_id, label(&enum_name));
+    for (variant_name, concrete_type) in variant_mappings {
+        let variant_name = variant_name.to_string();
+        let variant_id = node_id("variant", &format!("{}_{}", enum_name, variant_name));
+        let concrete_type = concrete_path_string(concrete_type);
+        let type_id = node_id("type", &concrete_type);
+        diagram.push_str(&format!(
+            "    {} --> {}[\"{}\"]\n    {} --> {}[\"{}\"]\n",
+            enum_id,
+            variant_id,
+            label(&variant_name),
+            variant_id,
+            type_id,
+            label(&concrete_type)
+        ));
+    }
+
+    quote! {
+        /// Mermaid flowchart statements for the variant to concrete type mapping, combined by
+        /// `concrete_type_rules::gen_mapping_diagram!`.
+        #[doc(hidden)]
+        pub const CONCRETE_DIAGRAM: &'static str = #diagram;
+    }
+}
+
+/// Generates the const `mapping_fingerprint` function returning the [`mapping_fingerprint`] of
+/// the enum.
+pub(crate) fn generate_mapping_fingerprint<'a>(
+    mappings: impl IntoIterator<Item = (&'a syn::Ident, &'a syn::Path)>,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let fingerprint = mapping_fingerprint(mappings);
+    let doc_hidden = options.doc_hidden_attr();
+    quote! {
+        /// Returns a fingerprint of the variant to concrete type mapping.
+        ///
+        /// The value is computed at compile time and only changes when a variant name or
+        /// concrete type path changes, so it can be used to key persisted caches.
+        #doc_hidden
+        pub const fn mapping_fingerprint() -> u64 {
+            #fingerprint
+        }
+    }
+}
+
+/// Computes a stable 64-bit fingerprint over the `(variant name, concrete path)` pairs.
+///
+/// Uses FNV-1a rather than `std`'s hashers, whose output is not guaranteed to be stable across
+/// Rust releases. Paths are rendered by [`api_string`] rather than through `Display` for the
+/// same reason. Each name and path is followed by a `0` byte so adjacent values can't run
+/// together.
+pub(crate) fn mapping_fingerprint<'a>(
+    mappings: impl IntoIterator<Item = (&'a syn::Ident, &'a syn::Path)>,
+) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for (variant_name, concrete_type) in mappings {
+        let variant_name = variant_name.to_string();
+        let concrete_type = api_string(quote! { #concrete_type });
+        for byte in [
+            variant_name.as_bytes(),
+            &[0],
+            concrete_type.as_bytes(),
+            &[0],
+        ]
+        .concat()
+        {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+/// Generates the invocations of the user macros named with `#[concrete(emit_with = "...")]`,
+/// one per macro and variant, as `hook!(Variant, path::to::Type, ConfigType)` next to the enum.
+///
+/// `variants` pairs each variant with its concrete type, its feature and the type its config is
+/// bound to. Variants whose feature is disabled are left out.
+pub(crate) fn generate_emit_with(
+    hooks: &[syn::Path],
+    variants: &[(
+        &syn::Ident,
+        &syn::Path,
+        Option<&syn::LitStr>,
+        proc_macro2::TokenStream,
+    )],
+) -> proc_macro2::TokenStream {
+    let invocations = hooks.iter().flat_map(|hook| {
+        variants
+            .iter()
+            .map(move |(variant_name, concrete_type, feature, config_type)| {
+                let cfg = feature.map(|feature| quote! { #[cfg(feature = #feature)] });
+                quote! {
+                    #cfg
+                    #hook!(#variant_name, #concrete_type, #config_type);
+                }
+            })
+    });
+    quote! { #(#invocations)* }
+}
@@ -0,0 +1,156 @@
+//! Binary encoding and JavaScript exports of unit variants.
+
+use quote::quote;
+
+use crate::options::EnumOptions;
+
+/// Generates the `encode` and `decode` methods of an enum of unit variants, packing each variant
+/// into its `#[concrete(tag = ...)]` as a `u16`, so the encoding survives reordering variants.
+/// With `encode = "serde"`, also implements `Serialize` and `Deserialize` as that `u16`.
+pub(crate) fn generate_encoding(
+    type_name: &syn::Ident,
+    variant_tags: &[(&syn::Ident, Option<&syn::LitInt>)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    // A variant added later without a tag would have no stable encoding
+    let mut encoded = Vec::new();
+    for (variant_name, tag) in variant_tags {
+        let Some(tag) = tag else {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                format!(
+                    "variant `{}` is missing `#[concrete(tag = ...)]`, which `encode` needs \
+                     for every variant",
+                    variant_name
+                ),
+            ));
+        };
+        let value = tag
+            .base10_parse::<u16>()
+            .map_err(|_| syn::Error::new_spanned(tag, "`encode` needs tags that fit in a `u16`"))?;
+        encoded.push((*variant_name, proc_macro2::Literal::u16_unsuffixed(value)));
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let encode_arms = encoded.iter().map(|(variant_name, value)| {
+        quote! { Self::#variant_name => #value }
+    });
+    let decode_arms = encoded.iter().map(|(variant_name, value)| {
+        quote! { #value => ::core::option::Option::Some(Self::#variant_name) }
+    });
+
+    let serde_impls = options.encode_serde.then(|| {
+        let expected = format!("the tag of a `{}` variant", type_name);
+        quote! {
+            impl ::serde::Serialize for #type_name {
+                fn serialize<S: ::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_u16(self.encode())
+                }
+            }
+
+            impl<'de> ::serde::Deserialize<'de> for #type_name {
+                fn deserialize<D: ::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::core::result::Result<Self, D::Error> {
+                    let tag = <u16 as ::serde::Deserialize>::deserialize(deserializer)?;
+                    Self::decode(tag).ok_or_else(|| {
+                        <D::Error as ::serde::de::Error>::invalid_value(
+                            ::serde::de::Unexpected::Unsigned(tag as u64),
+                            &#expected,
+                        )
+                    })
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #type_name {
+            /// Encodes the variant as its `#[concrete(tag = ...)]`, which is stable across
+            /// reorderings of the variants.
+            #doc_hidden
+            pub const fn encode(&self) -> u16 {
+                match self {
+                    #(#encode_arms),*
+                }
+            }
+
+            /// Decodes a variant from its `#[concrete(tag = ...)]`, or returns `None` if no
+            /// variant has the tag.
+            #doc_hidden
+            pub const fn decode(tag: u16) -> ::core::option::Option<Self> {
+                match tag {
+                    #(#decode_arms,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        #serde_impls
+    })
+}
+
+/// Generates the `{Enum}Wasm` enum exported to JavaScript with `#[wasm_bindgen]`, mirroring an
+/// enum of unit variants with its variant indices as discriminants, and the conversions between
+/// the two.
+pub(crate) fn generate_wasm(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_names: &[&syn::Ident],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let wasm_name = syn::Ident::new(&format!("{}Wasm", type_name), type_name.span());
+    let doc_hidden = options.doc_hidden_attr();
+    let wasm_doc = format!(
+        "[`{}`] exported to JavaScript as `{}`, with the variant indices as values.",
+        type_name, type_name
+    );
+
+    let wasm_variants = variant_names
+        .iter()
+        .enumerate()
+        .map(|(index, variant_name)| {
+            let index = proc_macro2::Literal::u32_unsuffixed(index as u32);
+            let variant_doc = format!("[`{}::{}`]", type_name, variant_name);
+            quote! {
+                #[doc = #variant_doc]
+                #variant_name = #index
+            }
+        });
+
+    quote! {
+        #[doc = #wasm_doc]
+        #[::wasm_bindgen::prelude::wasm_bindgen(js_name = #type_name)]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #doc_hidden
+        #vis enum #wasm_name {
+            #(#wasm_variants),*
+        }
+
+        impl ::core::convert::From<#type_name> for #wasm_name {
+            fn from(value: #type_name) -> Self {
+                match value {
+                    #(#type_name::#variant_names => #wasm_name::#variant_names),*
+                }
+            }
+        }
+
+        impl ::core::convert::From<#wasm_name> for #type_name {
+            fn from(value: #wasm_name) -> Self {
+                match value {
+                    #(#wasm_name::#variant_names => #type_name::#variant_names),*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,439 @@
+//! Error types returned by generated methods and dispatch rules.
+
+use quote::quote;
+
+use crate::options::EnumOptions;
+
+/// Generates the `{Enum}DispatchError` attaching the variant and its concrete type to an error
+/// raised while dispatching, returned by the `@try` dispatch rule, and the `wrap_err` method
+/// building it from a variant.
+pub(crate) fn generate_dispatch_error(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_names: &[&syn::Ident],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let error_name = syn::Ident::new(&format!("{}DispatchError", type_name), type_name.span());
+    let name_arms = variant_names.iter().map(|variant_name| {
+        let variant_str = variant_name.to_string();
+        quote! {
+            Self::#variant_name { .. } => #variant_str
+        }
+    });
+    let error_doc = format!(
+        "Error raised while dispatching on a [`{}`] variant, along with the variant and its \
+         concrete type.",
+        type_name
+    );
+    let enum_prefix = format!("{}::", type_name);
+    let doc_hidden = options.doc_hidden_attr();
+
+    quote! {
+        #[doc = #error_doc]
+        #[derive(::core::fmt::Debug)]
+        #doc_hidden
+        #vis struct #error_name<E> {
+            variant: &'static str,
+            concrete_type: &'static str,
+            error: E,
+        }
+
+        impl<E> #error_name<E> {
+            /// Returns the name of the variant dispatched on.
+            pub fn variant(&self) -> &'static str {
+                self.variant
+            }
+
+            /// Returns the path of the variant's concrete type.
+            pub fn concrete_type(&self) -> &'static str {
+                self.concrete_type
+            }
+
+            /// Returns the error raised while dispatching.
+            pub fn error(&self) -> &E {
+                &self.error
+            }
+
+            /// Returns the error raised while dispatching, dropping the variant context.
+            pub fn into_error(self) -> E {
+                self.error
+            }
+        }
+
+        impl<E: ::std::fmt::Display> ::std::fmt::Display for #error_name<E> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::core::write!(
+                    f,
+                    "{}{} (`{}`): {}",
+                    #enum_prefix, self.variant, self.concrete_type, self.error
+                )
+            }
+        }
+
+        impl<E: ::std::error::Error + 'static> ::std::error::Error for #error_name<E> {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                ::core::option::Option::Some(&self.error)
+            }
+        }
+
+        impl #type_name {
+            /// Wraps an error raised while dispatching on this variant, attaching the variant
+            /// name and its concrete type.
+            #doc_hidden
+            pub fn wrap_err<E>(&self, error: E) -> #error_name<E> {
+                #error_name {
+                    variant: match self {
+                        #(#name_arms),*
+                    },
+                    concrete_type: self.concrete_type_name(),
+                    error,
+                }
+            }
+        }
+    }
+}
+
+/// Generates the `{Enum}TypeMismatch` returned by the `@checked` form of a config enum's dispatch
+/// macro when a `Box<dyn Trait>` config declared with `#[concrete(config = "...")]` holds a value
+/// of another type.
+pub(crate) fn generate_type_mismatch(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let mismatch_name = syn::Ident::new(&format!("{}TypeMismatch", type_name), type_name.span());
+    let mismatch_doc = format!(
+        "Error returned when a [`{}`] variant holds an erased config of another type than declared.",
+        type_name
+    );
+    let type_str = type_name.to_string();
+    let doc_hidden = options.doc_hidden_attr();
+
+    quote! {
+        #[doc = #mismatch_doc]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+        )]
+        #doc_hidden
+        #vis struct #mismatch_name {
+            variant: &'static str,
+            expected: &'static str,
+        }
+
+        impl #mismatch_name {
+            #[doc(hidden)]
+            pub const fn new(variant: &'static str, expected: &'static str) -> Self {
+                Self { variant, expected }
+            }
+
+            /// Returns the name of the variant holding the config.
+            pub const fn variant(&self) -> &'static str {
+                self.variant
+            }
+
+            /// Returns the path of the config type the variant declares.
+            pub const fn expected(&self) -> &'static str {
+                self.expected
+            }
+        }
+
+        impl ::std::fmt::Display for #mismatch_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::core::write!(
+                    f,
+                    "`{}::{}` holds a config that isn't a `{}`",
+                    #type_str, self.variant, self.expected
+                )
+            }
+        }
+
+        impl ::std::error::Error for #mismatch_name {}
+    }
+}
+
+/// Generates the `{Enum}Fallback<T>` returned by the `fallback_{enum}_configs!` macro of a config
+/// enum, telling whether the primary or the secondary config succeeded, and the
+/// `{Enum}FallbackError<E>` holding both errors when neither did.
+pub(crate) fn generate_fallback_types(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let fallback_name = syn::Ident::new(&format!("{}Fallback", type_name), type_name.span());
+    let error_name = syn::Ident::new(&format!("{}FallbackError", type_name), type_name.span());
+    let fallback_doc = format!(
+        "Value produced by the `fallback` macro of [`{}`], tagged with the config that produced it.",
+        type_name
+    );
+    let error_doc = format!(
+        "Error returned by the `fallback` macro of [`{}`] when both configs failed.",
+        type_name
+    );
+    let error_str = error_name.to_string();
+    let doc_hidden = options.doc_hidden_attr();
+
+    quote! {
+        #[doc = #fallback_doc]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #doc_hidden
+        #vis enum #fallback_name<T> {
+            /// Produced by the primary config.
+            Primary(T),
+            /// Produced by the secondary config, after the primary one failed.
+            Secondary(T),
+        }
+
+        impl<T> #fallback_name<T> {
+            /// Returns `true` when the secondary config produced the value.
+            pub const fn is_secondary(&self) -> bool {
+                ::core::matches!(self, Self::Secondary(_))
+            }
+
+            /// Returns the value, whichever config produced it.
+            pub fn value(&self) -> &T {
+                match self {
+                    Self::Primary(value) | Self::Secondary(value) => value,
+                }
+            }
+
+            /// Returns the value, discarding which config produced it.
+            pub fn into_value(self) -> T {
+                match self {
+                    Self::Primary(value) | Self::Secondary(value) => value,
+                }
+            }
+        }
+
+        #[doc = #error_doc]
+        #doc_hidden
+        #vis struct #error_name<E> {
+            primary: E,
+            secondary: E,
+        }
+
+        impl<E> #error_name<E> {
+            #[doc(hidden)]
+            pub fn new(primary: E, secondary: E) -> Self {
+                Self { primary, secondary }
+            }
+
+            /// Returns the error the primary config failed with.
+            pub fn primary(&self) -> &E {
+                &self.primary
+            }
+
+            /// Returns the error the secondary config failed with.
+            pub fn secondary(&self) -> &E {
+                &self.secondary
+            }
+
+            /// Returns the errors of the primary and secondary configs.
+            pub fn into_errors(self) -> (E, E) {
+                (self.primary, self.secondary)
+            }
+        }
+
+        impl<E: ::std::fmt::Debug> ::std::fmt::Debug for #error_name<E> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#error_str)
+                    .field("primary", &self.primary)
+                    .field("secondary", &self.secondary)
+                    .finish()
+            }
+        }
+
+        impl<E: ::std::fmt::Display> ::std::fmt::Display for #error_name<E> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::core::write!(
+                    f,
+                    "primary failed: {}; secondary failed: {}",
+                    self.primary, self.secondary
+                )
+            }
+        }
+
+        impl<E: ::std::error::Error + 'static> ::std::error::Error for #error_name<E> {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                ::core::option::Option::Some(&self.secondary)
+            }
+        }
+    }
+}
+
+/// Generates the `{Enum}FailoverError` returned by the `@failover` dispatch rule when every
+/// variant it tried failed, holding each variant with its error in the order they were tried.
+pub(crate) fn generate_failover_error(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_names: &[&syn::Ident],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let error_name = syn::Ident::new(&format!("{}FailoverError", type_name), type_name.span());
+    let name_arms = variant_names.iter().map(|variant_name| {
+        let variant_str = variant_name.to_string();
+        quote! {
+            #type_name::#variant_name { .. } => #variant_str
+        }
+    });
+    let error_doc = format!(
+        "Error returned by the `@failover` dispatch rule when every [`{}`] variant tried failed.",
+        type_name
+    );
+    let no_variant = format!("no `{}` variant to try", type_name);
+    let all_failed = format!("every `{}` variant failed: ", type_name);
+    let doc_hidden = options.doc_hidden_attr();
+
+    quote! {
+        #[doc = #error_doc]
+        #doc_hidden
+        #vis struct #error_name<E> {
+            errors: ::std::vec::Vec<(#type_name, E)>,
+        }
+
+        impl<E> #error_name<E> {
+            #[doc(hidden)]
+            pub fn new(errors: ::std::vec::Vec<(#type_name, E)>) -> Self {
+                Self { errors }
+            }
+
+            /// Returns each variant tried with the error it failed with, in the order they were
+            /// tried. Empty when there was no variant to try.
+            pub fn errors(&self) -> &[(#type_name, E)] {
+                &self.errors
+            }
+
+            /// Returns each variant tried with the error it failed with.
+            pub fn into_errors(self) -> ::std::vec::Vec<(#type_name, E)> {
+                self.errors
+            }
+
+            fn variant_name(variant: &#type_name) -> &'static str {
+                match variant {
+                    #(#name_arms),*
+                }
+            }
+        }
+
+        impl<E: ::std::fmt::Debug> ::std::fmt::Debug for #error_name<E> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_map()
+                    .entries(
+                        self.errors
+                            .iter()
+                            .map(|(variant, error)| (Self::variant_name(variant), error)),
+                    )
+                    .finish()
+            }
+        }
+
+        impl<E: ::std::fmt::Display> ::std::fmt::Display for #error_name<E> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                if self.errors.is_empty() {
+                    return f.write_str(#no_variant);
+                }
+                f.write_str(#all_failed)?;
+                for (index, (variant, error)) in self.errors.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str("; ")?;
+                    }
+                    ::core::write!(f, "{}: {}", Self::variant_name(variant), error)?;
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+
+        impl<E: ::std::error::Error + 'static> ::std::error::Error for #error_name<E> {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                self.errors
+                    .last()
+                    .map(|(_, error)| error as &(dyn ::std::error::Error + 'static))
+            }
+        }
+    }
+}
+
+/// Returns the name of the error returned by `downcast_instance`, e.g. `ExchangeDowncastError`.
+pub(crate) fn downcast_error_name(type_name: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(&format!("{}DowncastError", type_name), type_name.span())
+}
+
+/// Generates the `{Enum}DowncastError` returned by `downcast_instance`.
+pub(crate) fn generate_downcast_error(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let error_name = downcast_error_name(type_name);
+    let error_doc = format!(
+        "Error returned by [`{}::downcast_instance`], holding the value that failed to downcast.",
+        type_name
+    );
+    let doc_hidden = options.doc_hidden_attr();
+
+    quote! {
+        #[doc = #error_doc]
+        ///
+        /// Returned when the requested type is not the concrete type of the variant, or when
+        /// the value is not an instance of it.
+        #[derive(::core::fmt::Debug)]
+        #doc_hidden
+        #vis struct #error_name {
+            variant: &'static str,
+            expected: &'static str,
+            requested: ::core::option::Option<&'static str>,
+            value: ::std::boxed::Box<dyn ::core::any::Any>,
+        }
+
+        impl #error_name {
+            /// Returns the name of the variant the downcast was checked against.
+            pub fn variant(&self) -> &'static str {
+                self.variant
+            }
+
+            /// Returns the path of the variant's concrete type.
+            pub fn expected_type(&self) -> &'static str {
+                self.expected
+            }
+
+            /// Returns the type name requested, if it is not the variant's concrete type.
+            pub fn requested_type(&self) -> ::core::option::Option<&'static str> {
+                self.requested
+            }
+
+            /// Returns the value that failed to downcast.
+            pub fn into_value(self) -> ::std::boxed::Box<dyn ::core::any::Any> {
+                self.value
+            }
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self.requested {
+                    ::core::option::Option::Some(requested) => ::core::write!(
+                        f,
+                        "cannot downcast to `{}`, variant `{}` maps to `{}`",
+                        requested, self.variant, self.expected
+                    ),
+                    ::core::option::Option::None => ::core::write!(
+                        f,
+                        "value is not a `{}`, the concrete type of variant `{}`",
+                        self.expected, self.variant
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+    }
+}
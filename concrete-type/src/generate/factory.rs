@@ -0,0 +1,621 @@
+//! Dynamic factories, instance enums and handles building the concrete types.
+
+use quote::quote;
+
+use crate::delegate::delegate_macro_name;
+use crate::options::{EnumOptions, VariantOptions};
+use crate::paths::concrete_path_string;
+
+/// Generates the `{Enum}Factory` trait, its implementation for the enum, and the
+/// `{Enum}CreateError` returned when the supplied config has the wrong type or the variant's
+/// feature is disabled.
+///
+/// Each concrete type is constructed through `factory_trait`, which must provide a
+/// `type Config where Self: Sized` and a `fn new(config: Self::Config) -> Self where Self: Sized`
+/// so that it stays object safe.
+///
+/// Variants with `#[concrete(constructor = "try_new")]` are constructed through that fallible
+/// constructor instead. `create` then returns an `{Enum}ConstructError`, wrapping either the
+/// `{Enum}CreateError` or the constructor's error along with the variant name.
+///
+/// When variants are marked `#[concrete(non_send)]`, the `{Enum}Send` enum over the remaining
+/// variants is generated as well, implementing `{Enum}SendFactory` whose `create` returns
+/// `Box<dyn Trait + Send>`. Its concrete types are boxed as `Send` trait objects, so a variant
+/// that isn't `Send` but lacks the marker fails to compile there.
+pub(crate) fn generate_factory(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    factory_trait: &syn::Path,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let factory_name = syn::Ident::new(&format!("{}Factory", type_name), type_name.span());
+    let error_name = syn::Ident::new(&format!("{}CreateError", type_name), type_name.span());
+    let construct_error_name =
+        syn::Ident::new(&format!("{}ConstructError", type_name), type_name.span());
+
+    // With a fallible constructor on any variant, `create` reports constructor errors too
+    let fallible = variant_mappings
+        .iter()
+        .any(|(_, _, variant_options)| variant_options.constructor.is_some());
+    let create_error = if fallible {
+        quote! { #construct_error_name }
+    } else {
+        quote! { #error_name }
+    };
+
+    let create_arm = |enum_name: &syn::Ident,
+                      (variant_name, concrete_type, variant_options): &(
+        &syn::Ident,
+        syn::Path,
+        VariantOptions,
+    )| {
+        let variant_str = variant_name.to_string();
+        let construct = match &variant_options.constructor {
+            None => quote! { <#concrete_type as #factory_trait>::new(*config) },
+            Some(constructor) => quote! {
+                <#concrete_type>::#constructor(*config).map_err(|error| {
+                    #construct_error_name::Constructor {
+                        variant: #variant_str,
+                        source: error.into(),
+                    }
+                })?
+            },
+        };
+        let create_arm = quote! {
+            #enum_name::#variant_name => {
+                type Config = <#concrete_type as #factory_trait>::Config;
+                let config = config.downcast::<Config>().map_err(|_| #error_name {
+                    variant: #variant_str,
+                    expected: ::core::any::type_name::<Config>(),
+                    feature: ::core::option::Option::None,
+                })?;
+                ::core::result::Result::Ok(::std::boxed::Box::new(#construct))
+            }
+        };
+        match &variant_options.feature {
+            None => create_arm,
+            Some(feature) => quote! {
+                #[cfg(feature = #feature)]
+                #create_arm,
+                #[cfg(not(feature = #feature))]
+                #enum_name::#variant_name => ::core::result::Result::Err(#error_name {
+                    variant: #variant_str,
+                    expected: ::core::any::type_name::<()>(),
+                    feature: ::core::option::Option::Some(#feature),
+                }
+                .into())
+            },
+        }
+    };
+    let create_arms = variant_mappings
+        .iter()
+        .map(|mapping| create_arm(type_name, mapping));
+
+    let doc_hidden = options.doc_hidden_attr();
+    let send_factory = variant_mappings
+        .iter()
+        .any(|(_, _, variant_options)| variant_options.non_send)
+        .then(|| {
+            let send_name = syn::Ident::new(&format!("{}Send", type_name), type_name.span());
+            let send_factory_name =
+                syn::Ident::new(&format!("{}SendFactory", type_name), type_name.span());
+            let send_mappings: Vec<_> = variant_mappings
+                .iter()
+                .filter(|(_, _, variant_options)| !variant_options.non_send)
+                .collect();
+            let send_variants = send_mappings
+                .iter()
+                .map(|(variant_name, _, _)| *variant_name)
+                .collect::<Vec<_>>();
+            let send_arms = send_mappings
+                .iter()
+                .map(|mapping| create_arm(&send_name, mapping));
+            let send_doc = format!(
+                "The [`{}`] variants whose concrete type is `Send`, i.e. not marked \
+                 `#[concrete(non_send)]`.",
+                type_name
+            );
+            let send_factory_doc = format!(
+                "Constructs the concrete type of a [`{}`] variant as a `Send` trait object.",
+                send_name
+            );
+            quote! {
+                #[doc = #send_doc]
+                #[derive(
+                    ::core::fmt::Debug,
+                    ::core::clone::Clone,
+                    ::core::marker::Copy,
+                    ::core::cmp::PartialEq,
+                    ::core::cmp::Eq,
+                    ::core::hash::Hash,
+                )]
+                #doc_hidden
+                #vis enum #send_name {
+                    #(#[allow(missing_docs)] #send_variants),*
+                }
+
+                impl ::core::convert::From<#send_name> for #type_name {
+                    fn from(variant: #send_name) -> Self {
+                        match variant {
+                            #(#send_name::#send_variants => Self::#send_variants),*
+                        }
+                    }
+                }
+
+                impl ::core::convert::TryFrom<#type_name> for #send_name {
+                    type Error = #type_name;
+
+                    /// Fails with the variant itself when it is marked `#[concrete(non_send)]`.
+                    fn try_from(variant: #type_name) -> ::core::result::Result<Self, #type_name> {
+                        match variant {
+                            #(
+                                #type_name::#send_variants => {
+                                    ::core::result::Result::Ok(Self::#send_variants)
+                                }
+                            )*
+                            variant => ::core::result::Result::Err(variant),
+                        }
+                    }
+                }
+
+                #[doc = #send_factory_doc]
+                #doc_hidden
+                #vis trait #send_factory_name {
+                    /// Downcasts `config` to the variant's config type and constructs its
+                    /// concrete type.
+                    fn create(
+                        &self,
+                        config: ::std::boxed::Box<dyn ::core::any::Any>,
+                    ) -> ::core::result::Result<
+                        ::std::boxed::Box<dyn #factory_trait + ::core::marker::Send>,
+                        #create_error,
+                    >;
+                }
+
+                impl #send_factory_name for #send_name {
+                    fn create(
+                        &self,
+                        config: ::std::boxed::Box<dyn ::core::any::Any>,
+                    ) -> ::core::result::Result<
+                        ::std::boxed::Box<dyn #factory_trait + ::core::marker::Send>,
+                        #create_error,
+                    > {
+                        match self {
+                            #(#send_arms),*
+                        }
+                    }
+                }
+            }
+        });
+    let construct_error = fallible.then(|| {
+        let construct_error_doc = format!(
+            "Error returned by [`{}::create`] when a variant has a fallible constructor.",
+            factory_name
+        );
+        quote! {
+            #[doc = #construct_error_doc]
+            #[derive(::core::fmt::Debug)]
+            #doc_hidden
+            #vis enum #construct_error_name {
+                /// The config could not be passed to the variant's constructor.
+                Create(#error_name),
+                /// The variant's `#[concrete(constructor = "...")]` returned an error.
+                Constructor {
+                    /// Name of the variant whose constructor failed.
+                    variant: &'static str,
+                    /// Error returned by the constructor.
+                    source: ::std::boxed::Box<
+                        dyn ::std::error::Error + ::core::marker::Send + ::core::marker::Sync,
+                    >,
+                },
+            }
+
+            impl #construct_error_name {
+                /// Returns the name of the variant that failed to be created.
+                pub fn variant(&self) -> &'static str {
+                    match self {
+                        Self::Create(error) => error.variant(),
+                        Self::Constructor { variant, .. } => variant,
+                    }
+                }
+            }
+
+            impl ::core::convert::From<#error_name> for #construct_error_name {
+                fn from(error: #error_name) -> Self {
+                    Self::Create(error)
+                }
+            }
+
+            impl ::core::fmt::Display for #construct_error_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        Self::Create(error) => ::core::fmt::Display::fmt(error, f),
+                        Self::Constructor { variant, source } => {
+                            ::core::write!(
+                                f,
+                                "failed to construct variant `{}`: {}",
+                                variant,
+                                source
+                            )
+                        }
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #construct_error_name {
+                fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                    match self {
+                        Self::Create(error) => ::core::option::Option::Some(error),
+                        Self::Constructor { source, .. } => {
+                            ::core::option::Option::Some(source.as_ref())
+                        }
+                    }
+                }
+            }
+        }
+    });
+    let error_doc = format!("Error returned by [`{}::create`].", factory_name);
+    let factory_doc = format!(
+        "Constructs the concrete type of a [`{}`] variant from a type-erased config.",
+        type_name
+    );
+
+    quote! {
+        #[doc = #error_doc]
+        ///
+        /// Returned when the supplied config is not the `Config` type of the variant's
+        /// concrete type, or when the variant's feature is disabled.
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+        )]
+        #doc_hidden
+        #vis struct #error_name {
+            variant: &'static str,
+            expected: &'static str,
+            feature: ::core::option::Option<&'static str>,
+        }
+
+        impl #error_name {
+            /// Returns the name of the variant that failed to be created.
+            pub fn variant(&self) -> &'static str {
+                self.variant
+            }
+
+            /// Returns the type name of the config the variant expected.
+            pub fn expected_config(&self) -> &'static str {
+                self.expected
+            }
+
+            /// Returns the disabled feature the variant requires, if that is why it failed.
+            pub fn required_feature(&self) -> ::core::option::Option<&'static str> {
+                self.feature
+            }
+        }
+
+        impl ::core::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                match self.feature {
+                    ::core::option::Option::Some(feature) => ::core::write!(
+                        f,
+                        "variant `{}` requires the `{}` feature",
+                        self.variant, feature
+                    ),
+                    ::core::option::Option::None => ::core::write!(
+                        f,
+                        "invalid config for variant `{}`, expected `{}`",
+                        self.variant, self.expected
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        #construct_error
+
+        #[doc = #factory_doc]
+        #doc_hidden
+        #vis trait #factory_name {
+            /// Downcasts `config` to the variant's config type and constructs its concrete type.
+            fn create(
+                &self,
+                config: ::std::boxed::Box<dyn ::core::any::Any>,
+            ) -> ::core::result::Result<::std::boxed::Box<dyn #factory_trait>, #create_error>;
+        }
+
+        impl #factory_name for #type_name {
+            fn create(
+                &self,
+                config: ::std::boxed::Box<dyn ::core::any::Any>,
+            ) -> ::core::result::Result<::std::boxed::Box<dyn #factory_trait>, #create_error> {
+                match self {
+                    #(#create_arms),*
+                }
+            }
+        }
+
+        #send_factory
+    }
+}
+
+/// Generates the `{Enum}Instance` enum holding a value of each concrete type, and the
+/// `new_default` method building it from the concrete type's `Default` implementation.
+pub(crate) fn generate_instance(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    // Disabled variants have no concrete type to hold
+    if let Some(feature) = variant_mappings
+        .iter()
+        .find_map(|(_, _, variant_options)| variant_options.feature.as_ref())
+    {
+        return Err(syn::Error::new_spanned(
+            feature,
+            "`new_default` can't be combined with feature-gated variants",
+        ));
+    }
+
+    let instance_name = syn::Ident::new(&format!("{}Instance", type_name), type_name.span());
+    let instance_variants = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, _)| {
+            quote! {
+                #[allow(missing_docs)]
+                #variant_name(#concrete_type)
+            }
+        });
+    let new_default_arms = variant_mappings.iter().map(|(variant_name, concrete_type, _)| {
+        quote! {
+            #type_name::#variant_name => {
+                #instance_name::#variant_name(<#concrete_type as ::core::default::Default>::default())
+            }
+        }
+    });
+
+    // Each delegated trait is implemented by the macro its `#[concrete_delegate]` generated
+    // next to it
+    let delegate_variants = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, _)| quote! { #variant_name(#concrete_type) });
+    let delegate_variants = quote! { #(#delegate_variants),* };
+    let mut delegations = Vec::new();
+    for trait_path in &options.delegate_traits {
+        let mut macro_path = trait_path.clone();
+        let Some(last) = macro_path.segments.last_mut() else {
+            continue;
+        };
+        if !last.arguments.is_none() {
+            return Err(syn::Error::new_spanned(
+                trait_path,
+                "`delegate_trait` doesn't support generic traits",
+            ));
+        }
+        last.ident = delegate_macro_name(&last.ident);
+        delegations.push(quote! {
+            #macro_path!([#trait_path] #instance_name [#delegate_variants]);
+        });
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let instance_doc = format!(
+        "A value of the concrete type of a [`{}`] variant, built by [`{}::new_default`].",
+        type_name, type_name
+    );
+
+    Ok(quote! {
+        #[doc = #instance_doc]
+        #doc_hidden
+        #vis enum #instance_name {
+            #(#instance_variants),*
+        }
+
+        #(#delegations)*
+
+        impl #type_name {
+            /// Builds the default value of the concrete type this variant maps to.
+            #doc_hidden
+            pub fn new_default(&self) -> #instance_name {
+                match self {
+                    #(#new_default_arms),*
+                }
+            }
+        }
+    })
+}
+
+/// Generates the `{Enum}VTable` describing a variant, with a constant table per variant, and the
+/// `{Enum}Handle` pointing to one.
+///
+/// The vtable holds the variant itself, its name, `#[concrete(tag = ...)]` and concrete type
+/// path, and function pointers to the constructors generated by `factory` and `new_default`
+/// when those are enabled.
+pub(crate) fn generate_handle(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let vtable_name = syn::Ident::new(&format!("{}VTable", type_name), type_name.span());
+    let handle_name = syn::Ident::new(&format!("{}Handle", type_name), type_name.span());
+
+    // Constructors are called through the methods generated for the enum, which already handle
+    // fallible constructors and disabled features
+    let factory_name = syn::Ident::new(&format!("{}Factory", type_name), type_name.span());
+    let mut constructor_fields = Vec::new();
+    if let Some(factory_trait) = &options.factory {
+        let fallible = variant_mappings
+            .iter()
+            .any(|(_, _, variant_options)| variant_options.constructor.is_some());
+        let create_error = if fallible {
+            format!("{}ConstructError", type_name)
+        } else {
+            format!("{}CreateError", type_name)
+        };
+        let create_error = syn::Ident::new(&create_error, type_name.span());
+        constructor_fields.push(quote! {
+            /// Constructs the variant's concrete type, see the `create` method of the factory
+            /// trait.
+            pub create: fn(
+                ::std::boxed::Box<dyn ::core::any::Any>,
+            ) -> ::core::result::Result<::std::boxed::Box<dyn #factory_trait>, #create_error>,
+        });
+    }
+    if options.new_default {
+        let instance_name = syn::Ident::new(&format!("{}Instance", type_name), type_name.span());
+        constructor_fields.push(quote! {
+            /// Builds the default value of the variant's concrete type.
+            pub new_default: fn() -> #instance_name,
+        });
+    }
+
+    let mut vtable_arms = Vec::new();
+    for (variant_name, concrete_type, variant_options) in variant_mappings {
+        let variant_str = variant_name.to_string();
+        let concrete_name = concrete_path_string(concrete_type);
+        let tag = match &variant_options.tag {
+            Some(tag) => {
+                let tag = proc_macro2::Literal::u64_unsuffixed(tag.base10_parse()?);
+                quote! { ::core::option::Option::Some(#tag) }
+            }
+            None => quote! { ::core::option::Option::None },
+        };
+        let create = options.factory.is_some().then(|| {
+            quote! {
+                create: |config| #factory_name::create(&#type_name::#variant_name, config),
+            }
+        });
+        let new_default = options
+            .new_default
+            .then(|| quote! { new_default: || #type_name::#variant_name.new_default(), });
+        vtable_arms.push(quote! {
+            #type_name::#variant_name => {
+                const VTABLE: #vtable_name = #vtable_name {
+                    kind: #type_name::#variant_name,
+                    name: #variant_str,
+                    tag: #tag,
+                    concrete_type_name: #concrete_name,
+                    #create
+                    #new_default
+                };
+                &VTABLE
+            }
+        });
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let vtable_doc = format!(
+        "Constant description of a [`{}`] variant, pointed to by [`{}`].",
+        type_name, handle_name
+    );
+    let handle_doc = format!(
+        "A copyable token standing for a [`{}`] variant, dereferencing to its [`{}`].",
+        type_name, vtable_name
+    );
+
+    Ok(quote! {
+        #[doc = #vtable_doc]
+        #doc_hidden
+        #vis struct #vtable_name {
+            /// The variant described.
+            pub kind: #type_name,
+            /// Name of the variant.
+            pub name: &'static str,
+            /// The variant's `#[concrete(tag = ...)]`, if any.
+            pub tag: ::core::option::Option<u64>,
+            /// Path of the concrete type the variant maps to, as written in its attribute.
+            pub concrete_type_name: &'static str,
+            #(#constructor_fields)*
+        }
+
+        impl ::std::fmt::Debug for #vtable_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(::core::stringify!(#vtable_name))
+                    .field("name", &self.name)
+                    .field("tag", &self.tag)
+                    .field("concrete_type_name", &self.concrete_type_name)
+                    .finish_non_exhaustive()
+            }
+        }
+
+        #[doc = #handle_doc]
+        ///
+        /// The handle is a single non-null pointer to a constant vtable, so dynamic layers can
+        /// pass it around, including through FFI as an opaque pointer, and read the variant's
+        /// metadata without matching on the enum.
+        #[derive(::core::clone::Clone, ::core::marker::Copy)]
+        #[repr(transparent)]
+        #doc_hidden
+        #vis struct #handle_name(&'static #vtable_name);
+
+        impl #handle_name {
+            /// Returns the vtable of the variant.
+            pub const fn vtable(self) -> &'static #vtable_name {
+                self.0
+            }
+
+            /// Returns the variant the handle stands for.
+            pub const fn kind(self) -> &'static #type_name {
+                &self.0.kind
+            }
+        }
+
+        impl ::core::ops::Deref for #handle_name {
+            type Target = #vtable_name;
+
+            fn deref(&self) -> &#vtable_name {
+                self.0
+            }
+        }
+
+        impl ::core::cmp::PartialEq for #handle_name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.kind.variant_index() == other.0.kind.variant_index()
+            }
+        }
+
+        impl ::core::cmp::Eq for #handle_name {}
+
+        impl ::core::hash::Hash for #handle_name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                self.0.kind.variant_index().hash(state);
+            }
+        }
+
+        impl ::std::fmt::Debug for #handle_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_tuple(::core::stringify!(#handle_name))
+                    .field(&self.0.name)
+                    .finish()
+            }
+        }
+
+        impl ::core::convert::From<#type_name> for #handle_name {
+            fn from(variant: #type_name) -> Self {
+                variant.handle()
+            }
+        }
+
+        impl #type_name {
+            /// Returns the constant vtable describing this variant.
+            #doc_hidden
+            pub const fn vtable(&self) -> &'static #vtable_name {
+                match self {
+                    #(#vtable_arms),*
+                }
+            }
+
+            /// Returns a copyable handle to this variant's vtable.
+            #doc_hidden
+            pub const fn handle(&self) -> #handle_name {
+                #handle_name(self.vtable())
+            }
+        }
+    })
+}
@@ -0,0 +1,648 @@
+//! Inherent methods and constants generated on the enums, most of them by default, and the
+//! `#[concrete(debug)]` implementation.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::dispatch::concrete_type_expr;
+use crate::generate::errors::downcast_error_name;
+use crate::options::{EnumOptions, VariantOptions, name_case, substitute_variant_placeholders};
+use crate::paths::concrete_path_string;
+
+/// Generates the const `concrete_type_name` method and a `{VARIANT}_TYPE_NAME` constant per
+/// variant, holding the concrete type path as written in the attribute, along with the const
+/// `type_name_short` method returning only its final segment and the `metric_labels` method
+/// pairing it with the variant name.
+///
+/// Variants whose names only differ in case, e.g. `HttpClient` and `HTTPClient`, would share a
+/// constant and are rejected.
+pub(crate) fn generate_type_name_items(
+    variant_mappings: &[(&syn::Ident, &syn::Path)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let doc_hidden = options.doc_hidden_attr();
+    let mut const_names: Vec<syn::Ident> = Vec::new();
+    for (index, (variant_name, _)) in variant_mappings.iter().enumerate() {
+        let const_name = syn::Ident::new(
+            &format!(
+                "{}_TYPE_NAME",
+                variant_name.to_string().to_case(Case::UpperSnake)
+            ),
+            variant_name.span(),
+        );
+        if let Some(existing) = const_names.iter().position(|name| *name == const_name) {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                format!(
+                    "variants `{}` and `{}` would both generate the constant `{}`, rename one \
+                     of them",
+                    variant_mappings[existing].0, variant_mappings[index].0, const_name
+                ),
+            ));
+        }
+        const_names.push(const_name);
+    }
+    let consts = variant_mappings.iter().zip(&const_names).map(
+        |((variant_name, concrete_type), const_name)| {
+            let concrete_type = concrete_path_string(concrete_type);
+            let const_doc = format!("Concrete type path of the `{}` variant.", variant_name);
+            quote! {
+                #[doc = #const_doc]
+                #doc_hidden
+                pub const #const_name: &'static str = #concrete_type;
+            }
+        },
+    );
+    let name_arms =
+        variant_mappings
+            .iter()
+            .zip(&const_names)
+            .map(|((variant_name, _), const_name)| {
+                quote! {
+                    Self::#variant_name { .. } => Self::#const_name
+                }
+            });
+
+    // The final segment without generic arguments, e.g. `Binance` for `crate::Binance<Clock>`
+    let short_name_arms = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type)| {
+            let short_name = concrete_type
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default();
+            quote! {
+                Self::#variant_name { .. } => #short_name
+            }
+        });
+    let label_arms = variant_mappings.iter().map(|(variant_name, _)| {
+        let variant_str = variant_name.to_string();
+        quote! {
+            Self::#variant_name { .. } => #variant_str
+        }
+    });
+
+    Ok(quote! {
+        #(#consts)*
+
+        /// Returns the path of the concrete type this variant maps to, as written in its
+        /// attribute.
+        #doc_hidden
+        pub const fn concrete_type_name(&self) -> &'static str {
+            match self {
+                #(#name_arms),*
+            }
+        }
+
+        /// Returns the name of the concrete type this variant maps to, without its module path
+        /// or generic arguments, e.g. for low-cardinality metrics labels.
+        #doc_hidden
+        pub const fn type_name_short(&self) -> &'static str {
+            match self {
+                #(#short_name_arms),*
+            }
+        }
+
+        /// Returns `[("kind", variant name), ("impl", type_name_short())]`, labels for
+        /// per-backend metrics with one value per variant.
+        #doc_hidden
+        pub const fn metric_labels(&self) -> [(&'static str, &'static str); 2] {
+            let kind = match self {
+                #(#label_arms),*
+            };
+            [("kind", kind), ("impl", self.type_name_short())]
+        }
+    })
+}
+
+/// Generates the `VARIANT_COUNT` constant and the const `variant_index` method numbering the
+/// variants densely in declaration order, and for enums of unit variants the `from_index`
+/// function inverting it.
+///
+/// Generated tables indexed by variant, such as `{Enum}Set`, use the same indices.
+pub(crate) fn generate_index_methods(
+    variant_names: &[&syn::Ident],
+    all_unit: bool,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let doc_hidden = options.doc_hidden_attr();
+    let variant_count = variant_names.len();
+    let indices: Vec<_> = (0..variant_count).collect();
+    let from_index = all_unit.then(|| {
+        quote! {
+            /// Returns the variant at `index` in declaration order, the inverse of
+            /// `variant_index`, or `None` if `index` is out of range.
+            #doc_hidden
+            pub const fn from_index(index: usize) -> ::core::option::Option<Self> {
+                match index {
+                    #(#indices => ::core::option::Option::Some(Self::#variant_names),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// Number of variants, one more than the largest `variant_index`.
+        #doc_hidden
+        pub const VARIANT_COUNT: usize = #variant_count;
+
+        /// Returns the position of this variant in declaration order.
+        ///
+        /// Indices are dense, from `0` to `VARIANT_COUNT - 1`, so they can index arrays kept in
+        /// parallel to the variants. They only change when variants are added, removed or
+        /// reordered.
+        #doc_hidden
+        pub const fn variant_index(&self) -> usize {
+            match self {
+                #(Self::#variant_names { .. } => #indices),*
+            }
+        }
+
+        #from_index
+    }
+}
+
+/// Generates the const `tag` method returning a variant's `#[concrete(tag = ...)]` and, for enums
+/// of unit variants, the const `from_tag` function inverting it. Nothing is generated when no
+/// variant has a tag.
+pub(crate) fn generate_tag_methods(
+    variant_tags: &[(&syn::Ident, Option<&syn::LitInt>)],
+    all_unit: bool,
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut tagged: Vec<(u64, &syn::Ident)> = Vec::new();
+    for (variant_name, tag) in variant_tags {
+        let Some(tag) = tag else {
+            continue;
+        };
+        let value = tag.base10_parse::<u64>()?;
+        if let Some((_, existing)) = tagged.iter().find(|(existing, _)| *existing == value) {
+            return Err(syn::Error::new_spanned(
+                tag,
+                format!("tag `{}` is already used by variant `{}`", value, existing),
+            ));
+        }
+        tagged.push((value, variant_name));
+    }
+    if tagged.is_empty() {
+        return Ok(proc_macro2::TokenStream::new());
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let tag_arms = variant_tags.iter().map(|(variant_name, _)| {
+        let tag = match tagged
+            .iter()
+            .find(|(_, tagged_name)| tagged_name == variant_name)
+        {
+            Some((value, _)) => {
+                let value = proc_macro2::Literal::u64_unsuffixed(*value);
+                quote! { ::core::option::Option::Some(#value) }
+            }
+            None => quote! { ::core::option::Option::None },
+        };
+        quote! {
+            Self::#variant_name { .. } => #tag
+        }
+    });
+    let from_tag = all_unit.then(|| {
+        let from_tag_arms = tagged.iter().map(|(value, variant_name)| {
+            let value = proc_macro2::Literal::u64_unsuffixed(*value);
+            quote! { #value => ::core::option::Option::Some(Self::#variant_name) }
+        });
+        quote! {
+            /// Returns the variant with the given `#[concrete(tag = ...)]`, or `None` if no
+            /// variant has it.
+            #doc_hidden
+            pub const fn from_tag(tag: u64) -> ::core::option::Option<Self> {
+                match tag {
+                    #(#from_tag_arms,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        /// Returns the variant's `#[concrete(tag = ...)]`, if it has one.
+        #doc_hidden
+        pub const fn tag(&self) -> ::core::option::Option<u64> {
+            match self {
+                #(#tag_arms),*
+            }
+        }
+
+        #from_tag
+    })
+}
+
+/// Generates the const `routing_key` method returning the `#[concrete(routing = "...")]` template
+/// with each variant's placeholders substituted: `{Variant}` as written, `{variant}` or `{snake}`
+/// in snake_case and `{kebab}` in kebab-case. Nothing is generated without a template.
+pub(crate) fn generate_routing_key(
+    variant_names: &[&syn::Ident],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(template) = &options.routing else {
+        return Ok(proc_macro2::TokenStream::new());
+    };
+
+    let template_str = template.value();
+    let mut keys: Vec<(String, &syn::Ident)> = Vec::new();
+    for variant_name in variant_names {
+        let key = substitute_variant_placeholders(&template_str, variant_name)
+            .replace("{snake}", &variant_name.to_string().to_case(Case::Snake))
+            .replace("{kebab}", &variant_name.to_string().to_case(Case::Kebab));
+        if key.contains('{') || key.contains('}') {
+            return Err(syn::Error::new_spanned(
+                template,
+                "unknown placeholder in `routing`, expected `{Variant}`, `{variant}`, `{snake}` \
+                 or `{kebab}`",
+            ));
+        }
+        if let Some((_, existing)) = keys.iter().find(|(existing, _)| *existing == key) {
+            return Err(syn::Error::new_spanned(
+                template,
+                format!(
+                    "`routing` gives variants `{}` and `{}` the same key `{}`",
+                    existing, variant_name, key
+                ),
+            ));
+        }
+        keys.push((key, variant_name));
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let doc = format!(
+        "Returns the routing key of the variant, from the template `{}`.",
+        template_str
+    );
+    let key_arms = keys.iter().map(|(key, variant_name)| {
+        quote! {
+            Self::#variant_name { .. } => #key
+        }
+    });
+    Ok(quote! {
+        #[doc = #doc]
+        #doc_hidden
+        pub const fn routing_key(&self) -> &'static str {
+            match self {
+                #(#key_arms),*
+            }
+        }
+    })
+}
+
+/// Generates the const `build_info` function returning a summary of the compiled mapping: the
+/// name and version of the crate deriving the enum, then a line per variant with its concrete
+/// type, tag and feature. Nothing is generated unless `#[concrete(build_info)]` is set.
+///
+/// The crate and the features are read in the crate deriving the enum. Whether each feature is
+/// enabled is spelled by a local macro defined under `#[cfg]`, as `concat!` expands macros but
+/// can't evaluate `cfg!` conditions.
+pub(crate) fn generate_build_info(
+    type_name: &syn::Ident,
+    variants: &[(&syn::Ident, &syn::Path, &VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    if !options.build_info {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let mut feature_macros = Vec::new();
+    let lines = variants
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            let target = if variant_options.nested {
+                format!("nested {}", concrete_path_string(concrete_type))
+            } else if variant_options.cluster.is_empty() {
+                concrete_path_string(concrete_type)
+            } else {
+                variant_options
+                    .cluster
+                    .iter()
+                    .map(concrete_path_string)
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            };
+            let tag = variant_options
+                .tag
+                .as_ref()
+                .map(|tag| format!(", tag {}", tag.base10_digits()))
+                .unwrap_or_default();
+            let line = format!("\n  {} -> {}{}", variant_name, target, tag);
+            let Some(feature) = &variant_options.feature else {
+                return quote! { #line };
+            };
+            let status_macro = syn::Ident::new(
+                &format!("__concrete_feature_{}", feature_macros.len()),
+                variant_name.span(),
+            );
+            feature_macros.push(quote! {
+                #[cfg(feature = #feature)]
+                macro_rules! #status_macro {
+                    () => { "enabled" };
+                }
+                #[cfg(not(feature = #feature))]
+                macro_rules! #status_macro {
+                    () => { "disabled" };
+                }
+            });
+            let feature = format!(", feature {} ", feature.value());
+            quote! { #line, #feature, #status_macro!() }
+        })
+        .collect::<Vec<_>>();
+    let header = format!("{} mapping of ", type_name);
+
+    let doc_hidden = options.doc_hidden_attr();
+    quote! {
+        /// Returns a summary of the compiled mapping, for `--version` output and startup logs:
+        /// the crate version, then each variant with its concrete type, tag and feature, and
+        /// whether the feature is enabled in this build.
+        #doc_hidden
+        pub const fn build_info() -> &'static str {
+            #(#feature_macros)*
+            ::core::concat!(
+                #header,
+                ::core::env!("CARGO_PKG_NAME"),
+                " ",
+                ::core::env!("CARGO_PKG_VERSION"),
+                #(#lines),*
+            )
+        }
+    }
+}
+
+/// Generates the const `as_str` method returning the variant's name, converted to the case
+/// given with `#[concrete(as_str = "...")]`, and `name` returning the same. Fails when two
+/// variants end up with the same name, which converting the case can cause.
+pub(crate) fn generate_as_str(
+    variant_names: &[&syn::Ident],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if !options.as_str {
+        return Ok(proc_macro2::TokenStream::new());
+    }
+
+    let case = options.as_str_case.as_ref().and_then(name_case);
+    let mut names: Vec<(String, &syn::Ident)> = Vec::new();
+    for variant_name in variant_names {
+        let name = match case {
+            Some(case) => variant_name.to_string().to_case(case),
+            None => variant_name.to_string(),
+        };
+        if let Some((_, existing)) = names.iter().find(|(existing, _)| *existing == name) {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                format!(
+                    "`as_str` gives variants `{}` and `{}` the same name `{}`",
+                    existing, variant_name, name
+                ),
+            ));
+        }
+        names.push((name, variant_name));
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let name_arms = names.iter().map(|(name, variant_name)| {
+        quote! {
+            Self::#variant_name { .. } => #name
+        }
+    });
+    Ok(quote! {
+        /// Returns the name of the variant, unique among the variants of the enum.
+        #doc_hidden
+        pub const fn as_str(&self) -> &'static str {
+            match self {
+                #(#name_arms),*
+            }
+        }
+
+        /// Returns the name of the variant, the same as [`as_str`](Self::as_str).
+        #doc_hidden
+        pub const fn name(&self) -> &'static str {
+            self.as_str()
+        }
+    })
+}
+
+/// Generates the const `required_feature` method and, for enums of unit variants, the
+/// `enabled_variants` function listing the variants whose feature is compiled in.
+///
+/// The features are checked in the crate deriving the enum.
+pub(crate) fn generate_feature_methods(
+    variant_features: &[(&syn::Ident, Option<&syn::LitStr>)],
+    all_unit: bool,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let doc_hidden = options.doc_hidden_attr();
+    let feature_arms = variant_features.iter().map(|(variant_name, feature)| {
+        let feature = match feature {
+            Some(feature) => quote! { ::core::option::Option::Some(#feature) },
+            None => quote! { ::core::option::Option::None },
+        };
+        quote! {
+            Self::#variant_name { .. } => #feature
+        }
+    });
+    let enabled_variants = all_unit.then(|| {
+        let entries = variant_features.iter().map(|(variant_name, feature)| {
+            let enabled = match feature {
+                Some(feature) => quote! { ::core::cfg!(feature = #feature) },
+                None => quote! { true },
+            };
+            quote! { (#enabled, Self::#variant_name) }
+        });
+        quote! {
+            /// Returns the variants whose Cargo feature is enabled in this build, e.g. to report
+            /// which backends were compiled in.
+            #doc_hidden
+            pub fn enabled_variants() -> ::std::vec::Vec<Self> {
+                [#(#entries),*]
+                    .into_iter()
+                    .filter(|(enabled, _)| *enabled)
+                    .map(|(_, variant)| variant)
+                    .collect()
+            }
+        }
+    });
+
+    quote! {
+        /// Returns the Cargo feature this variant's concrete type is gated behind, if any.
+        #doc_hidden
+        pub const fn required_feature(&self) -> ::core::option::Option<&'static str> {
+            match self {
+                #(#feature_arms),*
+            }
+        }
+
+        #enabled_variants
+    }
+}
+
+/// Generates the `concrete_type_id`, `same_concrete_as`, `try_into_concrete` and
+/// `downcast_instance` methods, which compare variants by the `TypeId` of their concrete types.
+///
+/// Variants whose feature is disabled report the `TypeId` of their stub.
+pub(crate) fn generate_type_id_methods(
+    type_name: &syn::Ident,
+    variant_mappings: &[(&syn::Ident, &syn::Path, Option<&syn::LitStr>)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let doc_hidden = options.doc_hidden_attr();
+    let error_name = downcast_error_name(type_name);
+    let variant_name_arms = variant_mappings.iter().map(|(variant_name, _, _)| {
+        let variant_str = variant_name.to_string();
+        quote! {
+            Self::#variant_name { .. } => #variant_str
+        }
+    });
+    let type_id_arms = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, feature)| {
+            let type_id =
+                concrete_type_expr(type_name, variant_name, concrete_type, *feature, |ty| {
+                    quote! { ::core::any::TypeId::of::<#ty>() }
+                });
+            quote! {
+                Self::#variant_name { .. } => #type_id
+            }
+        });
+
+    quote! {
+        /// Returns the `TypeId` of the concrete type this variant maps to.
+        ///
+        /// Comparing the result across different derived enums tells whether two selections
+        /// resolve to the same concrete type.
+        #doc_hidden
+        pub fn concrete_type_id(&self) -> ::core::any::TypeId {
+            match self {
+                #(#type_id_arms),*
+            }
+        }
+
+        /// Returns true if both variants map to the same concrete type.
+        #doc_hidden
+        pub fn same_concrete_as(&self, other: &Self) -> bool {
+            self.concrete_type_id() == other.concrete_type_id()
+        }
+
+        /// Returns the default value of `T` if this variant maps to `T`, and `self` otherwise.
+        #doc_hidden
+        pub fn try_into_concrete<T: 'static + ::core::default::Default>(
+            self,
+        ) -> ::core::result::Result<T, Self> {
+            self.try_into_concrete_with(T::default)
+        }
+
+        /// Returns the value built by `constructor` if this variant maps to `T`, and `self`
+        /// otherwise.
+        #doc_hidden
+        pub fn try_into_concrete_with<T: 'static>(
+            self,
+            constructor: impl ::core::ops::FnOnce() -> T,
+        ) -> ::core::result::Result<T, Self> {
+            if self.concrete_type_id() == ::core::any::TypeId::of::<T>() {
+                ::core::result::Result::Ok(constructor())
+            } else {
+                ::core::result::Result::Err(self)
+            }
+        }
+
+        /// Downcasts a type-erased instance to `T`, checking first that this variant maps to
+        /// `T`.
+        ///
+        /// Unlike a bare `Box<dyn Any>` downcast, the error names the variant and its concrete
+        /// type, and hands the value back.
+        #doc_hidden
+        pub fn downcast_instance<T: 'static>(
+            &self,
+            value: ::std::boxed::Box<dyn ::core::any::Any>,
+        ) -> ::core::result::Result<::std::boxed::Box<T>, #error_name> {
+            let error = |requested, value| #error_name {
+                variant: match self {
+                    #(#variant_name_arms),*
+                },
+                expected: self.concrete_type_name(),
+                requested,
+                value,
+            };
+            if self.concrete_type_id() != ::core::any::TypeId::of::<T>() {
+                let requested = ::core::any::type_name::<T>();
+                return ::core::result::Result::Err(error(
+                    ::core::option::Option::Some(requested),
+                    value,
+                ));
+            }
+            value.downcast::<T>().map_err(|value| error(::core::option::Option::None, value))
+        }
+    }
+}
+
+/// Generates `iter_with_types`, listing the variants of a unit-only enum along with their concrete
+/// types.
+pub(crate) fn generate_iter_with_types(
+    type_name: &syn::Ident,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let doc_hidden = options.doc_hidden_attr();
+    let entries = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            let concrete_name = concrete_type_expr(
+                type_name,
+                variant_name,
+                concrete_type,
+                variant_options.feature.as_ref(),
+                |ty| {
+                    quote! { ::core::any::type_name::<#ty>() }
+                },
+            );
+            let type_id = concrete_type_expr(
+                type_name,
+                variant_name,
+                concrete_type,
+                variant_options.feature.as_ref(),
+                |ty| {
+                    quote! { ::core::any::TypeId::of::<#ty>() }
+                },
+            );
+            quote! { (Self::#variant_name, #concrete_name, #type_id) }
+        });
+    quote! {
+        /// Returns an iterator over every variant, along with the name and `TypeId` of the
+        /// concrete type it maps to.
+        #doc_hidden
+        pub fn iter_with_types(
+        ) -> impl ::core::iter::Iterator<Item = (Self, &'static str, ::core::any::TypeId)> {
+            [#(#entries),*].into_iter()
+        }
+    }
+}
+
+/// Generates a `Debug` implementation writing each variant with its concrete type path.
+pub(crate) fn generate_debug(
+    type_name: &syn::Ident,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+) -> proc_macro2::TokenStream {
+    let debug_arms = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, _)| {
+            let debug = format!("{} → {}", variant_name, concrete_path_string(concrete_type));
+            quote! {
+                Self::#variant_name { .. } => #debug
+            }
+        });
+    quote! {
+        impl ::std::fmt::Debug for #type_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(match self {
+                    #(#debug_arms),*
+                })
+            }
+        }
+    }
+}
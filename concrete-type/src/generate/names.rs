@@ -0,0 +1,452 @@
+//! Lookups of variants by name, and the axum extractor built on them.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+
+use crate::generate::describe::json_string;
+use crate::options::{EnumOptions, VariantOptions};
+
+/// Returns every variant name and `#[concrete(alias = "...")]` alias paired with the variant it
+/// refers to, failing on a name used twice.
+pub(crate) fn variant_names_with_aliases<'a>(
+    variants: impl Iterator<Item = (&'a syn::Ident, &'a VariantOptions)>,
+) -> syn::Result<Vec<(String, &'a syn::Ident)>> {
+    let mut names: Vec<(String, &syn::Ident)> = Vec::new();
+    for (variant_name, variant_options) in variants {
+        let aliases = variant_options
+            .aliases
+            .iter()
+            .map(|alias| (alias.value(), Some(alias)));
+        for (name, alias) in std::iter::once((variant_name.to_string(), None)).chain(aliases) {
+            if let Some((_, existing)) = names.iter().find(|(existing, _)| *existing == name) {
+                let message = format!("name `{}` is already used by variant `{}`", name, existing);
+                return Err(match alias {
+                    Some(alias) => syn::Error::new_spanned(alias, message),
+                    None => syn::Error::new_spanned(variant_name, message),
+                });
+            }
+            names.push((name, variant_name));
+        }
+    }
+    Ok(names)
+}
+
+/// Generates the `from_named_value` method building a `ConcreteConfig` variant from its name and
+/// a JSON value deserialized into its config type, and the `{Enum}NamedValueError` it returns.
+///
+/// Unit variants ignore the value.
+pub(crate) fn generate_from_named_value(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variants: &[(&syn::Variant, &VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let names = variant_names_with_aliases(
+        variants
+            .iter()
+            .map(|(variant, variant_options)| (&variant.ident, *variant_options)),
+    )?;
+    let error_name = syn::Ident::new(&format!("{}NamedValueError", type_name), type_name.span());
+
+    let mut arms = Vec::new();
+    for (name, variant_name) in &names {
+        let Some((variant, _)) = variants
+            .iter()
+            .find(|(variant, _)| variant.ident == **variant_name)
+        else {
+            continue;
+        };
+        let variant_str = variant_name.to_string();
+        let build = match variant.fields.iter().next().map(|field| &field.ty) {
+            None => quote! { ::core::result::Result::Ok(#type_name::#variant_name) },
+            Some(syn::Type::Reference(reference)) => {
+                return Err(syn::Error::new_spanned(
+                    reference,
+                    "`from_named_value` can't deserialize a borrowed config, use an owned type",
+                ));
+            }
+            Some(config_type) => quote! {
+                ::serde_json::from_value::<#config_type>(value)
+                    .map(#type_name::#variant_name)
+                    .map_err(|source| #error_name::InvalidValue {
+                        variant: #variant_str,
+                        source,
+                    })
+            },
+        };
+        arms.push(quote! { #name => #build });
+    }
+
+    let doc_hidden = options.doc_hidden_attr();
+    let error_doc = format!("Error returned by [`{}::from_named_value`].", type_name);
+    let unknown_kind = format!("unknown `{}` kind `{{}}`", type_name);
+    let invalid_value = format!("invalid config for `{}::{{}}`: {{}}", type_name);
+
+    Ok(quote! {
+        #[doc = #error_doc]
+        #[derive(::core::fmt::Debug)]
+        #doc_hidden
+        #vis enum #error_name {
+            /// The kind matches no variant name or alias.
+            UnknownKind {
+                /// The kind that failed to match.
+                kind: ::std::string::String,
+            },
+            /// The value doesn't deserialize into the config type of the variant.
+            InvalidValue {
+                /// Name of the variant the kind selected.
+                variant: &'static str,
+                /// The deserialization error.
+                source: ::serde_json::Error,
+            },
+        }
+
+        impl ::std::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    Self::UnknownKind { kind } => ::core::write!(f, #unknown_kind, kind),
+                    Self::InvalidValue { variant, source } => {
+                        ::core::write!(f, #invalid_value, variant, source)
+                    }
+                }
+            }
+        }
+
+        impl ::std::error::Error for #error_name {
+            fn source(&self) -> ::core::option::Option<&(dyn ::std::error::Error + 'static)> {
+                match self {
+                    Self::UnknownKind { .. } => ::core::option::Option::None,
+                    Self::InvalidValue { source, .. } => ::core::option::Option::Some(source),
+                }
+            }
+        }
+
+        impl #type_name {
+            /// Builds the variant named `kind`, or one of its `#[concrete(alias = "...")]`
+            /// aliases, deserializing `value` into its config type. Unit variants ignore `value`.
+            #doc_hidden
+            pub fn from_named_value(
+                kind: &str,
+                value: ::serde_json::Value,
+            ) -> ::core::result::Result<Self, #error_name> {
+                // Unit variants leave the value unused
+                let _ = &value;
+                match kind {
+                    #(#arms,)*
+                    _ => ::core::result::Result::Err(#error_name::UnknownKind {
+                        kind: kind.to_string(),
+                    }),
+                }
+            }
+        }
+    })
+}
+
+/// Generates the `from_name` method, the `FromStr` implementation and the `{Enum}ParseError`
+/// returned for unknown names, over the names and aliases of an enum of unit variants.
+pub(crate) fn generate_from_name(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let names = variant_names_with_aliases(
+        variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| (*variant_name, variant_options)),
+    )?;
+
+    let doc_hidden = options.doc_hidden_attr();
+    let from_name = from_name_method(type_name, &names, &doc_hidden);
+    let from_name_fast = from_name_fast_method(type_name, &names, &doc_hidden);
+    let lookup = runtime_name_lookup();
+    let error_name = syn::Ident::new(&format!("{}ParseError", type_name), type_name.span());
+    let error_doc = format!(
+        "Error returned when parsing a [`{}`] from a name that matches no variant or alias.",
+        type_name
+    );
+    let unknown_name = format!("unknown `{}` name `{{}}`", type_name);
+
+    Ok(quote! {
+        #[doc = #error_doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #doc_hidden
+        #vis struct #error_name {
+            name: ::std::string::String,
+        }
+
+        impl #error_name {
+            /// Returns the name that failed to parse.
+            pub fn name(&self) -> &str {
+                &self.name
+            }
+        }
+
+        impl ::std::fmt::Display for #error_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::core::write!(f, #unknown_name, self.name)
+            }
+        }
+
+        impl ::std::error::Error for #error_name {}
+
+        impl #type_name {
+            #from_name
+            #from_name_fast
+        }
+
+        impl ::std::str::FromStr for #type_name {
+            type Err = #error_name;
+
+            fn from_str(name: &str) -> ::core::result::Result<Self, Self::Err> {
+                Self::#lookup(name).ok_or_else(|| #error_name {
+                    name: name.to_string(),
+                })
+            }
+        }
+    })
+}
+
+/// Generates the axum integration of an enum of unit variants: a `FromRequestParts`
+/// implementation parsing a path parameter with `from_name`, a `TryFrom<&HeaderValue>`
+/// implementation, and the `{Enum}Rejection` both return, answering with a JSON body listing the
+/// accepted names.
+pub(crate) fn generate_web(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let names = variant_names_with_aliases(
+        variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| (*variant_name, variant_options)),
+    )?;
+    let param = match &options.web_param {
+        Some(param) => param.value(),
+        None => type_name.to_string().to_case(Case::Snake),
+    };
+    let rejection_name = syn::Ident::new(&format!("{}Rejection", type_name), type_name.span());
+    let doc_hidden = options.doc_hidden_attr();
+    let lookup = runtime_name_lookup();
+
+    let expected = names.iter().map(|(name, _)| name);
+    let expected_json = format!(
+        "[{}]",
+        names
+            .iter()
+            .map(|(name, _)| json_string(name))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let expected_list = names
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rejection_doc = format!(
+        "Rejection of the [`{}`] extractor and `TryFrom<&HeaderValue>` implementation.",
+        type_name
+    );
+    let missing_param_doc = format!("The route has no `{{{}}}` path parameter.", param);
+    let type_str = type_name.to_string();
+    let missing_param = format!(
+        "route has no `{{{}}}` path parameter to extract a `{}` from",
+        param, type_name
+    );
+
+    Ok(quote! {
+        #[doc = #rejection_doc]
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+        #doc_hidden
+        #vis enum #rejection_name {
+            /// The value matches no variant name or alias.
+            UnknownName {
+                /// The value that failed to parse.
+                input: ::std::string::String,
+            },
+            #[doc = #missing_param_doc]
+            MissingParam,
+        }
+
+        impl #rejection_name {
+            /// The variant names and aliases accepted.
+            pub const EXPECTED: &'static [&'static str] = &[#(#expected),*];
+
+            /// Returns `400 Bad Request` for unknown names, and `500 Internal Server Error` for
+            /// a route missing the path parameter.
+            pub fn status(&self) -> ::axum::http::StatusCode {
+                match self {
+                    Self::UnknownName { .. } => ::axum::http::StatusCode::BAD_REQUEST,
+                    Self::MissingParam => ::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for #rejection_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    Self::UnknownName { input } => ::core::write!(
+                        f,
+                        "unknown `{}` `{}`, expected one of: {}",
+                        #type_str, input, #expected_list
+                    ),
+                    Self::MissingParam => f.write_str(#missing_param),
+                }
+            }
+        }
+
+        impl ::std::error::Error for #rejection_name {}
+
+        impl ::axum::response::IntoResponse for #rejection_name {
+            fn into_response(self) -> ::axum::response::Response {
+                // `{"error": "...", "expected": [...]}`, escaping the user-supplied input
+                let message = self.to_string();
+                let mut body = ::std::string::String::with_capacity(message.len() + 32);
+                body.push_str("{\"error\":\"");
+                for c in message.chars() {
+                    match c {
+                        '"' => body.push_str("\\\""),
+                        '\\' => body.push_str("\\\\"),
+                        c if c.is_control() => {
+                            body.push_str(&::std::format!("\\u{:04x}", c as u32));
+                        }
+                        c => body.push(c),
+                    }
+                }
+                body.push_str("\",\"expected\":");
+                body.push_str(#expected_json);
+                body.push('}');
+                (
+                    self.status(),
+                    [(::axum::http::header::CONTENT_TYPE, "application/json")],
+                    body,
+                )
+                    .into_response()
+            }
+        }
+
+        impl<S: ::core::marker::Send + ::core::marker::Sync>
+            ::axum::extract::FromRequestParts<S> for #type_name
+        {
+            type Rejection = #rejection_name;
+
+            async fn from_request_parts(
+                parts: &mut ::axum::http::request::Parts,
+                state: &S,
+            ) -> Result<Self, Self::Rejection> {
+                let params = <::axum::extract::RawPathParams as ::axum::extract::FromRequestParts<S>>::from_request_parts(parts, state)
+                    .await
+                    .map_err(|_| #rejection_name::MissingParam)?;
+                let (_, input) = params
+                    .iter()
+                    .find(|(name, _)| *name == #param)
+                    .ok_or(#rejection_name::MissingParam)?;
+                Self::#lookup(input).ok_or_else(|| #rejection_name::UnknownName {
+                    input: input.to_string(),
+                })
+            }
+        }
+
+        impl ::core::convert::TryFrom<&::axum::http::HeaderValue> for #type_name {
+            type Error = #rejection_name;
+
+            fn try_from(value: &::axum::http::HeaderValue) -> Result<Self, Self::Error> {
+                value
+                    .to_str()
+                    .ok()
+                    .and_then(Self::#lookup)
+                    .ok_or_else(|| #rejection_name::UnknownName {
+                        input: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                    })
+            }
+        }
+    })
+}
+
+/// Builds the `from_name` method, looking up a variant with a match over the name's bytes, as
+/// `str` can't be matched in const functions.
+pub(crate) fn from_name_method(
+    type_name: &syn::Ident,
+    names: &[(String, &syn::Ident)],
+    doc_hidden: &Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let arms = names.iter().map(|(name, variant_name)| {
+        let name = proc_macro2::Literal::byte_string(name.as_bytes());
+        quote! { #name => ::core::option::Option::Some(#type_name::#variant_name) }
+    });
+    quote! {
+        /// Returns the variant with the given name or `#[concrete(alias = "...")]`.
+        #doc_hidden
+        pub const fn from_name(name: &str) -> ::core::option::Option<Self> {
+            match name.as_bytes() {
+                #(#arms,)*
+                _ => ::core::option::Option::None,
+            }
+        }
+    }
+}
+
+/// Names the method parsing names at runtime, in `FromStr` and the web extractors: the perfect
+/// hash map lookup with the `phf` feature, the const `from_name` otherwise.
+pub(crate) fn runtime_name_lookup() -> syn::Ident {
+    let name = if cfg!(feature = "phf") {
+        "from_name_fast"
+    } else {
+        "from_name"
+    };
+    syn::Ident::new(name, proc_macro2::Span::call_site())
+}
+
+/// Without the `phf` feature, `from_name` is the only lookup.
+#[cfg(not(feature = "phf"))]
+pub(crate) fn from_name_fast_method(
+    _type_name: &syn::Ident,
+    _names: &[(String, &syn::Ident)],
+    _doc_hidden: &Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    proc_macro2::TokenStream::new()
+}
+
+/// Builds the `from_name_fast` method, looking up a variant through a perfect hash map computed
+/// at compile time so lookups don't compare the name against every variant. Map lookups aren't
+/// const, so it sits next to the const `from_name` rather than replacing it.
+#[cfg(feature = "phf")]
+pub(crate) fn from_name_fast_method(
+    type_name: &syn::Ident,
+    names: &[(String, &syn::Ident)],
+    doc_hidden: &Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let keys: Vec<&str> = names.iter().map(|(name, _)| name.as_str()).collect();
+    let state = phf_generator::generate_hash(&keys);
+    let key = state.key;
+    let disps = state.disps.iter().map(|(d1, d2)| quote! { (#d1, #d2) });
+    let entries = state.map.iter().map(|&index| {
+        let (name, variant_name) = &names[index];
+        quote! { (#name, #type_name::#variant_name) }
+    });
+
+    // The map holds the variants themselves, which are rebuilt so the enum needn't be `Clone`
+    let mut variant_names: Vec<&syn::Ident> = Vec::new();
+    for (_, variant_name) in names {
+        if !variant_names.contains(variant_name) {
+            variant_names.push(variant_name);
+        }
+    }
+
+    quote! {
+        /// Returns the variant with the given name or `#[concrete(alias = "...")]`, looked up
+        /// through a perfect hash map.
+        #doc_hidden
+        pub fn from_name_fast(name: &str) -> ::core::option::Option<Self> {
+            static NAMES: ::phf::Map<&'static str, #type_name> = ::phf::Map {
+                key: #key,
+                disps: &[#(#disps),*],
+                entries: &[#(#entries),*],
+            };
+            NAMES.get(name).map(|variant| match variant {
+                #(#type_name::#variant_names => #type_name::#variant_names),*
+            })
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Selection of variants by weight or priority.
+
+use quote::quote;
+
+use crate::options::EnumOptions;
+
+/// Generates the const `weight` method and `choose_weighted`, picking a variant of an enum of unit
+/// variants at random with a probability proportional to its `#[concrete(weight = ...)]`.
+/// Nothing is generated when no variant has a weight.
+///
+/// Variants whose feature is disabled are never picked.
+pub(crate) fn generate_weighted_choice(
+    type_name: &syn::Ident,
+    variants: &[(&syn::Ident, Option<&syn::LitInt>, Option<&syn::LitStr>)],
+    all_unit: bool,
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let Some(first_weight) = variants.iter().find_map(|(_, weight, _)| *weight) else {
+        return Ok(proc_macro2::TokenStream::new());
+    };
+    if !cfg!(feature = "rand") {
+        return Err(syn::Error::new_spanned(
+            first_weight,
+            "`weight` requires the `rand` feature of `concrete-type`",
+        ));
+    }
+    if !all_unit {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`weight` requires every variant to be a unit variant",
+        ));
+    }
+
+    // As with priorities, a variant added later must state its weight
+    let mut weight_arms = Vec::new();
+    let mut effective_weights = Vec::new();
+    for (variant_name, weight, feature) in variants {
+        let Some(weight) = weight else {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                format!(
+                    "variant `{}` is missing `#[concrete(weight = ...)]`, which every variant \
+                     needs once one has it",
+                    variant_name
+                ),
+            ));
+        };
+        let weight = proc_macro2::Literal::u32_unsuffixed(weight.base10_parse::<u32>()?);
+        weight_arms.push(quote! {
+            Self::#variant_name => #weight
+        });
+        effective_weights.push(match feature {
+            Some(feature) => quote! { if ::core::cfg!(feature = #feature) { #weight } else { 0 } },
+            None => quote! { #weight },
+        });
+    }
+    let no_weight = format!("every `{}` variant has a weight of 0", type_name);
+
+    let doc_hidden = options.doc_hidden_attr();
+    Ok(quote! {
+        impl #type_name {
+            /// Returns the variant's `#[concrete(weight = ...)]`.
+            #doc_hidden
+            pub const fn weight(&self) -> u32 {
+                match self {
+                    #(#weight_arms),*
+                }
+            }
+
+            /// Picks a variant at random, each with a probability proportional to its weight.
+            ///
+            /// # Panics
+            ///
+            /// Panics if every enabled variant has a weight of 0.
+            #doc_hidden
+            pub fn choose_weighted<R: ::rand::Rng + ?::core::marker::Sized>(rng: &mut R) -> Self {
+                const WEIGHTS: &[u64] = &[#(#effective_weights),*];
+                let total: u64 = WEIGHTS.iter().sum();
+                ::core::assert!(total > 0, #no_weight);
+                let mut point = rng.random_range(0..total);
+                for (index, weight) in WEIGHTS.iter().enumerate() {
+                    if point < *weight {
+                        if let ::core::option::Option::Some(variant) = Self::from_index(index) {
+                            return variant;
+                        }
+                    }
+                    point -= weight;
+                }
+                ::core::unreachable!("the point is below the total weight")
+            }
+        }
+    })
+}
+
+/// Generates the const `priority` method, `iter_by_priority` and the `PartialOrd`/`Ord`
+/// implementations ordering an enum of unit variants by `#[concrete(priority = ...)]`, lowest
+/// first, then by declaration order. Nothing is generated when no variant has a priority.
+pub(crate) fn generate_priority_items(
+    type_name: &syn::Ident,
+    variants: &[(&syn::Ident, Option<&syn::LitInt>)],
+    all_unit: bool,
+    options: &EnumOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if variants.iter().all(|(_, priority)| priority.is_none()) {
+        return Ok(proc_macro2::TokenStream::new());
+    }
+    if !all_unit {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`priority` requires every variant to be a unit variant",
+        ));
+    }
+
+    // Every variant needs a priority, so a new variant can't silently land anywhere in the order
+    let mut priorities = Vec::new();
+    for (variant_name, priority) in variants {
+        let Some(priority) = priority else {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                format!(
+                    "variant `{}` is missing `#[concrete(priority = ...)]`, which every variant \
+                     needs once one has it",
+                    variant_name
+                ),
+            ));
+        };
+        priorities.push((*variant_name, priority.base10_parse::<i64>()?));
+    }
+    let mut sorted: Vec<_> = priorities.iter().enumerate().collect();
+    sorted.sort_by_key(|(index, (_, priority))| (*priority, *index));
+    let sorted_variants = sorted.iter().map(|(_, (variant_name, _))| *variant_name);
+    let priority_arms = priorities.iter().map(|(variant_name, priority)| {
+        quote! {
+            Self::#variant_name => #priority
+        }
+    });
+    let variant_count = variants.len();
+
+    let doc_hidden = options.doc_hidden_attr();
+    Ok(quote! {
+        impl #type_name {
+            /// Returns the variant's `#[concrete(priority = ...)]`.
+            #doc_hidden
+            pub const fn priority(&self) -> i64 {
+                match self {
+                    #(#priority_arms),*
+                }
+            }
+
+            /// Returns every variant ordered by priority, lowest first, with ties in declaration
+            /// order. This is the order of the generated `Ord` implementation.
+            #doc_hidden
+            pub fn iter_by_priority() -> impl ::core::iter::Iterator<Item = Self> {
+                const ORDER: [#type_name; #variant_count] = [#(#type_name::#sorted_variants),*];
+                ORDER.into_iter()
+            }
+        }
+
+        impl ::core::cmp::PartialOrd for #type_name {
+            fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(::core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        impl ::core::cmp::Ord for #type_name {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                (self.priority(), self.variant_index()).cmp(&(other.priority(), other.variant_index()))
+            }
+        }
+    })
+}
@@ -0,0 +1,228 @@
+//! Variant sets and per-variant arrays.
+
+use quote::quote;
+
+use crate::options::EnumOptions;
+
+/// Generates the `{Enum}Set` bitset over the variants of an enum of unit variants, and the
+/// `in_set` method on the enum.
+pub(crate) fn generate_set(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_names: &[&syn::Ident],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let set_name = syn::Ident::new(&format!("{}Set", type_name), type_name.span());
+    let variant_count = variant_names.len();
+    let words = variant_count.div_ceil(64).max(1);
+
+    let doc_hidden = options.doc_hidden_attr();
+    let set_doc = format!(
+        "A set of [`{}`] variants, stored as a bitset indexed by variant.",
+        type_name
+    );
+
+    quote! {
+        #[doc = #set_doc]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::default::Default,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #doc_hidden
+        #vis struct #set_name {
+            bits: [u64; #words],
+        }
+
+        impl #set_name {
+            /// Returns an empty set.
+            pub const fn new() -> Self {
+                Self { bits: [0; #words] }
+            }
+
+            /// Returns true if the set contains the variant.
+            pub const fn contains(&self, variant: &#type_name) -> bool {
+                let index = variant.variant_index();
+                self.bits[index / 64] & (1 << (index % 64)) != 0
+            }
+
+            /// Adds the variant to the set, returning true if it wasn't already present.
+            pub fn insert(&mut self, variant: #type_name) -> bool {
+                let index = variant.variant_index();
+                let inserted = !self.contains(&variant);
+                self.bits[index / 64] |= 1 << (index % 64);
+                inserted
+            }
+
+            /// Removes the variant from the set, returning true if it was present.
+            pub fn remove(&mut self, variant: &#type_name) -> bool {
+                let index = variant.variant_index();
+                let removed = self.contains(variant);
+                self.bits[index / 64] &= !(1 << (index % 64));
+                removed
+            }
+
+            /// Returns the number of variants in the set.
+            pub fn len(&self) -> usize {
+                self.bits.iter().map(|word| word.count_ones() as usize).sum()
+            }
+
+            /// Returns true if the set contains no variants.
+            pub fn is_empty(&self) -> bool {
+                self.bits.iter().all(|word| *word == 0)
+            }
+
+            /// Returns an iterator over the variants in the set, in declaration order.
+            pub fn iter(&self) -> impl ::core::iter::Iterator<Item = #type_name> + '_ {
+                (0..#variant_count)
+                    .filter(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+                    .filter_map(#type_name::from_index)
+            }
+        }
+
+        impl ::std::iter::FromIterator<#type_name> for #set_name {
+            fn from_iter<I: ::core::iter::IntoIterator<Item = #type_name>>(variants: I) -> Self {
+                let mut set = Self::new();
+                for variant in variants {
+                    set.insert(variant);
+                }
+                set
+            }
+        }
+
+        impl ::std::iter::Extend<#type_name> for #set_name {
+            fn extend<I: ::core::iter::IntoIterator<Item = #type_name>>(&mut self, variants: I) {
+                for variant in variants {
+                    self.insert(variant);
+                }
+            }
+        }
+
+        impl #type_name {
+            /// Returns true if this variant is in the set.
+            #doc_hidden
+            pub const fn in_set(&self, set: &#set_name) -> bool {
+                set.contains(self)
+            }
+        }
+    }
+}
+
+/// Generates the `Per{Enum}<T>` array holding one value per variant of an enum of unit variants,
+/// indexed by the variants themselves.
+pub(crate) fn generate_per_variant(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let per_name = syn::Ident::new(&format!("Per{}", type_name), type_name.span());
+    let missing_variant = format!(
+        "every index below `{}::VARIANT_COUNT` is a variant",
+        type_name
+    );
+
+    let doc_hidden = options.doc_hidden_attr();
+    let per_doc = format!(
+        "A value per [`{}`] variant, stored in an array indexed by variant.",
+        type_name
+    );
+
+    quote! {
+        #[doc = #per_doc]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #doc_hidden
+        #vis struct #per_name<T>([T; #type_name::VARIANT_COUNT]);
+
+        impl<T> #per_name<T> {
+            /// Builds the array by calling `f` on every variant, in declaration order.
+            pub fn from_fn(mut f: impl ::core::ops::FnMut(#type_name) -> T) -> Self {
+                Self(::core::array::from_fn(|index| {
+                    f(#type_name::from_index(index).expect(#missing_variant))
+                }))
+            }
+
+            /// Builds the array by calling `f` on every variant, in declaration order, stopping
+            /// at the first error.
+            pub fn try_from_fn<E>(
+                mut f: impl ::core::ops::FnMut(#type_name) -> ::core::result::Result<T, E>,
+            ) -> ::core::result::Result<Self, E> {
+                let mut error = ::core::option::Option::None;
+                let values: [::core::option::Option<T>; #type_name::VARIANT_COUNT] =
+                    ::core::array::from_fn(|index| {
+                        if error.is_some() {
+                            return ::core::option::Option::None;
+                        }
+                        let variant = #type_name::from_index(index).expect(#missing_variant);
+                        f(variant).map_err(|e| error = ::core::option::Option::Some(e)).ok()
+                    });
+                match error {
+                    ::core::option::Option::Some(error) => ::core::result::Result::Err(error),
+                    ::core::option::Option::None => {
+                        ::core::result::Result::Ok(Self(values.map(|value| {
+                            value.expect("every value is built when no call fails")
+                        })))
+                    }
+                }
+            }
+
+            /// Returns an iterator over every variant and its value, in declaration order.
+            pub fn iter(&self) -> impl ::core::iter::Iterator<Item = (#type_name, &T)> + '_ {
+                self.0
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, value)| {
+                        ::core::option::Option::Some((#type_name::from_index(index)?, value))
+                    })
+            }
+
+            /// Returns an iterator over every variant and a mutable reference to its value, in
+            /// declaration order.
+            pub fn iter_mut(
+                &mut self,
+            ) -> impl ::core::iter::Iterator<Item = (#type_name, &mut T)> + '_ {
+                self.0
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(|(index, value)| {
+                        ::core::option::Option::Some((#type_name::from_index(index)?, value))
+                    })
+            }
+
+            /// Returns the values, indexed by `variant_index`.
+            pub fn into_array(self) -> [T; #type_name::VARIANT_COUNT] {
+                self.0
+            }
+        }
+
+        impl<T: ::core::default::Default> ::core::default::Default for #per_name<T> {
+            fn default() -> Self {
+                Self::from_fn(|_| ::core::default::Default::default())
+            }
+        }
+
+        impl<T> ::core::ops::Index<#type_name> for #per_name<T> {
+            type Output = T;
+
+            fn index(&self, variant: #type_name) -> &T {
+                &self.0[variant.variant_index()]
+            }
+        }
+
+        impl<T> ::core::ops::IndexMut<#type_name> for #per_name<T> {
+            fn index_mut(&mut self, variant: #type_name) -> &mut T {
+                &mut self.0[variant.variant_index()]
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Subset enums declared with `#[concrete_subset(...)]`.
+
+use quote::quote;
+use syn::{DeriveInput, Fields};
+
+use crate::options::{EnumOptions, SubsetOptions, VariantOptions};
+use crate::paths::concrete_path_string;
+
+/// Generates a `#[concrete_subset(...)]` enum holding the listed variants, along with the
+/// conversions from it into the full enum and back, failing on variants outside the subset.
+///
+/// The subset enum derives `Concrete` itself, with each variant's resolved concrete type, feature
+/// gate and nesting restated and the enum-level options affecting the dispatch macro forwarded,
+/// so it dispatches exactly like the full enum.
+pub(crate) fn generate_subset(
+    input: &DeriveInput,
+    data_enum: &syn::DataEnum,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+    subset: &SubsetOptions,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let type_name = &input.ident;
+    let subset_name = &subset.name;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`concrete_subset` isn't supported on generic enums",
+        ));
+    }
+
+    let mut enum_options = Vec::new();
+    if let Some(crate_path) = &options.crate_path {
+        let crate_path = concrete_path_string(crate_path);
+        enum_options.push(quote! { crate_path = #crate_path });
+    }
+    for (option, enabled) in [
+        ("prefix_crate_name", options.prefix_crate_name),
+        ("doc_hidden", options.doc_hidden),
+        ("relative_macro", options.relative_macro),
+        ("partial", options.partial),
+        ("flatten", options.flatten),
+        ("minimal", options.minimal),
+    ] {
+        if enabled {
+            let option = syn::Ident::new(option, proc_macro2::Span::call_site());
+            enum_options.push(quote! { #option });
+        }
+    }
+    let enum_options =
+        (!enum_options.is_empty()).then(|| quote! { #[concrete(#(#enum_options),*)] });
+
+    let mut variants = Vec::new();
+    let mut into_arms = Vec::new();
+    let mut from_arms = Vec::new();
+    for (index, variant_name) in subset.variants.iter().enumerate() {
+        if subset.variants[..index].contains(variant_name) {
+            return Err(syn::Error::new_spanned(
+                &subset.variants_lit,
+                format!("variant `{}` is listed twice", variant_name),
+            ));
+        }
+        let Some(((_, concrete_type, variant_options), variant)) = variant_mappings
+            .iter()
+            .zip(&data_enum.variants)
+            .find(|((name, _, _), _)| *name == variant_name)
+        else {
+            return Err(syn::Error::new_spanned(
+                &subset.variants_lit,
+                format!("`{}` has no variant named `{}`", type_name, variant_name),
+            ));
+        };
+
+        let mut variant_attrs = Vec::new();
+        if let Some(nested_macro) = &variant_options.nested_macro {
+            let nested_macro = concrete_path_string(nested_macro);
+            variant_attrs.push(quote! { nested = #nested_macro });
+        } else if variant_options.nested_extern {
+            variant_attrs.push(quote! { nested_extern });
+        } else if variant_options.nested {
+            variant_attrs.push(quote! { nested });
+        } else {
+            let concrete_type = concrete_path_string(concrete_type);
+            variant_attrs.push(quote! { ty = #concrete_type });
+        }
+        if let Some(feature) = &variant_options.feature {
+            variant_attrs.push(quote! { feature = #feature });
+        }
+        let docs = variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        let fields = &variant.fields;
+        variants.push(quote! {
+            #(#docs)*
+            #[concrete(#(#variant_attrs),*)]
+            #variant_name #fields
+        });
+
+        let bindings = match &variant.fields {
+            Fields::Unit => quote! {},
+            Fields::Unnamed(unnamed) => {
+                let bindings = (0..unnamed.unnamed.len()).map(|index| {
+                    syn::Ident::new(&format!("field_{}", index), proc_macro2::Span::call_site())
+                });
+                quote! { (#(#bindings),*) }
+            }
+            Fields::Named(named) => {
+                let bindings = named.named.iter().map(|field| &field.ident);
+                quote! { { #(#bindings),* } }
+            }
+        };
+        into_arms.push(quote! {
+            #subset_name::#variant_name #bindings => #type_name::#variant_name #bindings
+        });
+        from_arms.push(quote! {
+            #type_name::#variant_name #bindings => {
+                ::core::result::Result::Ok(#subset_name::#variant_name #bindings)
+            }
+        });
+    }
+
+    let derives = &subset.derives;
+    let subset_doc = format!(
+        "The subset of [`{}`] variants {}, dispatching to the same concrete types.",
+        type_name,
+        subset
+            .variants
+            .iter()
+            .map(|variant_name| format!("`{}`", variant_name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(quote! {
+        #[doc = #subset_doc]
+        #[derive(::concrete_type::Concrete, #(#derives),*)]
+        #enum_options
+        #vis enum #subset_name {
+            #(#variants),*
+        }
+
+        impl ::core::convert::From<#subset_name> for #type_name {
+            fn from(variant: #subset_name) -> Self {
+                match variant {
+                    #(#into_arms),*
+                }
+            }
+        }
+
+        impl ::core::convert::TryFrom<#type_name> for #subset_name {
+            type Error = #type_name;
+
+            /// Fails with the variant itself when it isn't part of the subset.
+            fn try_from(variant: #type_name) -> ::core::result::Result<Self, #type_name> {
+                match variant {
+                    #(#from_arms,)*
+                    // Unreachable when the subset lists every variant
+                    #[allow(unreachable_patterns)]
+                    variant => ::core::result::Result::Err(variant),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,510 @@
+//! Type-level views of the mapping: key and marker types, type lists and the index-based
+//! mapping trait.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::dispatch::unsupported_stub_name;
+use crate::options::{EnumOptions, VariantOptions};
+
+/// Implements a `ConcreteOf<const V: usize>` trait on an enum of unit variants for the index of each
+/// variant, with the variant's concrete type as `Ty`, so generic code can name the type mapped
+/// to a variant.
+///
+/// The trait is `{Enum}ConcreteOf`, generated next to the enum, unless a trait of the same shape
+/// is given, which lets libraries be generic over several enums.
+pub(crate) fn generate_concrete_of(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let doc_hidden = options.doc_hidden_attr();
+    let alias_name = syn::Ident::new(&format!("{}Concrete", type_name), type_name.span());
+
+    let (trait_path, trait_def) = match &options.concrete_of_trait {
+        Some(trait_path) => (quote! { #trait_path }, None),
+        None => {
+            let trait_name = syn::Ident::new(&format!("{}ConcreteOf", type_name), type_name.span());
+            let trait_doc = format!(
+                "Implemented by [`{}`] for the `variant_index` of each variant.",
+                type_name
+            );
+            let trait_def = quote! {
+                #[doc = #trait_doc]
+                #doc_hidden
+                #vis trait #trait_name<const V: usize> {
+                    /// The concrete type the variant at index `V` maps to.
+                    type Ty;
+                }
+            };
+            (quote! { #trait_name }, Some(trait_def))
+        }
+    };
+
+    let impls = variant_mappings.iter().enumerate().map(
+        |(index, (variant_name, concrete_type, variant_options))| {
+            let ty = match &variant_options.feature {
+                None => quote! { type Ty = #concrete_type; },
+                Some(feature) => {
+                    let stub_name = unsupported_stub_name(type_name, variant_name);
+                    quote! {
+                        #[cfg(feature = #feature)]
+                        type Ty = #concrete_type;
+                        #[cfg(not(feature = #feature))]
+                        type Ty = #stub_name;
+                    }
+                }
+            };
+            quote! {
+                impl #trait_path<#index> for #type_name {
+                    #ty
+                }
+            }
+        },
+    );
+
+    let alias_doc = format!(
+        "The concrete type of the [`{}`] variant at index `V`, e.g. \
+         `{}<{{ {}::{}.variant_index() }}>`.",
+        type_name,
+        alias_name,
+        type_name,
+        variant_mappings
+            .first()
+            .map(|(variant_name, _, _)| variant_name.to_string())
+            .unwrap_or_default()
+    );
+
+    quote! {
+        #trait_def
+
+        #(#impls)*
+
+        #[doc = #alias_doc]
+        #doc_hidden
+        #vis type #alias_name<const V: usize> = <#type_name as #trait_path<V>>::Ty;
+    }
+}
+
+/// Generates the `{Enum}ConcreteTypes` tuple of the concrete types of an enum of unit variants, in
+/// declaration order, and the `{Enum}TypeVisitor` trait visited with each of them by
+/// `visit_concrete_types`.
+///
+/// Variants whose feature is disabled appear as their stub in the tuple and aren't visited.
+pub(crate) fn generate_type_list(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let types_name = syn::Ident::new(&format!("{}ConcreteTypes", type_name), type_name.span());
+    let visitor_name = syn::Ident::new(&format!("{}TypeVisitor", type_name), type_name.span());
+    let bound = match &options.visitor_bound {
+        Some(bound) => quote! { #bound + 'static },
+        None => quote! { 'static },
+    };
+    let doc_hidden = options.doc_hidden_attr();
+
+    // A tuple element can't be gated, so feature-gated variants go through an alias defined
+    // either way
+    let mut gated_aliases = Vec::new();
+    let mut types = Vec::new();
+    let mut visits = Vec::new();
+    for (variant_name, concrete_type, variant_options) in variant_mappings {
+        let visit = quote! {
+            visitor.visit::<#concrete_type>(Self::#variant_name);
+        };
+        match &variant_options.feature {
+            None => {
+                types.push(quote! { #concrete_type });
+                visits.push(visit);
+            }
+            Some(feature) => {
+                let alias_name = syn::Ident::new(
+                    &format!("__{}{}Concrete", type_name, variant_name),
+                    variant_name.span(),
+                );
+                let stub_name = unsupported_stub_name(type_name, variant_name);
+                gated_aliases.push(quote! {
+                    #[cfg(feature = #feature)]
+                    #[doc(hidden)]
+                    #vis type #alias_name = #concrete_type;
+                    #[cfg(not(feature = #feature))]
+                    #[doc(hidden)]
+                    #vis type #alias_name = #stub_name;
+                });
+                types.push(quote! { #alias_name });
+                visits.push(quote! {
+                    #[cfg(feature = #feature)]
+                    #visit
+                });
+            }
+        }
+    }
+
+    let types_doc = format!(
+        "The concrete types of the [`{}`] variants, in declaration order.",
+        type_name
+    );
+    let visitor_doc = format!(
+        "A visitor called with the concrete type of every [`{}`] variant by \
+         [`{}::visit_concrete_types`].",
+        type_name, type_name
+    );
+
+    quote! {
+        #(#gated_aliases)*
+
+        #[doc = #types_doc]
+        #doc_hidden
+        #vis type #types_name = (#(#types,)*);
+
+        #[doc = #visitor_doc]
+        #doc_hidden
+        #vis trait #visitor_name {
+            /// Visits the concrete type `T` of `variant`.
+            fn visit<T: #bound>(&mut self, variant: #type_name);
+        }
+
+        impl #type_name {
+            /// Calls `visitor` with the concrete type of every variant, in declaration order.
+            #doc_hidden
+            pub fn visit_concrete_types<V: #visitor_name>(visitor: &mut V) {
+                #(#visits)*
+            }
+        }
+    }
+}
+
+/// Generates the `{enum}_keys` module holding a zero-sized key type per variant and the `Key`
+/// trait tying each of them to its concrete type, the `{Enum}Key` enum over the key types, and
+/// the `key` method on the enum.
+pub(crate) fn generate_keys(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let keys_mod = syn::Ident::new(
+        &format!("{}_keys", type_name.to_string().to_case(Case::Snake)),
+        type_name.span(),
+    );
+    let key_name = syn::Ident::new(&format!("{}Key", type_name), type_name.span());
+    let doc_hidden = options.doc_hidden_attr();
+
+    let key_structs = variant_mappings.iter().map(|(variant_name, _, _)| {
+        let key_doc = format!(
+            "Key of [`{}::{}`](super::{}::{}).",
+            type_name, variant_name, type_name, variant_name
+        );
+        quote! {
+            #[doc = #key_doc]
+            #[derive(
+                ::core::fmt::Debug,
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::default::Default,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::hash::Hash,
+            )]
+            pub struct #variant_name;
+        }
+    });
+
+    // Implemented outside the module, so concrete paths resolve as written on the enum
+    let key_impls =
+        variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, variant_options)| {
+                let variant_str = variant_name.to_string();
+                let concrete = match &variant_options.feature {
+                    None => quote! { type Concrete = #concrete_type; },
+                    Some(feature) => {
+                        let stub_name = unsupported_stub_name(type_name, variant_name);
+                        quote! {
+                            #[cfg(feature = #feature)]
+                            type Concrete = #concrete_type;
+                            #[cfg(not(feature = #feature))]
+                            type Concrete = #stub_name;
+                        }
+                    }
+                };
+                quote! {
+                    impl #keys_mod::Key for #keys_mod::#variant_name {
+                        #concrete
+                        const VARIANT: &'static str = #variant_str;
+
+                        fn key() -> #key_name {
+                            #key_name::#variant_name(#keys_mod::#variant_name)
+                        }
+                    }
+                }
+            });
+
+    let key_variants = variant_mappings.iter().map(|(variant_name, _, _)| {
+        quote! {
+            #[allow(missing_docs)]
+            #variant_name(#keys_mod::#variant_name)
+        }
+    });
+    let key_arms = variant_mappings.iter().map(|(variant_name, _, _)| {
+        quote! {
+            Self::#variant_name { .. } => #key_name::#variant_name(#keys_mod::#variant_name)
+        }
+    });
+
+    let mod_doc = format!(
+        "Zero-sized key types, one per [`{}`] variant, e.g. for typemap-style containers.",
+        type_name
+    );
+    let trait_doc = format!(
+        "Implemented by the key type of each [`{}`] variant.",
+        type_name
+    );
+    let key_doc = format!(
+        "The key type of a [`{}`] variant, returned by [`{}::key`].",
+        type_name, type_name
+    );
+
+    quote! {
+        #[doc = #mod_doc]
+        #doc_hidden
+        #vis mod #keys_mod {
+            #[doc = #trait_doc]
+            pub trait Key: ::core::marker::Copy + ::core::default::Default + 'static {
+                /// The concrete type the variant maps to.
+                type Concrete;
+                /// The name of the variant.
+                const VARIANT: &'static str;
+
+                /// Returns the key as the enum over every key type.
+                fn key() -> super::#key_name;
+            }
+
+            #(#key_structs)*
+        }
+
+        #(#key_impls)*
+
+        #[doc = #key_doc]
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        #doc_hidden
+        #vis enum #key_name {
+            #(#key_variants),*
+        }
+
+        impl #type_name {
+            /// Returns the key of this variant.
+            #doc_hidden
+            pub fn key(&self) -> #key_name {
+                match self {
+                    #(#key_arms),*
+                }
+            }
+        }
+    }
+}
+
+/// Generates a zero-sized `{Variant}Kind` marker type per variant of an enum of unit variants,
+/// each implementing the `{Enum}Family` trait tying it to the variant and its concrete type.
+/// Markers convert into their variant with `From`, and back from the enum with `TryFrom`,
+/// which hands back the value when it is another variant.
+///
+/// Unlike the keys of [`generate_keys`], markers are declared next to the enum and name the
+/// variant itself, so they can stand in for it in const contexts and generic parameters.
+pub(crate) fn generate_family(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    variant_mappings: &[(&syn::Ident, syn::Path, VariantOptions)],
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let family_name = syn::Ident::new(&format!("{}Family", type_name), type_name.span());
+    let doc_hidden = options.doc_hidden_attr();
+
+    let markers = variant_mappings
+        .iter()
+        .map(|(variant_name, concrete_type, variant_options)| {
+            let marker_name =
+                syn::Ident::new(&format!("{}Kind", variant_name), variant_name.span());
+            let marker_doc = format!(
+                "Marker type of [`{}::{}`], naming it at the type level.",
+                type_name, variant_name
+            );
+            let concrete = match &variant_options.feature {
+                None => quote! { type Concrete = #concrete_type; },
+                Some(feature) => {
+                    let stub_name = unsupported_stub_name(type_name, variant_name);
+                    quote! {
+                        #[cfg(feature = #feature)]
+                        type Concrete = #concrete_type;
+                        #[cfg(not(feature = #feature))]
+                        type Concrete = #stub_name;
+                    }
+                }
+            };
+            quote! {
+                #[doc = #marker_doc]
+                #[derive(
+                    ::core::fmt::Debug,
+                    ::core::clone::Clone,
+                    ::core::marker::Copy,
+                    ::core::default::Default,
+                    ::core::cmp::PartialEq,
+                    ::core::cmp::Eq,
+                    ::core::hash::Hash,
+                )]
+                #doc_hidden
+                #vis struct #marker_name;
+
+                impl #family_name for #marker_name {
+                    #concrete
+                    const KIND: #type_name = #type_name::#variant_name;
+                }
+
+                impl ::core::convert::From<#marker_name> for #type_name {
+                    fn from(_: #marker_name) -> Self {
+                        #type_name::#variant_name
+                    }
+                }
+
+                impl ::core::convert::TryFrom<#type_name> for #marker_name {
+                    type Error = #type_name;
+
+                    fn try_from(value: #type_name) -> ::core::result::Result<Self, #type_name> {
+                        match value {
+                            #type_name::#variant_name => ::core::result::Result::Ok(#marker_name),
+                            #[allow(unreachable_patterns)]
+                            other => ::core::result::Result::Err(other),
+                        }
+                    }
+                }
+            }
+        });
+
+    let family_doc = format!(
+        "Implemented by the `{{Variant}}Kind` marker type of each [`{}`] variant.",
+        type_name
+    );
+
+    quote! {
+        #[doc = #family_doc]
+        #doc_hidden
+        #vis trait #family_name: ::core::marker::Copy + ::core::default::Default + 'static {
+            /// The concrete type the variant maps to.
+            type Concrete;
+            /// The variant the marker stands for.
+            const KIND: #type_name;
+        }
+
+        #(#markers)*
+    }
+}
+
+/// Generates the `{Enum}Tagged<T>` wrapper pairing a value with the variant that produced it,
+/// built in dispatch blocks with the `tagged` method `#[concrete(tagged)]` brings in scope.
+///
+/// The wrapper dereferences to the value. Its `Clone`, `Copy`, `Debug`, `PartialEq` and `Eq`
+/// implementations are bounded on the enum implementing them too, higher-ranked so that they are
+/// simply missing when it doesn't.
+pub(crate) fn generate_tagged(
+    type_name: &syn::Ident,
+    vis: &syn::Visibility,
+    options: &EnumOptions,
+) -> proc_macro2::TokenStream {
+    let tagged_name = syn::Ident::new(&format!("{}Tagged", type_name), type_name.span());
+    let tagged_str = tagged_name.to_string();
+    let doc_hidden = options.doc_hidden_attr();
+    let tagged_doc = format!(
+        "A value paired with the [`{}`] variant whose dispatch block produced it.",
+        type_name
+    );
+
+    quote! {
+        #[doc = #tagged_doc]
+        #doc_hidden
+        #vis struct #tagged_name<T>(pub T, pub #type_name);
+
+        impl<T> #tagged_name<T> {
+            /// Pairs `value` with `kind`.
+            pub const fn new(value: T, kind: #type_name) -> Self {
+                #tagged_name(value, kind)
+            }
+
+            /// Returns the variant that produced the value.
+            pub const fn kind(&self) -> &#type_name {
+                &self.1
+            }
+
+            /// Returns the value, discarding the variant.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+
+            /// Maps the value, keeping the variant that produced it.
+            pub fn map<U>(self, f: impl ::core::ops::FnOnce(T) -> U) -> #tagged_name<U> {
+                #tagged_name(f(self.0), self.1)
+            }
+        }
+
+        impl<T> ::core::ops::Deref for #tagged_name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> ::core::ops::DerefMut for #tagged_name<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+
+        impl<T: ::core::clone::Clone> ::core::clone::Clone for #tagged_name<T>
+        where
+            for<'__a> #type_name: ::core::clone::Clone,
+        {
+            fn clone(&self) -> Self {
+                #tagged_name(self.0.clone(), self.1.clone())
+            }
+        }
+
+        impl<T: ::core::marker::Copy> ::core::marker::Copy for #tagged_name<T> where
+            for<'__a> #type_name: ::core::marker::Copy
+        {
+        }
+
+        impl<T: ::core::fmt::Debug> ::core::fmt::Debug for #tagged_name<T>
+        where
+            for<'__a> #type_name: ::core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple(#tagged_str).field(&self.0).field(&self.1).finish()
+            }
+        }
+
+        impl<T: ::core::cmp::PartialEq> ::core::cmp::PartialEq for #tagged_name<T>
+        where
+            for<'__a> #type_name: ::core::cmp::PartialEq,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0 && self.1 == other.1
+            }
+        }
+
+        impl<T: ::core::cmp::Eq> ::core::cmp::Eq for #tagged_name<T> where
+            for<'__a> #type_name: ::core::cmp::Eq
+        {
+        }
+    }
+}
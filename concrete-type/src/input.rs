@@ -0,0 +1,237 @@
+//! Inputs of the function-like and attribute macros.
+
+mod keyword {
+    syn::custom_keyword!(via);
+    syn::custom_keyword!(prefix);
+}
+
+/// Input of [`concrete_impls`](crate::concrete_impls): `Enum [via path::to::macro]`.
+pub(crate) struct ConcreteImplsInput {
+    pub(crate) enum_name: syn::Ident,
+    pub(crate) macro_path: Option<syn::Path>,
+}
+
+impl syn::parse::Parse for ConcreteImplsInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let enum_name = input.parse()?;
+        let macro_path = if input.peek(keyword::via) {
+            input.parse::<keyword::via>()?;
+            Some(input.call(syn::Path::parse_mod_style)?)
+        } else {
+            None
+        };
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the enum, expected `Enum [via path]`"));
+        }
+
+        Ok(Self {
+            enum_name,
+            macro_path,
+        })
+    }
+}
+
+/// One enum of a combined matcher: its name, optionally the path of its dispatch macro and
+/// optionally the variant it is fixed to.
+pub(crate) struct MatchedEnum {
+    pub(crate) enum_name: syn::Ident,
+    pub(crate) macro_path: Option<syn::Path>,
+    pub(crate) fixed_variant: Option<syn::Ident>,
+}
+
+/// Input of [`gen_match_concretes!`](crate::gen_match_concretes):
+/// `Enum [via path::to::macro] [= Variant], ... [=> macro_name]`.
+pub(crate) struct MatchConcretesInput {
+    pub(crate) enums: Vec<MatchedEnum>,
+    pub(crate) macro_name: Option<syn::Ident>,
+}
+
+impl syn::parse::Parse for MatchConcretesInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut enums = Vec::new();
+        while !input.is_empty() && !input.peek(syn::Token![=>]) {
+            let enum_name: syn::Ident = input.parse()?;
+            let macro_path = if input.peek(keyword::via) {
+                input.parse::<keyword::via>()?;
+                Some(input.call(syn::Path::parse_mod_style)?)
+            } else {
+                None
+            };
+            let fixed_variant = if input.peek(syn::Token![=]) && !input.peek(syn::Token![=>]) {
+                input.parse::<syn::Token![=]>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            enums.push(MatchedEnum {
+                enum_name,
+                macro_path,
+                fixed_variant,
+            });
+
+            if input.is_empty() || input.peek(syn::Token![=>]) {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+
+        let macro_name = if input.peek(syn::Token![=>]) {
+            input.parse::<syn::Token![=>]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the combined matcher name"));
+        }
+
+        Ok(Self { enums, macro_name })
+    }
+}
+
+/// Input of [`gen_registry!`](crate::gen_registry):
+/// `[vis] Enum [via path::to::macro], trait = Bounds, constructor = name [, prefix = name]`.
+pub(crate) struct RegistryInput {
+    pub(crate) vis: syn::Visibility,
+    pub(crate) enum_name: syn::Ident,
+    pub(crate) macro_path: Option<syn::Path>,
+    pub(crate) bounds: syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>,
+    pub(crate) constructor: syn::Ident,
+    pub(crate) prefix: Option<syn::Ident>,
+}
+
+impl syn::parse::Parse for RegistryInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        let enum_name = input.parse()?;
+        let macro_path = if input.peek(keyword::via) {
+            input.parse::<keyword::via>()?;
+            Some(input.call(syn::Path::parse_mod_style)?)
+        } else {
+            None
+        };
+        input.parse::<syn::Token![,]>()?;
+        input.parse::<syn::Token![trait]>()?;
+        input.parse::<syn::Token![=]>()?;
+        let bounds = syn::punctuated::Punctuated::parse_separated_nonempty(input)?;
+        input.parse::<syn::Token![,]>()?;
+        let constructor_key: syn::Ident = input.parse()?;
+        if constructor_key != "constructor" {
+            return Err(syn::Error::new_spanned(
+                constructor_key,
+                "expected `constructor = name`",
+            ));
+        }
+        input.parse::<syn::Token![=]>()?;
+        let constructor = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        let prefix = if input.peek(keyword::prefix) {
+            input.parse::<keyword::prefix>()?;
+            input.parse::<syn::Token![=]>()?;
+            let prefix = input.parse()?;
+            input.parse::<Option<syn::Token![,]>>()?;
+            Some(prefix)
+        } else {
+            None
+        };
+        if !input.is_empty() {
+            return Err(input.error(
+                "unexpected tokens, expected `Enum, trait = Trait, constructor = name, prefix = name`",
+            ));
+        }
+
+        Ok(Self {
+            vis,
+            enum_name,
+            macro_path,
+            bounds,
+            constructor,
+            prefix,
+        })
+    }
+}
+
+/// One enum visited by [`gen_visitor2!`](crate::gen_visitor2): its name, optionally the path of its dispatch macro and
+/// the bounds of the type parameter standing for its concrete type.
+pub(crate) struct VisitedEnum {
+    pub(crate) enum_name: syn::Ident,
+    pub(crate) macro_path: Option<syn::Path>,
+    pub(crate) bounds: syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>,
+}
+
+impl syn::parse::Parse for VisitedEnum {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let enum_name = input.parse()?;
+        let macro_path = if input.peek(keyword::via) {
+            input.parse::<keyword::via>()?;
+            Some(input.call(syn::Path::parse_mod_style)?)
+        } else {
+            None
+        };
+        let bounds = if input.peek(syn::Token![:]) {
+            input.parse::<syn::Token![:]>()?;
+            syn::punctuated::Punctuated::parse_separated_nonempty(input)?
+        } else {
+            syn::punctuated::Punctuated::new()
+        };
+
+        Ok(Self {
+            enum_name,
+            macro_path,
+            bounds,
+        })
+    }
+}
+
+/// Input of [`gen_visitor2!`](crate::gen_visitor2):
+/// `[vis] Enum [via path] [: Bounds], Enum [via path] [: Bounds] [, visitor = Trait] [, accept = name]`.
+pub(crate) struct Visitor2Input {
+    pub(crate) vis: syn::Visibility,
+    pub(crate) first: VisitedEnum,
+    pub(crate) second: VisitedEnum,
+    pub(crate) visitor: Option<syn::Ident>,
+    pub(crate) accept: Option<syn::Ident>,
+}
+
+impl syn::parse::Parse for Visitor2Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let vis = input.parse()?;
+        let first = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let second = input.parse()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        let mut visitor = None;
+        let mut accept = None;
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            let slot = match key.to_string().as_str() {
+                "visitor" => &mut visitor,
+                "accept" => &mut accept,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        key,
+                        "unexpected tokens, `gen_visitor2!` takes exactly two enums, then \
+                         optionally `visitor = Trait` and `accept = name`",
+                    ));
+                }
+            };
+            if slot.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &key,
+                    format!("duplicate `{}` key", key),
+                ));
+            }
+            input.parse::<syn::Token![=]>()?;
+            *slot = Some(input.parse()?);
+            input.parse::<Option<syn::Token![,]>>()?;
+        }
+
+        Ok(Self {
+            vis,
+            first,
+            second,
+            visitor,
+            accept,
+        })
+    }
+}
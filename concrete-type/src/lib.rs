@@ -11,6 +11,12 @@
 //! - [`ConcreteConfig`] - For enums where each variant has associated configuration data
 //!   and maps to a specific concrete type
 //!
+//! Alongside them, [`ConcreteDisplay`] implements `Display` from the same mappings, showing
+//! each variant as `Variant (path::to::Type)`, [`gen_match_concretes!`] combines the
+//! dispatch macros of several enums into one matcher, [`gen_visitor2!`] does the same behind a
+//! visitor trait, and [`gen_registry!`] builds a static registry of constructors keyed by an
+//! enum's variants (all re-exported by `concrete-type-rules`).
+//!
 //! These macros enable type-level programming based on runtime enum values by generating
 //! helper methods and macros that provide access to the concrete types associated with
 //! enum variants.
@@ -26,6 +32,182 @@
 //! - `other_crate::path::to::Type` - Use this for types from external crates.
 //!   The path is used as-is.
 //!
+//! Generic parameters with defaults can be left out, e.g. `crate::Binance` for
+//! `Binance<C = SystemClock>`. The generated aliases are type positions, so the defaults apply
+//! there and in the generated methods alike.
+//!
+//! ## Variant Options
+//!
+//! Besides the `#[concrete = "path::to::Type"]` shorthand, a variant accepts the structured
+//! `#[concrete(key = value, ...)]` form:
+//!
+//! - `ty = "path::to::Type"` - The concrete type, equivalent to the shorthand.
+//! - `trait = "path::to::Trait"` - The trait the concrete type is used through.
+//! - `constructor = "new"` - The name of the concrete type's constructor.
+//! - `tag = 3` - A stable numeric tag identifying the variant, returned by the generated `tag`
+//!   method. Enums of unit variants also get a const `from_tag` lookup, and with
+//!   `#[concrete(encode)]` on the enum, `encode`/`decode` methods using the tags as a `u16` wire
+//!   encoding.
+//! - `feature = "name"` - See [Feature-Gated Variants](#feature-gated-variants).
+//!
+//! `trait`, `constructor` and `tag` are recorded in the `CONCRETE_MANIFEST` generated by
+//! `#[concrete(manifest)]`. Unknown keys, here and in the enum-level attribute, are rejected with
+//! the list of valid ones and a suggestion for likely typos.
+//!
+//! ## Variant Placeholders
+//!
+//! Any concrete path may contain the `{Variant}` and `{variant}` placeholders, which are
+//! replaced by the variant name as written and in snake_case respectively:
+//!
+//! ```rust
+//! # use concrete_type::Concrete;
+//! # mod exchanges {
+//! #     pub mod binance_spot {
+//! #         pub struct BinanceSpot;
+//! #     }
+//! # }
+//! #[derive(Concrete)]
+//! enum Exchange {
+//!     #[concrete = "crate::exchanges::{variant}::{Variant}"]
+//!     BinanceSpot, // crate::exchanges::binance_spot::BinanceSpot
+//! }
+//! # fn main() {
+//! # let name = exchange!(Exchange::BinanceSpot; E => { std::any::type_name::<E>() });
+//! # assert!(name.ends_with("::exchanges::binance_spot::BinanceSpot"));
+//! # }
+//! ```
+//!
+//! ## Enum-Level Base Path
+//!
+//! When every variant maps to the same generic type instantiated with a per-variant marker,
+//! the path can be declared once on the enum with `#[concrete(base = "...")]`. The
+//! placeholders are substituted for each variant, and an explicit `#[concrete = "..."]` on a
+//! variant overrides the base:
+//!
+//! ```rust
+//! # use concrete_type::Concrete;
+//! # pub struct Exchange<M>(std::marker::PhantomData<M>);
+//! # pub struct SpotMarket;
+//! # pub struct FuturesMarket;
+//! #[derive(Concrete)]
+//! #[concrete(base = "crate::Exchange<crate::{Variant}Market>")]
+//! enum Market {
+//!     Spot,    // crate::Exchange<crate::SpotMarket>
+//!     Futures, // crate::Exchange<crate::FuturesMarket>
+//! }
+//! # fn main() {
+//! # let name = market!(Market::Futures; M => { std::any::type_name::<M>() });
+//! # assert!(name.contains("::Exchange<") && name.ends_with("::FuturesMarket>"));
+//! # }
+//! ```
+//!
+//! ## Partially Applied Types
+//!
+//! With `#[concrete(partial)]`, concrete types may leave trailing generic parameters to the
+//! call site, which supplies them as `exchange!(e; T<MyClock> => { ... })`. The arguments are
+//! appended to those of the mapped path, so `crate::Okx<crate::Usd>` becomes
+//! `crate::Okx<crate::Usd, MyClock>`. Methods naming the concrete types, such as
+//! `concrete_type_id`, aren't generated for partial enums.
+//!
+//! ## Per-Type Impl Blocks
+//!
+//! `#[concrete_impls(Exchange)]` on a generic impl block, e.g.
+//! `impl<E: ExchangeApi> TradingSystem<E>`, emits the block once per concrete type of
+//! `Exchange` instead, with `E` aliased to that type. See [`macro@concrete_impls`].
+//!
+//! ## Macro Namespacing
+//!
+//! By default the generated macros are exported at the crate root. With
+//! `#[concrete(macros_mod = "dispatch")]` on the enum, the macro is instead re-exported from a
+//! `dispatch` module generated next to the enum, and is invoked as `dispatch::exchange!`.
+//! The module is generated per enum, so enums sharing a `macros_mod` name must live in
+//! different modules. As with root macros, the enum must be in scope at the call site.
+//!
+//! ## Re-exporting Through Facade Crates
+//!
+//! Generated macros are exported at the defining crate's root, so a facade crate re-exports them
+//! next to the enum with `pub use defining_crate::{Exchange, exchange};`. By default, `crate::`
+//! concrete types expand to `$crate::`, i.e. the defining crate. When the types should instead be
+//! reached through the facade's public paths, set `#[concrete(crate_path = "::facade")]` and
+//! `crate::path::Type` expands to `::facade::path::Type`.
+//!
+//! In mixed workspaces, where some consumers re-export the mapped types under the same paths
+//! from their own crate root, `#[concrete(relative_macro)]` additionally generates an
+//! `exchange_relative!` flavor. It expands `crate::` concrete types to the calling crate's
+//! `crate::` instead of `$crate::`, while `exchange!` keeps resolving them in the defining crate.
+//!
+//! `#[macro_export]` forces generated macros to be public. Add `#[concrete(doc_hidden)]` to keep
+//! them, along with the generated methods and types, out of the library's rustdoc.
+//!
+//! Exported macros share a single namespace in the final binary crate, so two dependencies
+//! both deriving an `exchange!` macro collide. `#[concrete(prefix_crate_name)]` prefixes the
+//! generated macro with the defining crate's name, e.g. `mycrate_exchange!`.
+//!
+//! ## Feature-Gated Variants
+//!
+//! A variant marked `#[concrete(feature = "binance")]` is kept when the `binance` feature is
+//! disabled, so configs mentioning it still parse. Dispatching on it then binds a stub type whose
+//! `new` accepts any config and returns an error naming the feature, and the generated factory
+//! returns a `CreateError` with `required_feature()` set. The feature is checked against the
+//! features of the crate deriving the enum, including when the dispatch macro is invoked from
+//! another crate.
+//!
+//! `cargo_feature` is accepted as another spelling of `feature`. The generated
+//! `required_feature()` method returns a variant's feature, and `enabled_variants()` lists the
+//! variants of a unit-only enum whose feature is enabled in the defining crate, e.g. to report
+//! the backends a binary was built with.
+//!
+//! ## Generation Levels
+//!
+//! By default both derives generate a set of methods and tables besides the dispatch macros:
+//! concrete type names, `TypeId` methods, feature lookups, the `{Enum}DowncastError` and
+//! `{Enum}DispatchError` types with the `@try` dispatch form, and the diagram used by
+//! `gen_mapping_diagram!`. For large enums in compile-time-sensitive crates,
+//! `#[concrete(minimal)]` leaves those out. It keeps:
+//!
+//! - the dispatch macro, which is the only macro exported at either level unless an option such
+//!   as `for_each`, `compare`, `fallback` or `relative_macro` requests another one
+//! - `mapping_fingerprint`, compared by `assert_same_mapping!`
+//! - `variant_index`, `VARIANT_COUNT` and `from_index`, which memoized combined matchers and the
+//!   tables of options such as `set` and `per_variant` index by
+//! - `tag` and `from_tag`, generated only when a variant sets a tag
+//! - everything other options request, except `selftest`, which checks the methods left out
+//!
+//! `#[concrete(full)]` states the default level explicitly.
+//!
+//! ## Cargo Features
+//!
+//! - `config-any` (default) - Generates the `config(&self) -> &dyn Any` method for
+//!   [`ConcreteConfig`] enums. Disable default features to omit it, e.g. for crates with a
+//!   no-`Any` policy. The dispatch macros are generated either way.
+//! - `async` - Allows `#[concrete(check_all)]`, generating an async `check_all` method running a
+//!   probe against the concrete type of every variant concurrently.
+//! - `phf` - Adds a `from_name_fast` lookup next to the const `from_name` generated by
+//!   `#[concrete(from_name)]`, backed by a perfect hash map computed at compile time instead of a
+//!   string match, and parses names through it in `FromStr`. The crate deriving the enum must
+//!   depend on `phf` 0.11.
+//! - `serde` - Allows `#[concrete(from_named_value)]` on [`ConcreteConfig`] enums, generating a
+//!   `from_named_value` method building a variant from its name and a `serde_json::Value`. The
+//!   crate deriving the enum must depend on `serde_json`. Also allows
+//!   `#[concrete(encode = "serde")]`, serializing variants as their tag, and
+//!   `#[concrete(derive(Serialize))]` on [`ConcreteConfig`] enums, for which the crate must
+//!   depend on `serde`.
+//! - `rand` - Allows `#[concrete(weight = ...)]` on variants, generating a `choose_weighted`
+//!   function picking a variant at random in proportion to its weight. The crate deriving the
+//!   enum must depend on `rand` 0.9.
+//! - `wasm` - Allows `#[concrete(wasm)]`, generating an `{Enum}Wasm` numeric enum exported to
+//!   JavaScript with `#[wasm_bindgen]` and conversions to and from the kind enum. The crate
+//!   deriving the enum must depend on `wasm-bindgen` 0.2.
+//! - `web` - Allows `#[concrete(web)]` alongside `from_name`, implementing axum's
+//!   `FromRequestParts` for the enum by parsing a path parameter, and `TryFrom<&HeaderValue>`,
+//!   both rejecting unknown names with a `400 Bad Request` listing the valid ones. The crate
+//!   deriving the enum must depend on `axum` 0.8.
+//! - `either` - Allows `#[concrete(either)]` on [`ConcreteConfig`] enums of exactly two variants,
+//!   generating `as_either`, `into_either` and an `either` method calling one of two closures
+//!   with the config. The crate deriving the enum must depend on `either`.
+//! - `expand-debug` - Makes the generated dispatch macros print the enum, variant, concrete type
+//!   and call site of each dispatch to stderr in builds with `debug_assertions`.
+//!
 //! ## Examples
 //!
 //! ### Basic Usage with `Concrete`
@@ -116,147 +298,57 @@
 
 extern crate proc_macro;
 
+mod delegate;
+mod dispatch;
+mod generate;
+mod input;
+mod options;
+mod paths;
+
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Attribute, DeriveInput, Expr, Fields, Lit, Meta, parse_macro_input};
-
-/// Helper function to extract concrete type path from an attribute
-fn extract_concrete_type_path(attrs: &[Attribute]) -> Option<syn::Path> {
-    for attr in attrs {
-        if attr.path().is_ident("concrete") {
-            if let Meta::NameValue(meta) = &attr.meta {
-                if let Expr::Lit(expr_lit) = &meta.value {
-                    if let Lit::Str(lit_str) = &expr_lit.lit {
-                        return syn::parse_str::<syn::Path>(&lit_str.value()).ok();
-                    }
-                }
-            }
-        }
-    }
-    None
-}
-
-/// Transforms a path for use in generated macro code.
-///
-/// If the path starts with `crate::`, it transforms to `$crate::` for proper
-/// macro hygiene. This allows the generated macro to work correctly both within
-/// the defining crate and from external crates.
-///
-/// This function also recursively transforms any `crate::` paths inside generic
-/// arguments (e.g., `Wrapper<crate::inner::Type>` becomes `Wrapper<$crate::inner::Type>`).
-///
-/// Paths that don't start with `crate::` are returned as-is (after processing their generics).
-fn transform_path_for_macro(path: &syn::Path) -> proc_macro2::TokenStream {
-    let starts_with_crate = path
-        .segments
-        .first()
-        .map(|s| s.ident == "crate")
-        .unwrap_or(false);
-
-    // Process each segment, transforming generic arguments recursively
-    let transformed_segments: Vec<proc_macro2::TokenStream> = path
-        .segments
-        .iter()
-        .enumerate()
-        .filter_map(|(i, segment)| {
-            // Skip the leading `crate` segment if present
-            if starts_with_crate && i == 0 {
-                return None;
-            }
-
-            let ident = &segment.ident;
-            let args = transform_path_arguments(&segment.arguments);
-
-            Some(quote! { #ident #args })
-        })
-        .collect();
-
-    if starts_with_crate && !transformed_segments.is_empty() {
-        quote! { $crate :: #(#transformed_segments)::* }
-    } else if transformed_segments.is_empty() {
-        // Path was just `crate` with no following segments - unusual but handle it
-        quote! { #path }
-    } else {
-        quote! { #(#transformed_segments)::* }
-    }
-}
-
-/// Transform path arguments (generic parameters), recursively handling nested `crate::` paths.
-fn transform_path_arguments(args: &syn::PathArguments) -> proc_macro2::TokenStream {
-    match args {
-        syn::PathArguments::None => quote! {},
-        syn::PathArguments::AngleBracketed(angle) => {
-            let transformed_args: Vec<proc_macro2::TokenStream> = angle
-                .args
-                .iter()
-                .map(|arg| match arg {
-                    syn::GenericArgument::Type(ty) => transform_type(ty),
-                    syn::GenericArgument::Lifetime(lt) => quote! { #lt },
-                    syn::GenericArgument::Const(expr) => quote! { #expr },
-                    other => quote! { #other },
-                })
-                .collect();
-            quote! { < #(#transformed_args),* > }
-        }
-        syn::PathArguments::Parenthesized(paren) => {
-            let inputs: Vec<_> = paren.inputs.iter().map(transform_type).collect();
-            let output = match &paren.output {
-                syn::ReturnType::Default => quote! {},
-                syn::ReturnType::Type(arrow, ty) => {
-                    let transformed = transform_type(ty);
-                    quote! { #arrow #transformed }
-                }
-            };
-            quote! { ( #(#inputs),* ) #output }
-        }
-    }
-}
+use syn::{DeriveInput, Fields, parse_macro_input};
 
-/// Transform a type, recursively handling `crate::` paths within.
-fn transform_type(ty: &syn::Type) -> proc_macro2::TokenStream {
-    match ty {
-        syn::Type::Path(type_path) => {
-            let transformed = transform_path_for_macro(&type_path.path);
-            if let Some(qself) = &type_path.qself {
-                let qself_ty = transform_type(&qself.ty);
-                quote! { < #qself_ty > :: #transformed }
-            } else {
-                transformed
-            }
-        }
-        syn::Type::Reference(ref_type) => {
-            let lifetime = &ref_type.lifetime;
-            let mutability = &ref_type.mutability;
-            let elem = transform_type(&ref_type.elem);
-            quote! { & #lifetime #mutability #elem }
-        }
-        syn::Type::Tuple(tuple) => {
-            let elems: Vec<_> = tuple.elems.iter().map(transform_type).collect();
-            quote! { ( #(#elems),* ) }
-        }
-        syn::Type::Slice(slice) => {
-            let elem = transform_type(&slice.elem);
-            quote! { [ #elem ] }
-        }
-        syn::Type::Array(array) => {
-            let elem = transform_type(&array.elem);
-            let len = &array.len;
-            quote! { [ #elem ; #len ] }
-        }
-        syn::Type::Ptr(ptr) => {
-            let mutability = if ptr.mutability.is_some() {
-                quote! { mut }
-            } else {
-                quote! { const }
-            };
-            let elem = transform_type(&ptr.elem);
-            quote! { * #mutability #elem }
-        }
-        // For other types, just quote them as-is
-        other => quote! { #other },
-    }
-}
+use crate::delegate::{delegate_macro_name, delegate_method};
+use crate::dispatch::concrete::generate_concrete_macros;
+use crate::dispatch::config::generate_config_macros;
+use crate::dispatch::{dispatch_macro_name, generate_unsupported_stubs};
+use crate::generate::checks::{generate_check_all, generate_selftest};
+use crate::generate::config::{
+    generate_config_method, generate_either, generate_forwarded_derives,
+};
+use crate::generate::describe::{
+    generate_api_snapshot, generate_diagram, generate_emit_with, generate_manifest,
+    generate_mapping_fingerprint,
+};
+use crate::generate::encoding::{generate_encoding, generate_wasm};
+use crate::generate::errors::{
+    generate_dispatch_error, generate_downcast_error, generate_failover_error,
+    generate_fallback_types, generate_type_mismatch,
+};
+use crate::generate::factory::{generate_factory, generate_handle, generate_instance};
+use crate::generate::methods::{
+    generate_as_str, generate_build_info, generate_debug, generate_feature_methods,
+    generate_index_methods, generate_iter_with_types, generate_routing_key, generate_tag_methods,
+    generate_type_id_methods, generate_type_name_items,
+};
+use crate::generate::names::{generate_from_name, generate_from_named_value, generate_web};
+use crate::generate::selection::{generate_priority_items, generate_weighted_choice};
+use crate::generate::sets::{generate_per_variant, generate_set};
+use crate::generate::subset::generate_subset;
+use crate::generate::types::{
+    generate_concrete_of, generate_family, generate_keys, generate_tagged, generate_type_list,
+};
+use crate::input::{
+    ConcreteImplsInput, MatchConcretesInput, RegistryInput, VisitedEnum, Visitor2Input,
+};
+use crate::options::{
+    ConfigField, check_concrete_options, check_config_options, parse_concrete_variants,
+    parse_config_variants, parse_enum_options, parse_subset_options, parse_variant_options,
+    resolve_concrete_type_path,
+};
+use crate::paths::{concrete_path_string, transform_path_for_macro};
 
 /// A derive macro that implements the mapping between enum variants and concrete types.
 ///
@@ -268,12 +360,174 @@ fn transform_type(ty: &syn::Type) -> proc_macro2::TokenStream {
 ///
 /// - Use `crate::path::to::Type` for types in the same crate (transforms to `$crate::`)
 /// - Use `other_crate::path::to::Type` for types from external crates (used as-is)
+/// - With `#[concrete(crate_path = "::facade")]` on the enum, `crate::` expands to `::facade::`
+///
+/// Paths may contain `{Variant}`/`{variant}` placeholders, and variants without an attribute
+/// fall back to the enum-level `#[concrete(base = "...")]` template.
+///
+/// # Nested Enums
+///
+/// A variant marked `#[concrete(nested)]` holds another `Concrete` enum in its single field
+/// instead of mapping to a concrete type, e.g. `Okx(OkxAccount)`. The dispatch macro hands the
+/// inner value to the inner enum's macro (by its default snake_case name, which must be in scope),
+/// so the block sees the innermost concrete type. When the inner enum is defined in another
+/// crate, `#[concrete(nested_extern)]` reaches its macro at the root of the crate named by the
+/// first segment of the field's path, e.g. `Dex(dex::DexExchange)` dispatches with
+/// `dex::dex_exchange!`. When the inner macro is renamed by `macros_mod` or `prefix_crate_name`,
+/// name it with `#[concrete(nested = "path::to::macro")]`, where `crate::` resolves like concrete
+/// types. Nested variants can't be fixed with `@variant` or listed in a variant subset, and
+/// methods such as `concrete_type_id` report the inner enum type itself.
+///
+/// With `#[concrete(flatten)]` on the outer enum, the dispatch macro instead expands to a single
+/// match over the cross product of outer and inner variants (e.g. `Exchange::Okx(OkxAccount::Spot)`),
+/// avoiding a second match at runtime. Inner variants sharing a concrete type still share an
+/// or-pattern arm. The guarded form keeps dispatching through the inner macros.
 ///
 /// # Generated Code
 ///
-/// The macro generates a macro with the snake_case name of the enum
-/// (e.g., `exchange!` for `Exchange`, `strategy_kind!` for `StrategyKind`) that can be used
-/// to execute code with the concrete type.
+/// The macro generates:
+/// 1. A macro with the snake_case name of the enum (e.g., `exchange!` for `Exchange`,
+///    `strategy_kind!` for `StrategyKind`) that can be used to execute code with the concrete
+///    type. `exchange!(value; T => { ... } where |value| guard)` only runs the block when the
+///    guard holds, evaluating to an `Option`. `exchange!(value; [Binance, Okx]; T => { ... } else
+///    { ... })` only expands the block for the listed variants, routing the others to the else
+///    block. `exchange!(@variant Binance; T => { ... })` aliases
+///    the concrete type of a variant named at compile time without matching, as used by combined
+///    matchers fixing an enum. `exchange!(@try value; T => { ... })` runs a block returning a
+///    `Result`, wrapping its error in an `{Enum}DispatchError` naming the variant and its concrete
+///    type, which the `wrap_err` method also builds from a variant.
+/// 2. A `mapping_fingerprint` const function returning a stable hash of the variant to concrete
+///    type mapping, and a const `concrete_type_name` method along with a `{VARIANT}_TYPE_NAME`
+///    constant per variant holding the concrete type path. `type_name_short` returns only its
+///    final segment, and `metric_labels` pairs it with the variant name as `kind`/`impl` labels.
+/// 3. `concrete_type_id` and `same_concrete_as` methods to compare variants by their concrete
+///    type without dispatching.
+///    `try_into_concrete`/`try_into_concrete_with` build the concrete value only when the
+///    variant maps to the requested type, and `downcast_instance` downcasts a `Box<dyn Any>` to
+///    it, failing with an `{Enum}DowncastError` naming the variant's concrete type. For enums of
+///    unit variants, `iter_with_types` lists every variant with its concrete type name and
+///    `TypeId`. `required_feature` returns a variant's Cargo feature gate, and for enums of unit
+///    variants `enabled_variants` lists the variants whose feature is enabled. `variant_index`
+///    numbers the variants densely in declaration order, up to `VARIANT_COUNT`, and
+///    `from_index` inverts it for enums of unit variants.
+/// 4. With `#[concrete(factory = "path::to::Trait")]` on the enum, an `{Enum}Factory` trait
+///    implemented by the enum, whose `create` method downcasts a `Box<dyn Any>` config to the
+///    variant's config type and returns the constructed `Box<dyn Trait>`, or an
+///    `{Enum}CreateError` on a config type mismatch. The trait must declare
+///    `type Config where Self: Sized;` and `fn new(config: Self::Config) -> Self where Self: Sized;`
+///    so it stays object safe. Variants with `#[concrete(constructor = "try_new")]` are built
+///    through that fallible constructor instead, and `create` then returns an
+///    `{Enum}ConstructError` wrapping constructor errors with the variant name. Marking variants
+///    `#[concrete(non_send)]` also generates an `{Enum}Send` enum over the other variants,
+///    implementing an `{Enum}SendFactory` trait whose `create` returns `Box<dyn Trait + Send>`.
+/// 5. With `#[concrete(new_default)]` on the enum, asserting every concrete type implements
+///    `Default`, an `{Enum}Instance` enum with a variant holding each concrete type, and a
+///    `new_default` method building the default instance of the variant's concrete type.
+///    Traits annotated with `#[concrete_delegate]` and named with
+///    `#[concrete(delegate_trait = "path::to::Trait")]` are implemented on the instance enum.
+/// 6. With `#[concrete(manifest)]` on the enum, a `CONCRETE_MANIFEST` constant holding a JSON
+///    description of the variants, their concrete type paths and feature gates.
+/// 7. With `#[concrete(set)]` on an enum of unit variants, an `{Enum}Set` bitset with `contains`,
+///    `insert`, `remove` and `iter` methods, and an `in_set` method on the enum.
+/// 8. With `#[concrete(from_name)]` on an enum of unit variants, a const `from_name` method and a
+///    `FromStr` implementation parsing variant names and `#[concrete(alias = "...")]` aliases,
+///    failing with an `{Enum}ParseError`.
+/// 9. With `#[concrete(keys)]` on the enum, an `{enum}_keys` module holding a zero-sized key type
+///    per variant, implementing its `Key` trait with the variant's concrete type as
+///    `Key::Concrete`, along with an `{Enum}Key` enum over them returned by a `key` method.
+/// 10. With `#[concrete(debug)]` on the enum, a `Debug` implementation printing the variant with
+///     its concrete type path, e.g. `Binance → crate::exchanges::Binance`, in place of
+///     `#[derive(Debug)]`.
+/// 11. With `#[concrete(check_all)]` (or `check_all = "path::to::Bound"`) on an enum of unit
+///     variants and the `async` feature enabled, an `{Enum}Probe` trait with a generic async
+///     `probe` method, and an async `check_all` method running a probe against the concrete type
+///     of every variant concurrently.
+/// 12. With `#[concrete(handle)]` on an enum of unit variants, an `{Enum}VTable` struct holding
+///     the variant, its name, tag, concrete type path and constructor function pointers, a
+///     constant vtable per variant returned by a `vtable` method, and a copyable,
+///     pointer-sized `{Enum}Handle` to it returned by a `handle` method.
+/// 13. With `#[concrete_subset(name = "SpotExchange", variants = "Binance, Okx")]` on the enum, a
+///     `SpotExchange` enum holding the listed variants and deriving `Concrete` with the same
+///     concrete types, along with `From<SpotExchange>` for the enum and `TryFrom` back, failing
+///     with the variant itself outside the subset. `derive(Clone, Copy)` in the attribute derives
+///     those traits on the subset enum.
+/// 14. With `#[concrete(priority = ...)]` on every variant of an enum of unit variants, a const
+///     `priority` method, an `iter_by_priority` function listing the variants by priority, lowest
+///     first, then in declaration order, and `PartialOrd`/`Ord` implementations following the
+///     same order.
+/// 15. With `#[concrete(failover)]` on the enum, an
+///     `exchange!(@failover variants; T => { ... })` dispatch form running a block returning a
+///     `Result` against each variant of an iterator in turn, returning the first success or an
+///     `{Enum}FailoverError` holding every variant's error.
+/// 16. With `#[concrete(weight = ...)]` on every variant of an enum of unit variants (requires
+///     the `rand` feature), a const `weight` method and a `choose_weighted` function picking a
+///     variant at random with a probability proportional to its weight.
+/// 17. With `#[concrete(selftest)]` on an enum of unit variants, a `#[cfg(test)]` module named
+///     `{enum}_selftest` whose tests check that the generated indices, tags, names, `TypeId`s,
+///     handles and priorities agree with the mapping.
+/// 18. With `#[concrete(per_variant)]` on an enum of unit variants, a `Per{Enum}<T>` array
+///     holding a value per variant, indexed by the variants, built with `from_fn` or
+///     `try_from_fn`.
+/// 19. With `#[concrete(family)]` on an enum of unit variants, a zero-sized `{Variant}Kind`
+///     marker type per variant implementing an `{Enum}Family` trait, whose `Concrete` type and
+///     `KIND` constant are the variant's concrete type and the variant itself. Markers convert
+///     into the enum with `From`, and the enum into a marker with `TryFrom`.
+/// 20. With `#[concrete(type_list)]` on an enum of unit variants, an `{Enum}ConcreteTypes`
+///     tuple of the concrete types and an `{Enum}TypeVisitor` trait, called with each of them
+///     by a `visit_concrete_types` function. `type_list = "crate::Trait"` bounds the visited
+///     types by the trait.
+/// 21. With `#[concrete(concrete_of)]` on an enum of unit variants, implementations of an
+///     `{Enum}ConcreteOf<const V: usize>` trait (or the one given, as in
+///     `concrete_of = "my_lib::ConcreteOf"`) for the index of each variant, with the concrete
+///     type as `Ty`, and an `{Enum}Concrete<V>` alias.
+/// 22. With `#[concrete(wasm)]` on an enum of unit variants (requires the `wasm` feature), an
+///     `{Enum}Wasm` enum exported to JavaScript under the enum's name with `#[wasm_bindgen]`,
+///     holding the variant indices, and `From` conversions to and from the enum.
+/// 23. With `#[concrete(encode)]` on an enum of unit variants, each with a `u16` tag, const
+///     `encode` and `decode` methods converting between the variants and their tags, and with
+///     `encode = "serde"` (requires the `serde` feature), `Serialize` and `Deserialize`
+///     implementations writing the tag.
+/// 24. With `#[concrete(web)]` alongside `from_name` (requires the `web` feature), axum's
+///     `FromRequestParts` implemented for the enum by parsing the path parameter named after it
+///     (or the one given, as in `web = "venue"`), a `TryFrom<&HeaderValue>` implementation, and
+///     the `{Enum}Rejection` they fail with, answering with a JSON list of the valid names.
+/// 25. With `#[concrete(max_combinations = N)]` on the enum, a compile-time check in every call
+///     of a combined matcher from `concrete-type-rules` including the enum that its block is
+///     expanded at most `N` times.
+/// 26. With `#[concrete(api_snapshot)]` on the enum, a hidden `CONCRETE_API` constant listing
+///     the items generated for the enum, one per line and sorted, as combined by
+///     `concrete_type_rules::gen_api_snapshot!`.
+/// 27. With `#[concrete(for_each)]` on the enum, a `for_each_{enum}!` macro (e.g.
+///     `for_each_exchange!(T => { ... })`) expanding the block once per variant with `T`
+///     aliasing its concrete type, in declaration order. Nested variants expand it for each
+///     variant of the inner enum, and variants whose feature is disabled are skipped. Unless
+///     `#[concrete(partial)]` is set, the dispatch macro accepts the same form as
+///     `exchange!(@for_each T => { ... })` without the option.
+/// 28. With `#[concrete(tagged)]` on an enum of unit variants, an `{Enum}Tagged<T>` wrapper
+///     pairing a value with a variant, and a `tagged` method on every value inside dispatch
+///     blocks (e.g. `T::new(config).tagged()`) wrapping it with the dispatched variant. Each
+///     variant gets its own match arm, even when it shares its concrete type with others.
+/// 29. When a variant maps to a cluster of concrete types with
+///     `#[concrete_all = "crate::BinanceSpot, crate::BinanceMargin"]`, an
+///     `exchange!(@all value; T => { ... })` form collecting the block's result for every type
+///     of the matched variant's cluster into a `Vec`, and an
+///     `exchange!(@fold value, init; (acc, T) => { ... })` form folding them, in the order
+///     listed. Other variants run the block once. Everywhere else, a cluster stands in for its
+///     first concrete type.
+/// 30. With `#[concrete(emit_with = "path::to::macro")]` on the enum, an invocation of the named
+///     macro next to the enum per variant, as `macro!(Variant, path::to::Type, ())`, for glue
+///     generated by the deriving crate. Each type of a cluster gets its own invocation, nested
+///     variants get none, and variants whose feature is disabled are skipped.
+/// 31. With `#[concrete(routing = "exch.{snake}.events")]` on the enum, a const `routing_key`
+///     method returning the template with the variant's name substituted, e.g. for message bus
+///     topics.
+/// 32. With `#[concrete(as_str)]` (or `as_str = "kebab"` and other cases) on the enum, const
+///     `as_str` and `name` methods returning the variant's name, checked at compile time to be
+///     unique after converting its case.
+/// 33. With `#[concrete(build_info)]` on the enum, a const `build_info` function summarizing the
+///     compiled mapping for `--version` output and startup logs: the deriving crate's name and
+///     version, then each variant with its concrete type, tag and feature, and whether the
+///     feature is enabled.
 ///
 /// # Example
 ///
@@ -298,10 +552,35 @@ fn transform_type(ty: &syn::Type) -> proc_macro2::TokenStream {
 ///
 /// This enables type-level programming with enums, where you can define enum variants and
 /// map them to concrete type implementations.
-#[proc_macro_derive(Concrete, attributes(concrete))]
+///
+/// Variants whose names only differ in case would share a `{VARIANT}_TYPE_NAME` constant and
+/// are rejected:
+///
+/// ```compile_fail
+/// use concrete_type::Concrete;
+///
+/// pub struct Client;
+///
+/// #[derive(Concrete)]
+/// enum Transport {
+///     #[concrete = "crate::Client"]
+///     HttpClient,
+///     #[concrete = "crate::Client"]
+///     HTTPClient,
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(Concrete, attributes(concrete, concrete_subset, concrete_all))]
 pub fn derive_concrete(input: TokenStream) -> TokenStream {
+    expand_concrete(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands `#[derive(Concrete)]`, see [`derive_concrete`].
+fn expand_concrete(input: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
     // Parse the input tokens into a syntax tree
-    let input = parse_macro_input!(input as DeriveInput);
+    let input = syn::parse::<DeriveInput>(input)?;
 
     // Extract the name of the type
     let type_name = &input.ident;
@@ -309,289 +588,2186 @@ pub fn derive_concrete(input: TokenStream) -> TokenStream {
     // Create a snake_case version of the type name for the macro_rules! name
     let type_name_str = type_name.to_string();
     let macro_name_str = type_name_str.to_case(Case::Snake);
-    let macro_name = syn::Ident::new(&macro_name_str, type_name.span());
 
     // Handle enum case
     let data_enum = match &input.data {
         syn::Data::Enum(data_enum) => data_enum,
         _ => {
-            return syn::Error::new_spanned(
+            return Err(syn::Error::new_spanned(
                 type_name,
                 "Concrete can only be derived for enums or structs with type parameters",
-            )
-            .to_compile_error()
-            .into();
+            ));
         }
     };
+    if data_enum.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "Concrete can't be derived for an enum without variants, its dispatch macro would \
+             have no concrete type to alias",
+        ));
+    }
+
+    // Parse enum-level options, e.g. #[concrete(base = "...")]
+    let options = parse_enum_options(&input.attrs)?;
+    let macro_name = dispatch_macro_name(&macro_name_str, type_name, &options)?;
+    check_concrete_options(type_name, &options)?;
 
     // Extract variant names and their concrete types
-    let mut variant_mappings = Vec::new();
+    let variant_mappings = parse_concrete_variants(data_enum, &options)?;
 
-    for variant in &data_enum.variants {
-        let variant_name = &variant.ident;
+    // Listing every variant requires them all to be constructible without data
+    let all_unit = data_enum
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit));
 
-        // Extract the concrete type path from the variant's attributes
-        if let Some(concrete_type) = extract_concrete_type_path(&variant.attrs) {
-            variant_mappings.push((variant_name, concrete_type));
-        } else {
-            // Variant is missing the #[concrete = "..."] attribute
-            return syn::Error::new_spanned(
-                variant_name,
-                format!(
-                    "Enum variant `{}` is missing the #[concrete = \"...\"] attribute",
-                    variant_name
-                ),
-            )
-            .to_compile_error()
-            .into();
-        }
-    }
+    // Generate the dispatch macro and its companions
+    let macro_def = generate_concrete_macros(
+        type_name,
+        &macro_name,
+        &macro_name_str,
+        &variant_mappings,
+        all_unit,
+        &options,
+    )?;
 
-    // Generate match arms for the macro_rules! version
-    let macro_match_arms = variant_mappings
-        .iter()
-        .map(|(variant_name, concrete_type)| {
-            let transformed_path = transform_path_for_macro(concrete_type);
-            quote! {
-                #type_name::#variant_name => {
-                    type $type_param = #transformed_path;
-                    $code_block
-                }
-            }
-        });
+    // Generate the methods implementation
+    // Checked by `assert_same_mapping!`, so generated whatever the generation level
+    let mapping_fingerprint = generate_mapping_fingerprint(
+        variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _)| (*variant_name, concrete_type)),
+        &options,
+    );
+    // `TypeId`-based methods need complete concrete types, so they're omitted for `partial`
+    let type_id_methods = (!options.partial).then(|| {
+        generate_type_id_methods(
+            type_name,
+            &variant_mappings
+                .iter()
+                .map(|(variant_name, concrete_type, variant_options)| {
+                    (
+                        *variant_name,
+                        concrete_type,
+                        variant_options.feature.as_ref(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            &options,
+        )
+    });
 
-    // Generate a top-level macro with the snake_case name of the enum
-    let macro_def = quote! {
-        #[macro_export]
-        macro_rules! #macro_name {
-            ($enum_instance:expr; $type_param:ident => $code_block:block) => {
-                match $enum_instance {
-                    #(#macro_match_arms),*
-                }
-            };
-        }
-    };
+    let iter_method = (all_unit && !options.partial)
+        .then(|| generate_iter_with_types(type_name, &variant_mappings, &options));
 
-    // Combine the macro definition and methods implementation
-    let expanded = quote! {
-        // Define the macro outside any module to make it directly accessible
-        #macro_def
-    };
+    let type_name_items = generate_type_name_items(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _)| (*variant_name, concrete_type))
+            .collect::<Vec<_>>(),
+        &options,
+    )?;
+    let feature_methods = generate_feature_methods(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| {
+                (*variant_name, variant_options.feature.as_ref())
+            })
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    );
+    let index_methods = generate_index_methods(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _)| *variant_name)
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    );
+    let tag_methods = generate_tag_methods(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| (*variant_name, variant_options.tag.as_ref()))
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    )?;
+    let routing_key = generate_routing_key(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _)| *variant_name)
+            .collect::<Vec<_>>(),
+        &options,
+    )?;
+    let as_str = generate_as_str(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _)| *variant_name)
+            .collect::<Vec<_>>(),
+        &options,
+    )?;
+    let build_info = generate_build_info(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, variant_options)| {
+                (*variant_name, concrete_type, variant_options)
+            })
+            .collect::<Vec<_>>(),
+        &options,
+    );
 
-    // Return the generated implementation
-    TokenStream::from(expanded)
-}
+    let manifest = options.manifest.then(|| {
+        let entries: Vec<_> = variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, variant_options)| {
+                (*variant_name, concrete_type, None, variant_options)
+            })
+            .collect();
+        generate_manifest(type_name, &entries, &options)
+    });
 
-/// A derive macro that implements the mapping between enum variants with associated data and
-/// concrete types.
-///
-/// This macro is designed for enums where each variant has associated configuration data and maps
-/// to a specific concrete type. Each variant must be annotated with the
-/// `#[concrete = "path::to::Type"]` attribute and contain a single field (no tuples)
-/// that holds the configuration data for that concrete type. If the variant has no data, then it
-/// defaults to the unit type `()`.
-///
-/// # Path Resolution
-///
-/// - Use `crate::path::to::Type` for types in the same crate (transforms to `$crate::`)
-/// - Use `other_crate::path::to::Type` for types from external crates (used as-is)
-///
-/// # Generated Code
-///
-/// The macro generates:
-/// 1. A `config` method that returns a reference to the configuration data.
-/// 2. A macro with the snake_case name of the enum + "_config" (with "Config" suffix removed if present)
-///    that allows access to both the concrete type and configuration data
-///
-/// # Example
-///
-/// ```rust,ignore
-/// use concrete_type::ConcreteConfig;
-///
-/// // Define concrete types and configuration types
-/// #[derive(Debug)]
-/// struct BinanceConfig {
-///     api_key: String,
-/// }
-///
-/// struct Binance;
-///
-/// struct Okx;
-///
-/// #[derive(ConcreteConfig)]
-/// enum ExchangeConfig {
-///     #[concrete = "Binance"]
-///     Binance(BinanceConfig),
-///     #[concrete = "Okx"]
-///     Okx,
-/// }
-///
-/// // Using the generated macro for a variant with config data
-/// let config = ExchangeConfig::Binance(BinanceConfig { api_key: "key".to_string() });
-/// let result = exchange_config!(config; (Exchange, cfg) => {
-///     // "Exchange" symbol is concrete type Binance
-///     // "cfg" symbol is a reference to the BinanceConfig instance
-///     format!("{} with config: {:?}", std::any::type_name::<Exchange>(), cfg)
-/// });
-///
-/// // Using the generated macro for a variant without config data
-/// let config = ExchangeConfig::Okx;
-/// let result = exchange_config!(config; (Exchange, cfg) => {
-///     // "Exchange" symbol is concrete type Okx
-///     // "cfg" symbol is a reference to the unit type () (since the Okx variant doesn't have config)
-///     format!("{} with config: {:?}", std::any::type_name::<Exchange>(), cfg)
-/// });
-/// ```
-#[proc_macro_derive(ConcreteConfig, attributes(concrete))]
-pub fn derive_concrete_config(input: TokenStream) -> TokenStream {
-    // Parse the input tokens into a syntax tree
-    let input = parse_macro_input!(input as DeriveInput);
+    let diagram = generate_diagram(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _)| (*variant_name, concrete_type))
+            .collect::<Vec<_>>(),
+    );
 
-    // Extract the name of the type
-    let type_name = &input.ident;
+    // With #[concrete(minimal)], the default methods and tables are left out, except the variant
+    // indices and tags that other generated items rely on
+    let methods_impl = if options.minimal {
+        quote! {
+            impl #type_name {
+                #mapping_fingerprint
 
-    // Create a snake_case version of the type name for the macro_rules! name
-    let type_name_str = type_name.to_string();
-    // Strip "Config" suffix if present for cleaner macro names
-    let base_name = if type_name_str.ends_with("Config") {
-        &type_name_str[0..type_name_str.len() - 6]
-    } else {
-        &type_name_str
-    };
-    let macro_name_str = format!("{}_config", base_name.to_case(Case::Snake));
-    let macro_name = syn::Ident::new(&macro_name_str, type_name.span());
+                #index_methods
 
-    // Ensure we're dealing with an enum
-    let data_enum = match &input.data {
-        syn::Data::Enum(data_enum) => data_enum,
-        _ => {
-            return syn::Error::new_spanned(
-                type_name,
-                "ConcreteConfig can only be derived for enums with data",
-            )
-            .to_compile_error()
-            .into();
-        }
-    };
+                #tag_methods
 
-    // Extract variant names, their concrete types, and field types
-    // We now include a boolean flag to indicate if the variant has config data
-    let mut variant_mappings = Vec::new();
+                #routing_key
 
-    for variant in &data_enum.variants {
-        let variant_name = &variant.ident;
+                #as_str
 
-        // Extract the concrete type path from the variant's attributes
-        if let Some(concrete_type) = extract_concrete_type_path(&variant.attrs) {
-            // Check variant field type - now accepting both unit variants and single-field variants
-            match &variant.fields {
-                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
-                    // Variant with config data
-                    variant_mappings.push((variant_name, concrete_type, true));
-                }
-                Fields::Unit => {
-                    // Unit variant (no config data)
-                    variant_mappings.push((variant_name, concrete_type, false));
-                }
-                _ => {
-                    return syn::Error::new_spanned(
-                        variant_name,
-                        format!(
-                            "Enum variant `{}` must either be a unit variant or have exactly one unnamed field for config",
-                            variant_name
-                        ),
-                    )
-                        .to_compile_error()
-                        .into();
-                }
+                #build_info
             }
-        } else {
-            // Variant is missing the #[concrete = "..."] attribute
-            return syn::Error::new_spanned(
-                variant_name,
-                format!(
-                    "Enum variant `{}` is missing the #[concrete = \"...\"] attribute",
-                    variant_name
-                ),
-            )
-            .to_compile_error()
-            .into();
         }
-    }
+    } else {
+        quote! {
+            impl #type_name {
+                #type_name_items
 
-    // Generate match arms for the config method
-    let config_arms = variant_mappings
-        .iter()
-        .map(|(variant_name, _concrete_type, has_config)| {
-            if *has_config {
-                quote! {
-                    #type_name::#variant_name(config) => config
-                }
-            } else {
-                quote! {
-                    #type_name::#variant_name => &() // Return unit type for variants w/o config
-                }
-            }
-        });
+                #diagram
 
-    // Generate match arms for the macro_rules! version
-    let macro_match_arms =
-        variant_mappings
-            .iter()
-            .map(|(variant_name, concrete_type, has_config)| {
-                let transformed_path = transform_path_for_macro(concrete_type);
-                if *has_config {
-                    quote! {
-                        #type_name::#variant_name(config) => {
-                            type $type_param = #transformed_path;
-                            let $config_param = config;
-                            $code_block
-                        }
-                    }
-                } else {
-                    quote! {
-                        #type_name::#variant_name => {
-                            type $type_param = #transformed_path;
-                            let $config_param = (); // Use unit type
-                            $code_block
-                        }
-                    }
-                }
-            });
+                #manifest
 
-    // Generate a top-level macro with the snake_case name of the enum + "_config"
-    let macro_def = quote! {
-        #[macro_export]
-        macro_rules! #macro_name {
-            ($enum_instance:expr; ($type_param:ident, $config_param:ident) => $code_block:block) => {
-                match $enum_instance {
-                    #(#macro_match_arms),*
-                }
-            };
-        }
-    };
+                #type_id_methods
 
-    // Generate the methods implementation
-    let methods_impl = quote! {
-        impl #type_name {
-            /// Returns a reference to the configuration data associated with this enum variant
-            /// Unit variants return a reference to the unit type `()`
-            pub fn config(&self) -> &dyn std::any::Any {
-                match self {
-                    #(#config_arms),*
-                }
+                #iter_method
+
+                #index_methods
+
+                #tag_methods
+
+                #routing_key
+
+                #as_str
+
+                #build_info
+
+                #feature_methods
+
+                #mapping_fingerprint
             }
         }
     };
 
-    // Combine the macro definition and methods implementation
-    let expanded = quote! {
-        // Define the macro
+    if options.factory.is_some() || options.new_default {
+        if let Some((variant_name, _, _)) = variant_mappings
+            .iter()
+            .find(|(_, _, variant_options)| variant_options.nested)
+        {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                "`factory` and `new_default` can't be combined with nested variants",
+            ));
+        }
+    }
+
+    if options.factory.is_none() {
+        if let Some((variant_name, _, _)) = variant_mappings
+            .iter()
+            .find(|(_, _, variant_options)| variant_options.non_send)
+        {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                "`non_send` requires `factory` on the enum, it selects the variants of the `Send` \
+                 factory",
+            ));
+        }
+    }
+
+    // Generate the factory trait if requested with #[concrete(factory = "...")]
+    let factory_impl = options.factory.as_ref().map(|factory_trait| {
+        generate_factory(
+            type_name,
+            &input.vis,
+            factory_trait,
+            &variant_mappings,
+            &options,
+        )
+    });
+
+    let unsupported_stubs = generate_unsupported_stubs(
+        type_name,
+        variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| {
+                (*variant_name, variant_options.feature.as_ref())
+            }),
+    );
+
+    // Generate the instance enum if requested with #[concrete(new_default)]
+    let instance_impl = if options.new_default {
+        Some(generate_instance(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        )?)
+    } else {
+        None
+    };
+
+    // Generate the variant set if requested with #[concrete(set)]
+    let set_impl = if options.set {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`set` requires every variant to be a unit variant",
+            ));
+        }
+        let variant_names: Vec<_> = variant_mappings
+            .iter()
+            .map(|(variant_name, _, _)| *variant_name)
+            .collect();
+        Some(generate_set(
+            type_name,
+            &input.vis,
+            &variant_names,
+            &options,
+        ))
+    } else {
+        None
+    };
+
+    // Generate the per-variant array if requested with #[concrete(per_variant)]
+    let per_variant_impl = if options.per_variant {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`per_variant` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_per_variant(type_name, &input.vis, &options))
+    } else {
+        None
+    };
+
+    // Generate name parsing if requested with #[concrete(from_name)]
+    let from_name_impl = if options.from_name {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`from_name` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_from_name(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        )?)
+    } else {
+        None
+    };
+
+    // Generate the axum extractor if requested with #[concrete(web)]
+    let web_impl = if options.web {
+        if !cfg!(feature = "web") {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`web` requires the `web` feature of `concrete-type`",
+            ));
+        }
+        if !options.from_name {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`web` parses values with `from_name`, add `#[concrete(from_name)]`",
+            ));
+        }
+        Some(generate_web(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        )?)
+    } else {
+        None
+    };
+
+    // Generate weighted random selection if any variant has #[concrete(weight = ...)]
+    let weighted_impl = generate_weighted_choice(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| {
+                (
+                    *variant_name,
+                    variant_options.weight.as_ref(),
+                    variant_options.feature.as_ref(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    )?;
+
+    // Generate the error of the @failover rule if requested with #[concrete(failover)]
+    let failover_error = options.failover.then(|| {
+        generate_failover_error(
+            type_name,
+            &input.vis,
+            &variant_mappings
+                .iter()
+                .map(|(variant_name, _, _)| *variant_name)
+                .collect::<Vec<_>>(),
+            &options,
+        )
+    });
+
+    // Generate the failover order if any variant has #[concrete(priority = ...)]
+    let priority_impl = generate_priority_items(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, variant_options)| {
+                (*variant_name, variant_options.priority.as_ref())
+            })
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    )?;
+
+    // Generate the subset enums declared with #[concrete_subset(...)]
+    let subset_impls = parse_subset_options(&input.attrs).and_then(|subsets| {
+        subsets
+            .iter()
+            .map(|subset| generate_subset(&input, data_enum, &variant_mappings, &options, subset))
+            .collect::<syn::Result<Vec<_>>>()
+    })?;
+
+    // Generate the handle and its vtable if requested with #[concrete(handle)]
+    let handle_impl = if options.handle {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`handle` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_handle(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        )?)
+    } else {
+        None
+    };
+
+    let downcast_error = (!options.partial && !options.minimal)
+        .then(|| generate_downcast_error(type_name, &input.vis, &options));
+    let dispatch_error = (!options.minimal).then(|| {
+        generate_dispatch_error(
+            type_name,
+            &input.vis,
+            &variant_mappings
+                .iter()
+                .map(|(variant_name, _, _)| *variant_name)
+                .collect::<Vec<_>>(),
+            &options,
+        )
+    });
+
+    // Generate the mapping's tests if requested with #[concrete(selftest)]
+    let selftest = if options.selftest {
+        if options.minimal {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`selftest` checks the methods `minimal` leaves out, remove one of them",
+            ));
+        }
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`selftest` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_selftest(type_name, &variant_mappings, &options)?)
+    } else {
+        None
+    };
+
+    // Generate the health check helper if requested with #[concrete(check_all)]
+    let check_all_impl = if options.check_all {
+        if !cfg!(feature = "async") {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`check_all` requires the `async` feature of `concrete-type`",
+            ));
+        }
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`check_all` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_check_all(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        ))
+    } else {
+        None
+    };
+
+    // Generate the per-variant key types if requested with #[concrete(keys)]
+    let keys_impl = options
+        .keys
+        .then(|| generate_keys(type_name, &input.vis, &variant_mappings, &options));
+
+    // Generate the type-level list if requested with #[concrete(type_list)]
+    let type_list_impl = if options.type_list {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`type_list` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_type_list(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        ))
+    } else {
+        None
+    };
+
+    // Implement the index-based mapping trait if requested with #[concrete(concrete_of)]
+    let concrete_of_impl = if options.concrete_of {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`concrete_of` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_concrete_of(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        ))
+    } else {
+        None
+    };
+
+    // Generate the marker types if requested with #[concrete(family)]
+    let family_impl = if options.family {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`family` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_family(
+            type_name,
+            &input.vis,
+            &variant_mappings,
+            &options,
+        ))
+    } else {
+        None
+    };
+
+    // Generate the provenance wrapper if requested with #[concrete(tagged)]
+    let tagged_impl = if options.tagged {
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`tagged` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_tagged(type_name, &input.vis, &options))
+    } else {
+        None
+    };
+
+    // Generate the tag-based binary encoding if requested with #[concrete(encode)]
+    let encoding_impl = if options.encode {
+        if options.encode_serde && !cfg!(feature = "serde") {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`encode = \"serde\"` requires the `serde` feature of `concrete-type`",
+            ));
+        }
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`encode` requires every variant to be a unit variant",
+            ));
+        }
+        Some(generate_encoding(
+            type_name,
+            &variant_mappings
+                .iter()
+                .map(|(variant_name, _, variant_options)| {
+                    (*variant_name, variant_options.tag.as_ref())
+                })
+                .collect::<Vec<_>>(),
+            &options,
+        )?)
+    } else {
+        None
+    };
+
+    // Generate the JavaScript export if requested with #[concrete(wasm)]
+    let wasm_impl = if options.wasm {
+        if !cfg!(feature = "wasm") {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`wasm` requires the `wasm` feature of `concrete-type`",
+            ));
+        }
+        if !all_unit {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`wasm` requires every variant to be a unit variant",
+            ));
+        }
+        let variant_names = variant_mappings
+            .iter()
+            .map(|(variant_name, _, _)| *variant_name)
+            .collect::<Vec<_>>();
+        Some(generate_wasm(
+            type_name,
+            &input.vis,
+            &variant_names,
+            &options,
+        ))
+    } else {
+        None
+    };
+
+    // Generate the Debug implementation if requested with #[concrete(debug)]
+    let debug_impl = options
+        .debug
+        .then(|| generate_debug(type_name, &variant_mappings));
+
+    // Invoke the user macros named with #[concrete(emit_with = "...")] for every concrete type.
+    // Nested variants are left to the inner enum's own hooks
+    let emit_with = generate_emit_with(
+        &options.emit_with,
+        &variant_mappings
+            .iter()
+            .filter(|(_, _, variant_options)| !variant_options.nested)
+            .flat_map(|(variant_name, concrete_type, variant_options)| {
+                let concrete_types = if variant_options.cluster.is_empty() {
+                    std::slice::from_ref(concrete_type)
+                } else {
+                    &variant_options.cluster[..]
+                };
+                concrete_types.iter().map(|concrete_type| {
+                    (
+                        *variant_name,
+                        concrete_type,
+                        variant_options.feature.as_ref(),
+                        quote! { () },
+                    )
+                })
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    // Combine the macro definition and methods implementation
+    let expanded = quote! {
+        // Define the macro outside any module to make it directly accessible
+        #macro_def
+
+        // Implement methods on the enum
+        #methods_impl
+
+        #downcast_error
+
+        #dispatch_error
+
+        #factory_impl
+
+        #instance_impl
+
+        #set_impl
+
+        #per_variant_impl
+
+        #from_name_impl
+
+        #web_impl
+
+        #handle_impl
+
+        #priority_impl
+
+        #weighted_impl
+
+        #selftest
+
+        #failover_error
+
+        #(#subset_impls)*
+
+        #keys_impl
+
+        #family_impl
+
+        #tagged_impl
+
+        #type_list_impl
+
+        #concrete_of_impl
+
+        #encoding_impl
+
+        #wasm_impl
+
+        #debug_impl
+
+        #check_all_impl
+
+        #unsupported_stubs
+    };
+
+    // Summarize the expansion if requested with #[concrete(api_snapshot)]
+    let api_snapshot = if options.api_snapshot {
+        Some(generate_api_snapshot(&input, &expanded)?)
+    } else {
+        None
+    };
+
+    // Return the generated implementation, along with the user's hooks, which are not part of
+    // the snapshot
+    Ok(quote! {
+        #expanded
+
+        #api_snapshot
+
+        #emit_with
+    })
+}
+
+/// A derive macro that implements the mapping between enum variants with associated data and
+/// concrete types.
+///
+/// This macro is designed for enums where each variant has associated configuration data and maps
+/// to a specific concrete type. Each variant must be annotated with the
+/// `#[concrete = "path::to::Type"]` attribute and contain a single field (no tuples)
+/// that holds the configuration data for that concrete type. If the variant has no data, then it
+/// defaults to the unit type `()`.
+///
+/// # Wrapped Configs
+///
+/// `Box<T>` and `Option<T>` fields are bound as-is, unless the variant or the whole enum is marked
+/// `#[concrete(unwrap)]`:
+///
+/// - `Box<T>` fields are unboxed when bound, so the config parameter is a `T`, or a `&T` when the
+///   enum is matched by reference.
+/// - `Option<T>` fields bind the inner `T` when `Some`. When `None`, the config parameter is
+///   `T::default()`, or an `else` block runs instead if one is given (the type alias and config
+///   parameter are not in scope there):
+///   `exchange_config!(config; (Exchange, cfg) => { .. } else { .. })`.
+/// - `Box<dyn Trait>` fields declared with `#[concrete(config = "path::to::Config")]` are
+///   downcast to the declared config and unboxed when bound. `Trait` must have `Any` as a
+///   supertrait. A config of another type panics, unless dispatched with the `@checked` form
+///   (`exchange_config!(@checked config; (Exchange, cfg) => { .. })`), which evaluates to a
+///   `Result` failing with an `{Enum}TypeMismatch`.
+///
+/// Erased configs are unwrapped by value, so pass the enum by value when dispatching on them. For
+/// unwrapped fields, the `config()` method returns the inner config, and `&()` for `None`.
+///
+/// # Generic Enums
+///
+/// The enum may be generic (e.g. `ExchangeConfig<Clock>`) with configs referencing its
+/// parameters. Generics and their bounds are carried over to the generated methods, and
+/// `config()` additionally requires the type parameters to be `'static`.
+///
+/// Configs may also be borrowed (e.g. `Binance(&'a BinanceConfig)`) for zero-copy dispatch over
+/// configs owned elsewhere. The reference is bound as-is, and `config()` returns the referenced
+/// config.
+///
+/// # Path Resolution
+///
+/// - Use `crate::path::to::Type` for types in the same crate (transforms to `$crate::`)
+/// - Use `other_crate::path::to::Type` for types from external crates (used as-is)
+/// - With `#[concrete(crate_path = "::facade")]` on the enum, `crate::` expands to `::facade::`
+///
+/// Paths may contain `{Variant}`/`{variant}` placeholders, and variants without an attribute
+/// fall back to the enum-level `#[concrete(base = "...")]` template.
+///
+/// # Generated Code
+///
+/// The macro generates:
+/// 1. A `config` method that returns a reference to the configuration data as `&dyn Any`
+///    (only with the default `config-any` feature enabled).
+/// 2. A macro with the snake_case name of the enum + "_config" (with "Config" suffix removed if present)
+///    that allows access to both the concrete type and configuration data, the latter bound to
+///    an irrefutable pattern such as `config` or `Credentials { api_key, .. }`. With
+///    `#[concrete(compare)]` on the enum, a `compare_{enum}_configs!` macro dispatching on two
+///    values at once, e.g.
+///    `compare_exchange_configs!(a, b; (A, config_a), (B, config_b) => { ... })`
+/// 3. A `mapping_fingerprint` const function returning a stable hash of the variant to concrete
+///    type mapping, and a const `concrete_type_name` method along with a `{VARIANT}_TYPE_NAME`
+///    constant per variant holding the concrete type path. `type_name_short` returns only its
+///    final segment, and `metric_labels` pairs it with the variant name as `kind`/`impl` labels.
+/// 4. `concrete_type_id` and `same_concrete_as` methods to compare variants by their concrete
+///    type without dispatching.
+///    `try_into_concrete`/`try_into_concrete_with` build the concrete value only when the
+///    variant maps to the requested type, and `downcast_instance` downcasts a `Box<dyn Any>` to
+///    it, failing with an `{Enum}DowncastError` naming the variant's concrete type.
+///    `variant_index` numbers the variants densely in declaration order, up to `VARIANT_COUNT`,
+///    and `required_feature` returns a variant's Cargo feature gate.
+/// 5. With `#[concrete(manifest)]` on the enum, a `CONCRETE_MANIFEST` constant holding a JSON
+///    description of the variants, their concrete type paths, config types and feature gates.
+/// 6. With `#[concrete(derive(Clone, Debug))]` on the enum, `Clone` and `Debug` implementations
+///    bounded on every config type implementing the trait. Unlike `#[derive(...)]`, an enum
+///    mixing configs that do and don't implement a trait still compiles, the implementation is
+///    just missing. `derive(Serialize)` (requires the `serde` feature) serializes the enum
+///    externally tagged. The configs of variants marked `#[concrete(secret)]` are written as
+///    `<redacted>` by `Debug` and `Serialize`, and `serialize_unredacted` writes them out.
+/// 7. With `#[concrete(from_named_value)]` on the enum (requires the `serde` feature), a
+///    `from_named_value` method looking up a variant by name or alias and deserializing its
+///    config from a `serde_json::Value`, failing with an `{Enum}NamedValueError`.
+/// 8. With `#[concrete(api_snapshot)]` on the enum, a hidden `CONCRETE_API` constant listing the
+///    items generated for the enum, as combined by `concrete_type_rules::gen_api_snapshot!`.
+/// 9. With `#[concrete(either)]` on an enum of exactly two variants (requires the `either`
+///    feature), `as_either`, `as_either_mut` and `into_either` methods returning the config as an
+///    `either::Either`, `From<Either<..>>`, and an `either` method dispatching to one of two
+///    closures without macro syntax.
+/// 10. With `#[concrete(fallback)]` on the enum, a `fallback_{enum}_configs!` macro (e.g.
+///     `fallback_exchange_configs!(primary, secondary; (T, config) => { ... })`) running a block
+///     returning a `Result` against the primary value, and against the secondary one only if it
+///     fails. Successes are tagged with an `{Enum}Fallback<T>`, and the errors of both configs
+///     are returned in an `{Enum}FallbackError<E>`.
+/// 11. With `#[concrete(emit_with = "path::to::macro")]` on the enum, an invocation of the named
+///     macro next to the enum per variant, as `macro!(Variant, path::to::Type, ConfigType)`.
+///     `ConfigType` is the type the config is bound to in dispatch blocks (`()` for unit
+///     variants), and variants whose feature is disabled are skipped.
+/// 12. With `#[concrete(routing = "exch.{snake}.events")]` on the enum, a const `routing_key`
+///     method returning the template with the variant's name substituted.
+/// 13. With `#[concrete(build_info)]` on the enum, a const `build_info` function summarizing the
+///     compiled mapping, as for `Concrete` enums.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use concrete_type::ConcreteConfig;
+///
+/// // Define concrete types and configuration types
+/// #[derive(Debug)]
+/// struct BinanceConfig {
+///     api_key: String,
+/// }
+///
+/// struct Binance;
+///
+/// struct Okx;
+///
+/// #[derive(ConcreteConfig)]
+/// enum ExchangeConfig {
+///     #[concrete = "Binance"]
+///     Binance(BinanceConfig),
+///     #[concrete = "Okx"]
+///     Okx,
+/// }
+///
+/// // Using the generated macro for a variant with config data
+/// let config = ExchangeConfig::Binance(BinanceConfig { api_key: "key".to_string() });
+/// let result = exchange_config!(config; (Exchange, cfg) => {
+///     // "Exchange" symbol is concrete type Binance
+///     // "cfg" symbol is a reference to the BinanceConfig instance
+///     format!("{} with config: {:?}", std::any::type_name::<Exchange>(), cfg)
+/// });
+///
+/// // Using the generated macro for a variant without config data
+/// let config = ExchangeConfig::Okx;
+/// let result = exchange_config!(config; (Exchange, cfg) => {
+///     // "Exchange" symbol is concrete type Okx
+///     // "cfg" symbol is a reference to the unit type () (since the Okx variant doesn't have config)
+///     format!("{} with config: {:?}", std::any::type_name::<Exchange>(), cfg)
+/// });
+/// ```
+#[proc_macro_derive(ConcreteConfig, attributes(concrete, concrete_all))]
+pub fn derive_concrete_config(input: TokenStream) -> TokenStream {
+    expand_concrete_config(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands `#[derive(ConcreteConfig)]`, see [`derive_concrete_config`].
+fn expand_concrete_config(input: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    // Parse the input tokens into a syntax tree
+    let input = syn::parse::<DeriveInput>(input)?;
+
+    // Extract the name of the type and its generics
+    let type_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Create a snake_case version of the type name for the macro_rules! name
+    let type_name_str = type_name.to_string();
+    // Strip "Config" suffix if present for cleaner macro names
+    let base_name = if type_name_str.ends_with("Config") {
+        &type_name_str[0..type_name_str.len() - 6]
+    } else {
+        &type_name_str
+    };
+    let macro_name_str = format!("{}_config", base_name.to_case(Case::Snake));
+
+    // Ensure we're dealing with an enum
+    let data_enum = match &input.data {
+        syn::Data::Enum(data_enum) => data_enum,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "ConcreteConfig can only be derived for enums with data",
+            ));
+        }
+    };
+    if data_enum.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "ConcreteConfig can't be derived for an enum without variants, its dispatch macro \
+             would have no concrete type to alias",
+        ));
+    }
+
+    // Parse enum-level options, e.g. #[concrete(base = "...")]
+    let options = parse_enum_options(&input.attrs)?;
+    let macro_name = dispatch_macro_name(&macro_name_str, type_name, &options)?;
+    check_config_options(type_name, &options)?;
+
+    // Extract variant names, their concrete types, and how their config field is bound
+    let variant_mappings = parse_config_variants(data_enum, &options)?;
+
+    // Generate the dispatch macro and its companions
+    let macro_def =
+        generate_config_macros(&input, base_name, macro_name, &variant_mappings, &options)?;
+
+    // Erased configs are dispatched by the `@checked` rule, failing with a type mismatch
+    let type_mismatch = variant_mappings
+        .iter()
+        .any(|(_, _, config_field, _)| matches!(config_field, ConfigField::Erased(_)))
+        .then(|| generate_type_mismatch(type_name, &input.vis, &options));
+
+    // Generate the methods implementation
+    // Checked by `assert_same_mapping!`, so generated whatever the generation level
+    let mapping_fingerprint = generate_mapping_fingerprint(
+        variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _, _)| (*variant_name, concrete_type)),
+        &options,
+    );
+
+    // The `Any`-based config() method can be disabled crate-wide via the `config-any` feature
+    let config_method = cfg!(feature = "config-any")
+        .then(|| generate_config_method(&input, &variant_mappings, &options));
+
+    let type_id_methods = generate_type_id_methods(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _, variant_options)| {
+                (
+                    *variant_name,
+                    concrete_type,
+                    variant_options.feature.as_ref(),
+                )
+            })
+            .collect::<Vec<_>>(),
+        &options,
+    );
+
+    let unsupported_stubs = generate_unsupported_stubs(
+        type_name,
+        variant_mappings
+            .iter()
+            .map(|(variant_name, _, _, variant_options)| {
+                (*variant_name, variant_options.feature.as_ref())
+            }),
+    );
+
+    let type_name_items = generate_type_name_items(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _, _)| (*variant_name, concrete_type))
+            .collect::<Vec<_>>(),
+        &options,
+    )?;
+
+    let manifest = options.manifest.then(|| {
+        let entries: Vec<_> = variant_mappings
+            .iter()
+            .zip(&data_enum.variants)
+            .map(
+                |((variant_name, concrete_type, _, variant_options), variant)| {
+                    let config_type = variant.fields.iter().next().map(|field| &field.ty);
+                    (*variant_name, concrete_type, config_type, variant_options)
+                },
+            )
+            .collect();
+        generate_manifest(type_name, &entries, &options)
+    });
+
+    let diagram = generate_diagram(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _, _)| (*variant_name, concrete_type))
+            .collect::<Vec<_>>(),
+    );
+
+    let all_unit = data_enum
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit));
+    let feature_methods = generate_feature_methods(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _, variant_options)| {
+                (*variant_name, variant_options.feature.as_ref())
+            })
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    );
+    let index_methods = generate_index_methods(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _, _)| *variant_name)
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    );
+    let tag_methods = generate_tag_methods(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _, variant_options)| {
+                (*variant_name, variant_options.tag.as_ref())
+            })
+            .collect::<Vec<_>>(),
+        all_unit,
+        &options,
+    )?;
+    let routing_key = generate_routing_key(
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, _, _, _)| *variant_name)
+            .collect::<Vec<_>>(),
+        &options,
+    )?;
+    let build_info = generate_build_info(
+        type_name,
+        &variant_mappings
+            .iter()
+            .map(|(variant_name, concrete_type, _, variant_options)| {
+                (*variant_name, concrete_type, variant_options)
+            })
+            .collect::<Vec<_>>(),
+        &options,
+    );
+
+    // With #[concrete(minimal)], only `config`, the mapping fingerprint, the variant indices and
+    // tags are generated
+    let methods_impl = if options.minimal {
+        quote! {
+            impl #impl_generics #type_name #ty_generics #where_clause {
+                #config_method
+
+                #mapping_fingerprint
+
+                #index_methods
+
+                #tag_methods
+
+                #routing_key
+
+                #build_info
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #type_name #ty_generics #where_clause {
+                #config_method
+
+                #type_name_items
+
+                #index_methods
+
+                #tag_methods
+
+                #routing_key
+
+                #build_info
+
+                #feature_methods
+
+                #diagram
+
+                #manifest
+
+                #type_id_methods
+
+                #mapping_fingerprint
+            }
+        }
+    };
+
+    let downcast_error =
+        (!options.minimal).then(|| generate_downcast_error(type_name, &input.vis, &options));
+    if let (Some(derive_trait), false) = (
+        options
+            .derives
+            .iter()
+            .find(|derive_trait| *derive_trait == "Serialize"),
+        cfg!(feature = "serde"),
+    ) {
+        return Err(syn::Error::new_spanned(
+            derive_trait,
+            "`derive(Serialize)` requires the `serde` feature of `concrete-type`",
+        ));
+    }
+    let secrets: Vec<_> = variant_mappings
+        .iter()
+        .filter(|(_, _, _, variant_options)| variant_options.secret)
+        .map(|(variant_name, _, _, _)| *variant_name)
+        .collect();
+    let forwarded_derives = generate_forwarded_derives(&input, data_enum, &secrets, &options);
+
+    let fallback_types = options
+        .fallback
+        .then(|| generate_fallback_types(type_name, &input.vis, &options));
+
+    // Generate the two-variant fast path if requested with #[concrete(either)]
+    let either_impl = if options.either {
+        Some(generate_either(&input, data_enum, &options)?)
+    } else {
+        None
+    };
+
+    // Generate name and JSON value parsing if requested with #[concrete(from_named_value)]
+    let from_named_value_impl = if options.from_named_value {
+        if !cfg!(feature = "serde") {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "`from_named_value` requires the `serde` feature of `concrete-type`",
+            ));
+        }
+        if let Some(param) = input.generics.params.first() {
+            return Err(syn::Error::new_spanned(
+                param,
+                "`from_named_value` doesn't support generic enums",
+            ));
+        }
+        let variants: Vec<_> = data_enum
+            .variants
+            .iter()
+            .zip(&variant_mappings)
+            .map(|(variant, (_, _, _, variant_options))| (variant, variant_options))
+            .collect();
+        Some(generate_from_named_value(
+            type_name, &input.vis, &variants, &options,
+        )?)
+    } else {
+        None
+    };
+
+    // Invoke the user macros named with #[concrete(emit_with = "...")], with the type each config
+    // is bound to
+    let emit_with = generate_emit_with(
+        &options.emit_with,
+        &variant_mappings
+            .iter()
+            .map(
+                |(variant_name, concrete_type, config_field, variant_options)| {
+                    let config_type = match config_field.erased_type() {
+                        Some(config_type) => quote! { #config_type },
+                        None => quote! { () },
+                    };
+                    (
+                        *variant_name,
+                        concrete_type,
+                        variant_options.feature.as_ref(),
+                        config_type,
+                    )
+                },
+            )
+            .collect::<Vec<_>>(),
+    );
+
+    // Combine the macro definition and methods implementation
+    let expanded = quote! {
+        // Define the macro
         #macro_def
 
         // Implement methods on the enum
         #methods_impl
+
+        #downcast_error
+
+        #forwarded_derives
+
+        #either_impl
+
+        #fallback_types
+
+        #type_mismatch
+
+        #from_named_value_impl
+
+        #unsupported_stubs
+    };
+
+    // Summarize the expansion if requested with #[concrete(api_snapshot)]
+    let api_snapshot = if options.api_snapshot {
+        Some(generate_api_snapshot(&input, &expanded)?)
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        #expanded
+
+        #api_snapshot
+
+        #emit_with
+    })
+}
+
+/// A derive macro that implements [`Display`](std::fmt::Display) for an enum using its concrete
+/// type mappings.
+///
+/// Each variant is displayed as `Variant (path::to::Type)`, using the same `#[concrete]`
+/// attributes (and enum-level options) as [`Concrete`] and [`ConcreteConfig`]. Variants may hold
+/// data, so the derive can be combined with either of them.
+///
+/// # Example
+///
+/// ```rust
+/// use concrete_type::{Concrete, ConcreteDisplay};
+/// # mod exchanges {
+/// #     pub struct Binance;
+/// # }
+///
+/// #[derive(Concrete, ConcreteDisplay)]
+/// enum Exchange {
+///     #[concrete = "crate::exchanges::Binance"]
+///     Binance,
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Exchange::Binance.to_string(), "Binance (crate::exchanges::Binance)");
+/// # }
+/// ```
+#[proc_macro_derive(ConcreteDisplay, attributes(concrete))]
+pub fn derive_concrete_display(input: TokenStream) -> TokenStream {
+    expand_concrete_display(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Expands `#[derive(ConcreteDisplay)]`, see [`derive_concrete_display`].
+fn expand_concrete_display(input: TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    // Parse the input tokens into a syntax tree
+    let input = syn::parse::<DeriveInput>(input)?;
+
+    // Extract the name of the type
+    let type_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    // Ensure we're dealing with an enum
+    let data_enum = match &input.data {
+        syn::Data::Enum(data_enum) => data_enum,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                "ConcreteDisplay can only be derived for enums",
+            ));
+        }
+    };
+
+    // Parse enum-level options, e.g. #[concrete(base = "...")]
+    let options = parse_enum_options(&input.attrs)?;
+
+    // Generate a match arm per variant with the pre-rendered display string
+    let mut display_arms = Vec::new();
+
+    for variant in &data_enum.variants {
+        let variant_name = &variant.ident;
+        let concrete_type = parse_variant_options(&variant.attrs).and_then(|variant_options| {
+            resolve_concrete_type_path(variant, &options, &variant_options)
+        })?;
+        let display = format!(
+            "{} ({})",
+            variant_name,
+            concrete_path_string(&concrete_type)
+        );
+
+        display_arms.push(quote! {
+            Self::#variant_name { .. } => #display
+        });
+    }
+
+    let expanded = quote! {
+        impl #impl_generics ::std::fmt::Display for #type_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(match self {
+                    #(#display_arms),*
+                })
+            }
+        }
+    };
+
+    Ok(expanded)
+}
+
+/// Generates a combined matcher macro dispatching on several concrete enums at once.
+///
+/// Each enum's dispatch macro (`exchange!` for `Exchange`) is called by its bare snake_case name,
+/// or through the path given with `via`, where a leading `crate::` refers to the crate defining
+/// the matcher. The generated macro is named `match_` followed by the snake_case enum names unless
+/// a name is given after `=>`. Any number of enums, from two upwards, can be combined.
+///
+/// ```rust
+/// # use concrete_type::{Concrete, gen_match_concretes};
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # #[concrete(macros_mod = "dispatch")]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// gen_match_concretes!(Exchange, Strategy via crate::dispatch::strategy => dispatch_pair);
+///
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Okx, Strategy::StrategyB);
+/// let name = dispatch_pair!(exchange, strategy; E, S => { std::any::type_name::<(E, S)>() });
+/// # assert!(name.contains("::Okx, ") && name.ends_with("::StrategyB)"));
+/// # }
+/// ```
+///
+/// An enum can be fixed to one of its variants with `Enum = Variant`, e.g. when a binary is built
+/// for a single exchange. The generated macro then only takes values for the remaining enums, still
+/// takes a type parameter for every enum, and aliases the fixed concrete type without matching:
+///
+/// ```rust
+/// # use concrete_type::{Concrete, gen_match_concretes};
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// gen_match_concretes!(Exchange = Binance, Strategy);
+///
+/// # fn main() {
+/// # let strategy = Strategy::StrategyA;
+/// let name = match_exchange_binance_strategy!(strategy; E, S => { std::any::type_name::<(E, S)>() });
+/// # assert!(name.contains("::Binance, ") && name.ends_with("::StrategyA)"));
+/// # }
+/// ```
+///
+/// For combination spaces too large to expand the block for every combination, the generated
+/// macro also accepts `@memoized` before the values and a closure-like body. The body is
+/// type-checked once as a generic function, each type parameter taking an optional trait bound,
+/// and only takes the listed arguments since it can't capture its environment. They are passed
+/// from the variables of the same names at the call site. Each call site keeps a lazily
+/// populated table of function pointers keyed by the variants' `variant_index`:
+///
+/// ```rust
+/// # use concrete_type::{Concrete, gen_match_concretes};
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// # gen_match_concretes!(Exchange, Strategy);
+/// # trait Fee {
+/// #     const BASIS_POINTS: u64;
+/// # }
+/// # impl Fee for Binance {
+/// #     const BASIS_POINTS: u64 = 10;
+/// # }
+/// # impl Fee for Okx {
+/// #     const BASIS_POINTS: u64 = 8;
+/// # }
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Binance, Strategy::StrategyA);
+/// let notional = 1_000_000;
+/// let fee = match_exchange_strategy!(@memoized exchange, strategy; E: Fee, S => |notional: u64| -> u64 {
+///     notional * E::BASIS_POINTS / 10_000
+/// });
+/// assert_eq!(fee, 1_000);
+/// # }
+/// ```
+///
+/// With `@named` before the values, the block sees a `COMBO_NAME` constant naming the matched
+/// combination, built from each enum's `@named` dispatch rule:
+///
+/// ```rust
+/// # use concrete_type::{Concrete, gen_match_concretes};
+/// # pub struct Binance;
+/// # pub struct Okx;
+/// # pub struct StrategyA;
+/// # pub struct StrategyB;
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete, Clone, Copy)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::StrategyA"]
+/// #     StrategyA,
+/// #     #[concrete = "crate::StrategyB"]
+/// #     StrategyB,
+/// # }
+/// # gen_match_concretes!(Exchange, Strategy);
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Binance, Strategy::StrategyA);
+/// let key = match_exchange_strategy!(@named exchange, strategy; E, S => { COMBO_NAME });
+/// assert_eq!(key, "binance+strategy_a");
+/// # }
+/// ```
+///
+/// `concrete-type-rules` re-exports this macro alongside `gen_match_concretes_macro!`.
+#[proc_macro]
+pub fn gen_match_concretes(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as MatchConcretesInput);
+
+    if input.enums.len() < 2 {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "gen_match_concretes! needs at least two enums to combine",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let macro_name = input.macro_name.unwrap_or_else(|| {
+        let snake_names: Vec<_> = input
+            .enums
+            .iter()
+            .map(|matched| match &matched.fixed_variant {
+                Some(variant) => format!("{}_{}", matched.enum_name, variant).to_case(Case::Snake),
+                None => matched.enum_name.to_string().to_case(Case::Snake),
+            })
+            .collect();
+        syn::Ident::new(
+            &format!("match_{}", snake_names.join("_")),
+            proc_macro2::Span::call_site(),
+        )
+    });
+
+    // Fixed enums are resolved at generation time, so they take no value
+    let value_vars: Vec<_> = (0..input.enums.len())
+        .map(|index| quote::format_ident!("value_{}", index))
+        .collect();
+    let dispatched_value_vars: Vec<_> = input
+        .enums
+        .iter()
+        .zip(&value_vars)
+        .filter(|(matched, _)| matched.fixed_variant.is_none())
+        .map(|(_, value_var)| value_var)
+        .collect();
+    let type_vars: Vec<_> = (0..input.enums.len())
+        .map(|index| quote::format_ident!("type_{}", index))
+        .collect();
+
+    let dispatch_macros: Vec<_> = input
+        .enums
+        .iter()
+        .map(|matched| match &matched.macro_path {
+            // `crate::` paths become `$crate::` so the matcher also works from other crates. The
+            // span of `crate` is kept so `$crate` resolves to the invoking crate even when called
+            // through `concrete-type-rules`
+            Some(macro_path) => {
+                let span = macro_path.segments[0].ident.span();
+                let mut dollar = proc_macro2::Punct::new('$', proc_macro2::Spacing::Alone);
+                dollar.set_span(span);
+                let crate_ident = syn::Ident::new("crate", span);
+                transform_path_for_macro(macro_path, &quote! { #dollar #crate_ident })
+            }
+            None => {
+                let name = syn::Ident::new(
+                    &matched.enum_name.to_string().to_case(Case::Snake),
+                    matched.enum_name.span(),
+                );
+                quote! { #name }
+            }
+        })
+        .collect();
+
+    // Nest the dispatch macros from the innermost enum outwards, matching on `value(index)`
+    let nest_dispatch =
+        |innermost: proc_macro2::TokenStream,
+         value: &dyn Fn(&syn::Ident) -> proc_macro2::TokenStream| {
+            let mut body = innermost;
+            for (((matched, dispatch_macro), value_var), type_var) in input
+                .enums
+                .iter()
+                .zip(&dispatch_macros)
+                .zip(&value_vars)
+                .zip(&type_vars)
+                .rev()
+            {
+                let target = match &matched.fixed_variant {
+                    Some(variant) => quote! { @variant #variant },
+                    None => value(value_var),
+                };
+                body = quote! {
+                    #dispatch_macro!(#target; $#type_var => {
+                        #body
+                    })
+                };
+            }
+            body
+        };
+    let body = nest_dispatch(quote! { $code_block }, &|value_var| quote! { $#value_var });
+
+    // Each call site checks the number of combinations the block is expanded into against the
+    // lowest `max_combinations` of the enums. Fixed enums don't multiply it, but their limit holds
+    let arm_counts = input
+        .enums
+        .iter()
+        .zip(&dispatch_macros)
+        .filter(|(matched, _)| matched.fixed_variant.is_none())
+        .map(|(_, dispatch_macro)| quote! { #dispatch_macro!(@arm_count) });
+    let too_many_combinations = format!(
+        "`{}!` expands its block into more combinations than the `max_combinations` of its enums \
+         allows, consider the `@memoized` form",
+        macro_name
+    );
+    let combinations_check = quote! {
+        const _: () = {
+            let mut limit = ::core::primitive::usize::MAX;
+            #(
+                if #dispatch_macros!(@max_combinations) < limit {
+                    limit = #dispatch_macros!(@max_combinations);
+                }
+            )*
+            let combinations = 1usize #(.saturating_mul(#arm_counts))*;
+            ::core::assert!(combinations <= limit, #too_many_combinations);
+        };
+    };
+
+    // The memoized form type-checks the block once as a generic function. Dispatching only picks
+    // the function pointer of the matched combination, cached per call site by variant indices
+    let bound_vars: Vec<_> = (0..input.enums.len())
+        .map(|index| quote::format_ident!("bound_{}", index))
+        .collect();
+    let index_types: Vec<_> = input
+        .enums
+        .iter()
+        .filter(|matched| matched.fixed_variant.is_none())
+        .map(|_| quote! { usize })
+        .collect();
+    // `||` is a single token, so closures without arguments get an arm of their own
+    let memoized_arms = [
+        (
+            quote! { |$($arg:ident: $arg_type:ty),* $(,)?| },
+            quote! { $($arg: $arg_type),* },
+            quote! { $($arg_type),* },
+            quote! { $($arg),* },
+        ),
+        (quote! { || }, quote! {}, quote! {}, quote! {}),
+    ]
+    .into_iter()
+    .map(|(closure_params, fn_params, arg_types, args)| {
+        let lookup = nest_dispatch(
+            quote! { __concrete_memoized::<#($#type_vars),*> as fn(#arg_types) -> $return_type },
+            &|value_var| quote! { #value_var },
+        );
+        quote! {
+            (
+                @memoized #($#dispatched_value_vars:expr),*;
+                #($#type_vars:ident $(: $#bound_vars:path)?),* =>
+                #closure_params -> $return_type:ty $code_block:block
+            ) => {{
+                fn __concrete_memoized<#($#type_vars $(: $#bound_vars)?),*>(
+                    #fn_params
+                ) -> $return_type $code_block
+
+                static TABLE: ::std::sync::OnceLock<
+                    ::std::sync::Mutex<
+                        ::std::collections::HashMap<
+                            (#(#index_types,)*),
+                            fn(#arg_types) -> $return_type,
+                        >,
+                    >,
+                > = ::std::sync::OnceLock::new();
+
+                #(let #dispatched_value_vars = $#dispatched_value_vars;)*
+                let key = (#(#dispatched_value_vars.variant_index(),)*);
+                let function = *TABLE
+                    .get_or_init(::core::default::Default::default)
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .entry(key)
+                    .or_insert_with(|| #lookup);
+                function(#args)
+            }};
+        }
+    });
+
+    // The named form binds `COMBO_NAME`, e.g. `"binance+strategy_a"`. Each dispatched enum is
+    // matched with its `@named` rule, which appends the matched variant's name to the names
+    // collected so far, as a bracketed list of literals, and passes them to the next step of a
+    // local macro. Names known at generation time (separators and fixed variants) are appended
+    // as suffixes along the way, and the last step concatenates everything into a literal
+    let mut named_steps = Vec::new();
+    let mut names = quote! { [] };
+    let mut pending: Vec<String> = Vec::new();
+    let mut named_chain = Vec::new();
+    for (index, ((matched, dispatch_macro), (value_var, type_var))) in input
+        .enums
+        .iter()
+        .zip(&dispatch_macros)
+        .zip(value_vars.iter().zip(&type_vars))
+        .enumerate()
+    {
+        if index > 0 {
+            pending.push(String::from("+"));
+        }
+        match &matched.fixed_variant {
+            Some(variant) => {
+                pending.push(variant.to_string().to_case(Case::Snake));
+                named_chain.push((dispatch_macro, quote! { @variant #variant; $#type_var }));
+            }
+            None => {
+                let step = quote::format_ident!("__named_{}", named_steps.len() + 1);
+                let suffixes = std::mem::take(&mut pending);
+                named_chain.push((
+                    dispatch_macro,
+                    quote! {
+                        @named $#value_var; $#type_var; #names #(#suffixes),* =>
+                        __concrete_named { @#step }
+                    },
+                ));
+                named_steps.push((step, named_chain.len()));
+                names = quote! { $names };
+            }
+        }
+    }
+    // Nests the dispatch macros from `start`, which is either the first enum or the one after a
+    // dispatched enum handing its names to a step
+    let named_body = |start: usize| {
+        let end = named_steps
+            .iter()
+            .map(|(_, resume)| *resume)
+            .find(|resume| *resume > start)
+            .unwrap_or(usize::MAX);
+        if end > named_chain.len() {
+            // The last dispatched enum has been matched, only fixed enums may remain
+            let names = if named_steps.is_empty() {
+                quote! { "" }
+            } else {
+                quote! { ::core::concat! $names }
+            };
+            let mut body = quote! {{
+                #[allow(dead_code)]
+                const COMBO_NAME: &str = ::core::concat!(#names #(, #pending)*);
+                $code_block
+            }};
+            for (dispatch_macro, target) in named_chain[start..].iter().rev() {
+                body = quote! { #dispatch_macro!(#target => #body) };
+            }
+            return body;
+        }
+        let (dispatch_macro, target) = &named_chain[end - 1];
+        let mut body = quote! { #dispatch_macro!(#target) };
+        for (dispatch_macro, target) in named_chain[start..end - 1].iter().rev() {
+            body = quote! { #dispatch_macro!(#target => { #body }) };
+        }
+        body
+    };
+    let named_step_rules = named_steps.iter().map(|(step, resume)| {
+        let body = named_body(*resume);
+        quote! {
+            (@#step $names:tt) => { #body };
+        }
+    });
+    let named_local_macro = (!named_steps.is_empty()).then(|| {
+        quote! {
+            macro_rules! __concrete_named {
+                #(#named_step_rules)*
+            }
+        }
+    });
+    let named_entry = named_body(0);
+    let named_rules = quote! {
+        (@named #($#dispatched_value_vars:expr),*; #($#type_vars:ident),* => $code_block:block) => {{
+            #combinations_check
+            #named_local_macro
+            #named_entry
+        }};
+    };
+
+    let enum_names: Vec<_> = input
+        .enums
+        .iter()
+        .map(|matched| matched.enum_name.to_string())
+        .collect();
+    let usage = format!(
+        "{}; {} => {{ ... }}",
+        input
+            .enums
+            .iter()
+            .filter(|matched| matched.fixed_variant.is_none())
+            .map(|matched| matched.enum_name.to_string().to_case(Case::Snake))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (1..=enum_names.len())
+            .map(|index| format!("T{}", index))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let error_message = format!(
+        "invalid `{}!` invocation, expected `{}!({})`",
+        macro_name, macro_name, usage
+    );
+    let macro_doc = format!(
+        "Dispatches on `{}` values at once, aliasing the concrete type of each matched variant.\n\n\
+         Usage: `{}!({})`\n\n\
+         Prefix the values with `@memoized` and replace the block with a closure-like \
+         `|arg: Type, ...| -> Output {{ ... }}` to type-check the body once as a generic \
+         function and cache the function pointer of each combination. Prefix the values with \
+         `@named` to bind `COMBO_NAME`, the matched variants' snake_case names joined by `+`.",
+        enum_names.join("`, `"),
+        macro_name,
+        usage
+    );
+
+    let expanded = quote! {
+        #[doc = #macro_doc]
+        #[macro_export]
+        macro_rules! #macro_name {
+            (#($#dispatched_value_vars:expr),*; #($#type_vars:ident),* => $code_block:block) => {{
+                #combinations_check
+                #body
+            }};
+            #(#memoized_arms)*
+            #named_rules
+            ($($tokens:tt)*) => {
+                ::core::compile_error!(#error_message)
+            };
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Lets instance enums implement the annotated trait by forwarding every method to the value
+/// of the matched variant.
+///
+/// The trait is left unchanged. Alongside it, a hidden macro describing its methods is
+/// generated, which `#[concrete(delegate_trait = "path::to::Trait")]` on a `Concrete` enum
+/// with `new_default` invokes to implement the trait on the `{Enum}Instance` enum:
+///
+/// ```rust
+/// # use concrete_type::{Concrete, concrete_delegate};
+/// # use std::time::Duration;
+/// # mod exchanges {
+/// #     use std::time::Duration;
+/// #     #[derive(Default)]
+/// #     pub struct Binance;
+/// #     impl crate::ExchangeApi for Binance {
+/// #         fn name(&self) -> &'static str {
+/// #             "binance"
+/// #         }
+/// #         async fn ping(&mut self, _timeout: Duration) -> bool {
+/// #             true
+/// #         }
+/// #     }
+/// # }
+/// #[concrete_delegate]
+/// pub trait ExchangeApi {
+///     fn name(&self) -> &'static str;
+///     async fn ping(&mut self, timeout: Duration) -> bool;
+/// }
+///
+/// #[derive(Concrete)]
+/// #[concrete(new_default, delegate_trait = "crate::ExchangeApi")]
+/// enum Exchange {
+///     #[concrete = "crate::exchanges::Binance"]
+///     Binance,
+/// }
+///
+/// # fn main() {
+/// let name = Exchange::Binance.new_default().name();
+/// # assert_eq!(name, "binance");
+/// # }
+/// ```
+///
+/// Methods take `self`, `&self` or `&mut self`. Associated functions with a default body keep
+/// it, while other associated functions, types and constants can't be forwarded. The hidden
+/// macro is only visible within the crate, so the trait must be defined in the crate deriving
+/// the enum.
+#[proc_macro_attribute]
+pub fn concrete_delegate(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(
+            proc_macro2::TokenStream::from(args)
+                .into_iter()
+                .next()
+                .map_or_else(proc_macro2::Span::call_site, |token| token.span()),
+            "`concrete_delegate` takes no arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let item_trait = parse_macro_input!(input as syn::ItemTrait);
+
+    if let Some(param) = item_trait.generics.params.first() {
+        return syn::Error::new_spanned(
+            param,
+            "`concrete_delegate` doesn't support generic traits",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let mut methods = Vec::new();
+    for item in &item_trait.items {
+        let method = match item {
+            syn::TraitItem::Fn(method) => delegate_method(method),
+            syn::TraitItem::Const(constant) if constant.default.is_some() => Ok(None),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "`concrete_delegate` can only forward methods",
+            )),
+        };
+        match method {
+            Ok(method) => methods.extend(method),
+            Err(error) => return error.to_compile_error().into(),
+        }
+    }
+
+    let macro_name = delegate_macro_name(&item_trait.ident);
+    let expanded = quote! {
+        #item_trait
+
+        #[doc(hidden)]
+        macro_rules! #macro_name {
+            ([$trait_path:path] $instance:ident [$($variant:ident($ty:ty)),*]) => {
+                impl $trait_path for $instance {
+                    #(#methods)*
+                }
+            };
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub(crate) use #macro_name;
     };
 
     TokenStream::from(expanded)
 }
+
+/// Generates a static registry of constructors keyed by the variants of a `Concrete` enum, the
+/// bridge between the kinds read from configuration and the objects built from them at runtime.
+///
+/// ```rust
+/// use concrete_type::{Concrete, gen_registry};
+///
+/// trait ExchangeApi {
+///     fn name(&self) -> &'static str;
+/// }
+///
+/// struct Binance;
+/// struct Okx;
+///
+/// impl Binance {
+///     fn new() -> Self {
+///         Binance
+///     }
+/// }
+///
+/// impl Okx {
+///     fn new() -> Self {
+///         Okx
+///     }
+/// }
+///
+/// impl ExchangeApi for Binance {
+///     fn name(&self) -> &'static str {
+///         "binance"
+///     }
+/// }
+///
+/// impl ExchangeApi for Okx {
+///     fn name(&self) -> &'static str {
+///         "okx"
+///     }
+/// }
+///
+/// #[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+/// enum Exchange {
+///     #[concrete = "crate::Binance"]
+///     Binance,
+///     #[concrete = "crate::Okx"]
+///     Okx,
+/// }
+///
+/// gen_registry!(Exchange, trait = ExchangeApi + Send, constructor = new);
+///
+/// # fn main() {
+/// // static REGISTRY: &[(Exchange, fn() -> Box<dyn ExchangeApi + Send>)]
+/// let names: Vec<_> = REGISTRY
+///     .iter()
+///     .map(|(kind, construct)| (*kind, construct().name()))
+///     .collect();
+/// assert_eq!(names, [(Exchange::Binance, "binance"), (Exchange::Okx, "okx")]);
+///
+/// // fn build(kind: Exchange) -> Box<dyn ExchangeApi + Send>
+/// assert_eq!(build(Exchange::Okx).name(), "okx");
+/// # }
+/// ```
+///
+/// Each concrete type is built by calling `Type::constructor()` and boxed as a trait object of
+/// the given bounds. The generated items are private unless a visibility precedes the enum, as in
+/// `gen_registry!(pub(crate) Exchange, ...)`. The enum must only have unit variants. Variants
+/// gated behind a disabled feature are left out of `REGISTRY`, and `build` panics for them.
+///
+/// `prefix = name` names the items `{NAME}_REGISTRY` and `{name}_build` instead, so the
+/// registries of several enums can share a module, as in
+/// `gen_registry!(Strategy, trait = StrategyApi, constructor = new, prefix = strategy)`.
+///
+/// The enum's dispatch macro is called by its bare snake_case name, or through the path given
+/// with `via`, as for [`macro@concrete_impls`]. `concrete-type-rules` re-exports this macro.
+#[proc_macro]
+pub fn gen_registry(input: TokenStream) -> TokenStream {
+    let RegistryInput {
+        vis,
+        enum_name,
+        macro_path,
+        bounds,
+        constructor,
+        prefix,
+    } = parse_macro_input!(input as RegistryInput);
+
+    let macro_path = macro_path.unwrap_or_else(|| {
+        syn::Ident::new(
+            &enum_name.to_string().to_case(Case::Snake),
+            enum_name.span(),
+        )
+        .into()
+    });
+
+    // Registries of several enums in one module are told apart by their prefix
+    let (registry, build) = match &prefix {
+        Some(prefix) => (
+            syn::Ident::new(
+                &format!("{}_REGISTRY", prefix.to_string().to_case(Case::UpperSnake)),
+                prefix.span(),
+            ),
+            syn::Ident::new(&format!("{}_build", prefix), prefix.span()),
+        ),
+        None => (
+            syn::Ident::new("REGISTRY", enum_name.span()),
+            syn::Ident::new("build", enum_name.span()),
+        ),
+    };
+
+    TokenStream::from(quote! {
+        #macro_path!(@registry #vis, [#bounds], #constructor, #registry, #build);
+    })
+}
+
+/// Generates a double dispatch visitor over two `Concrete` enums, for code that can't expand the
+/// nested dispatch macros in place, such as callbacks stored in a struct.
+///
+/// ```rust
+/// # use concrete_type::{Concrete, gen_visitor2};
+/// # trait ExchangeApi {
+/// #     const NAME: &'static str;
+/// # }
+/// # trait StrategyApi {
+/// #     const NAME: &'static str;
+/// # }
+/// # pub struct Okx;
+/// # pub struct Grid;
+/// # impl ExchangeApi for Okx {
+/// #     const NAME: &'static str = "okx";
+/// # }
+/// # impl StrategyApi for Grid {
+/// #     const NAME: &'static str = "grid";
+/// # }
+/// # #[derive(Concrete)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::Okx"]
+/// #     Okx,
+/// # }
+/// # #[derive(Concrete)]
+/// # enum Strategy {
+/// #     #[concrete = "crate::Grid"]
+/// #     Grid,
+/// # }
+/// gen_visitor2!(Exchange: ExchangeApi, Strategy: StrategyApi);
+///
+/// struct Describe;
+///
+/// impl Visitor2 for Describe {
+///     type Output = String;
+///
+///     fn visit<A: ExchangeApi, B: StrategyApi>(&mut self) -> String {
+///         format!("{} on {}", B::NAME, A::NAME)
+///     }
+/// }
+///
+/// # fn main() {
+/// # let (exchange, strategy) = (Exchange::Okx, Strategy::Grid);
+/// let description = accept(&exchange, &strategy, &mut Describe);
+/// assert_eq!(description, "grid on okx");
+/// # }
+/// ```
+///
+/// The generated `Visitor2` trait has a `visit<A, B>` method generic over the concrete types of
+/// both enums, each bounded by the traits given after the enum. `accept` dispatches on both values
+/// and calls `visit` with the concrete types of the matched variants. The items are private
+/// unless a visibility precedes the first enum, as in `gen_visitor2!(pub Exchange, Strategy)`.
+///
+/// `visitor = Trait` and `accept = name` after the enums name the trait and the function, so
+/// visitors over different pairs can share a module, as in
+/// `gen_visitor2!(Exchange, Market, visitor = MarketVisitor, accept = accept_market)`.
+///
+/// Each enum's dispatch macro is called by its bare snake_case name, or through the path given
+/// with `via`, as for [`macro@concrete_impls`]. `concrete-type-rules` re-exports this macro.
+#[proc_macro]
+pub fn gen_visitor2(input: TokenStream) -> TokenStream {
+    let Visitor2Input {
+        vis,
+        first,
+        second,
+        visitor,
+        accept,
+    } = parse_macro_input!(input as Visitor2Input);
+    let visitor =
+        visitor.unwrap_or_else(|| syn::Ident::new("Visitor2", proc_macro2::Span::call_site()));
+    let accept =
+        accept.unwrap_or_else(|| syn::Ident::new("accept", proc_macro2::Span::call_site()));
+
+    let macro_path = |visited: &VisitedEnum| {
+        visited.macro_path.clone().unwrap_or_else(|| {
+            syn::Ident::new(
+                &visited.enum_name.to_string().to_case(Case::Snake),
+                visited.enum_name.span(),
+            )
+            .into()
+        })
+    };
+    let (first_macro, second_macro) = (macro_path(&first), macro_path(&second));
+    let (first_name, second_name) = (&first.enum_name, &second.enum_name);
+    let (first_bounds, second_bounds) = (&first.bounds, &second.bounds);
+
+    let trait_doc = format!(
+        "Visitor called with the concrete types of a `{}` and a `{}` by [`{}`].",
+        first_name, second_name, accept
+    );
+    let visit_doc = format!(
+        "Called with `A` and `B` aliased to the concrete types of the matched `{}` and `{}` \
+         variants.",
+        first_name, second_name
+    );
+    let output_doc = format!("Value returned by [`{}`].", accept);
+    let accept_doc = format!(
+        "Dispatches on a `{}` and a `{}`, calling `visitor.visit::<A, B>()` with their concrete \
+         types.",
+        first_name, second_name
+    );
+
+    TokenStream::from(quote! {
+        #[doc = #trait_doc]
+        #vis trait #visitor {
+            #[doc = #output_doc]
+            type Output;
+
+            #[doc = #visit_doc]
+            fn visit<A: #first_bounds, B: #second_bounds>(&mut self) -> Self::Output;
+        }
+
+        #[doc = #accept_doc]
+        #vis fn #accept<V: #visitor>(
+            first: &#first_name,
+            second: &#second_name,
+            visitor: &mut V,
+        ) -> V::Output {
+            #first_macro!(first; A => {
+                #second_macro!(second; B => {
+                    visitor.visit::<A, B>()
+                })
+            })
+        }
+    })
+}
+
+/// Instantiates a generic impl block once per concrete type of a `Concrete` enum.
+///
+/// The first type parameter of the impl stands for the concrete type. It is removed from the
+/// impl's generics, its bounds moved to the where clause, and the impl is emitted once per
+/// distinct concrete type with the parameter aliased to it. This sidesteps coherence and
+/// inference limits of blanket impls, e.g. two impls over different traits of the same type:
+///
+/// ```rust
+/// # use concrete_type::{Concrete, concrete_impls};
+/// # use std::marker::PhantomData;
+/// # trait ExchangeApi {
+/// #     const NAME: &'static str;
+/// # }
+/// # mod exchanges {
+/// #     pub struct Binance;
+/// #     pub struct Okx;
+/// #     impl crate::ExchangeApi for Binance {
+/// #         const NAME: &'static str = "binance";
+/// #     }
+/// #     impl crate::ExchangeApi for Okx {
+/// #         const NAME: &'static str = "okx";
+/// #     }
+/// # }
+/// # #[derive(Concrete)]
+/// # enum Exchange {
+/// #     #[concrete = "crate::exchanges::Binance"]
+/// #     Binance,
+/// #     #[concrete = "crate::exchanges::Okx"]
+/// #     Okx,
+/// # }
+/// # struct TradingSystem<E>(PhantomData<E>);
+/// #[concrete_impls(Exchange)]
+/// impl<E: ExchangeApi> TradingSystem<E> {
+///     fn exchange_name(&self) -> &'static str {
+///         E::NAME
+///     }
+/// }
+/// // Expands to `impl TradingSystem<exchanges::Binance> where exchanges::Binance: ExchangeApi`,
+/// // and likewise for every other concrete type of `Exchange`
+/// # fn main() {
+/// # assert_eq!(TradingSystem::<exchanges::Okx>(PhantomData).exchange_name(), "okx");
+/// # }
+/// ```
+///
+/// The enum's dispatch macro (`exchange!` for `Exchange`) is called by its bare snake_case name,
+/// or through the path given with `via`, e.g. `#[concrete_impls(Exchange via crate::dispatch::exchange)]`.
+/// Variants gated behind a disabled feature get no impl.
+#[proc_macro_attribute]
+pub fn concrete_impls(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ConcreteImplsInput);
+    let mut item_impl = parse_macro_input!(input as syn::ItemImpl);
+
+    // Take the first type parameter out of the impl's generics, keeping its bounds
+    let mut type_param = None;
+    for param in std::mem::take(&mut item_impl.generics.params) {
+        match param {
+            syn::GenericParam::Type(param) if type_param.is_none() => type_param = Some(param),
+            param => item_impl.generics.params.push(param),
+        }
+    }
+    let Some(type_param) = type_param else {
+        return syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "`concrete_impls` needs a type parameter standing for the concrete type",
+        )
+        .to_compile_error()
+        .into();
+    };
+    if let Some(default) = &type_param.default {
+        return syn::Error::new_spanned(
+            default,
+            "`concrete_impls` type parameter can't have a default",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let param_ident = &type_param.ident;
+    if !type_param.bounds.is_empty() {
+        let bounds = &type_param.bounds;
+        item_impl
+            .generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #param_ident: #bounds });
+    }
+    if item_impl.generics.params.is_empty() {
+        item_impl.generics.lt_token = None;
+        item_impl.generics.gt_token = None;
+    }
+
+    let macro_path = args.macro_path.unwrap_or_else(|| {
+        syn::Ident::new(
+            &args.enum_name.to_string().to_case(Case::Snake),
+            args.enum_name.span(),
+        )
+        .into()
+    });
+
+    TokenStream::from(quote! {
+        #macro_path!(@impls #param_ident; { #item_impl });
+    })
+}
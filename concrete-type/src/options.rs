@@ -0,0 +1,1558 @@
+//! Parsing of the `#[concrete(...)]` enum and variant options, and of the concrete types
+//! they map variants to.
+
+use convert_case::{Case, Casing};
+use quote::quote;
+use syn::{Attribute, Expr, Fields, Lit, Meta};
+
+use crate::paths::source_string;
+
+/// Helper function to extract concrete type path from an attribute
+///
+/// Any `{Variant}`/`{variant}` placeholders in the attribute value are substituted before the
+/// path is parsed (see [`substitute_variant_placeholders`]).
+///
+/// Returns `Ok(None)` when the variant has no `#[concrete = "..."]` attribute, and an error
+/// spanned on the offending tokens when the attribute is malformed or repeated.
+pub(crate) fn extract_concrete_type_path(
+    attrs: &[Attribute],
+    variant_ident: &syn::Ident,
+) -> syn::Result<Option<syn::Path>> {
+    let mut concrete_type = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("concrete")) {
+        let meta = match &attr.meta {
+            Meta::NameValue(meta) => meta,
+            // `#[concrete("...")]` is a common slip for the name-value form
+            Meta::List(list) if list.parse_args::<syn::LitStr>().is_ok() => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "expected `#[concrete = \"...\"]`, found `#[concrete(\"...\")]`",
+                ));
+            }
+            // Other list attributes hold variant-level options
+            Meta::List(_) => continue,
+            Meta::Path(_) => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "expected `#[concrete = \"path::to::Type\"]`",
+                ));
+            }
+        };
+
+        let Expr::Lit(syn::ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) = &meta.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &meta.value,
+                "expected a string literal, e.g. `#[concrete = \"path::to::Type\"]`",
+            ));
+        };
+
+        if concrete_type.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "Enum variant `{}` has more than one #[concrete = \"...\"] attribute",
+                    variant_ident
+                ),
+            ));
+        }
+
+        let path_str = substitute_variant_placeholders(&lit_str.value(), variant_ident);
+        let path = parse_path_at(&path_str, lit_str.span()).map_err(|_| {
+            syn::Error::new_spanned(lit_str, format!("`{}` is not a valid type path", path_str))
+        })?;
+        concrete_type = Some(path);
+    }
+
+    Ok(concrete_type)
+}
+
+/// Extracts the concrete types of a `#[concrete_all = "crate::A, crate::B"]` cluster, with
+/// variant placeholders substituted in each path.
+///
+/// Returns `Ok(None)` when the variant has no `#[concrete_all = "..."]` attribute.
+pub(crate) fn extract_concrete_cluster(
+    attrs: &[Attribute],
+    variant_ident: &syn::Ident,
+) -> syn::Result<Option<Vec<syn::Path>>> {
+    let mut cluster = None;
+
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("concrete_all"))
+    {
+        let Meta::NameValue(syn::MetaNameValue {
+            value:
+                Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(lit_str),
+                    ..
+                }),
+            ..
+        }) = &attr.meta
+        else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected `#[concrete_all = \"path::to::A, path::to::B\"]`",
+            ));
+        };
+        if cluster.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "Enum variant `{}` has more than one #[concrete_all = \"...\"] attribute",
+                    variant_ident
+                ),
+            ));
+        }
+
+        // Placeholders are substituted first, as they aren't valid tokens. The list is parsed as
+        // a whole so that generic arguments can hold commas, and its paths get the literal's span
+        let paths_str = substitute_variant_placeholders(&lit_str.value(), variant_ident);
+        let paths = syn::LitStr::new(&paths_str, lit_str.span())
+            .parse_with(syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+            .map_err(|_| {
+                syn::Error::new_spanned(
+                    lit_str,
+                    format!(
+                        "`{}` is not a comma-separated list of type paths",
+                        paths_str
+                    ),
+                )
+            })?
+            .into_iter()
+            .collect::<Vec<_>>();
+        if paths.len() < 2 {
+            return Err(syn::Error::new_spanned(
+                lit_str,
+                "`concrete_all` lists the concrete types of a cluster, use \
+                 `#[concrete = \"...\"]` for a single type",
+            ));
+        }
+        cluster = Some(paths);
+    }
+
+    Ok(cluster)
+}
+
+/// Parses a concrete type path written in a string, giving its tokens the span of `span`.
+///
+/// Paths parsed from strings would otherwise be spanned at the derive, so type errors involving
+/// the concrete type in a dispatch macro arm point at the attribute of the responsible variant
+/// instead, along with the macro call.
+pub(crate) fn parse_path_at(path_str: &str, span: proc_macro2::Span) -> syn::Result<syn::Path> {
+    fn respan(
+        tokens: proc_macro2::TokenStream,
+        span: proc_macro2::Span,
+    ) -> proc_macro2::TokenStream {
+        tokens
+            .into_iter()
+            .map(|mut token| {
+                if let proc_macro2::TokenTree::Group(group) = &token {
+                    let mut respanned =
+                        proc_macro2::Group::new(group.delimiter(), respan(group.stream(), span));
+                    respanned.set_span(span);
+                    return proc_macro2::TokenTree::Group(respanned);
+                }
+                token.set_span(span);
+                token
+            })
+            .collect()
+    }
+
+    let tokens: proc_macro2::TokenStream = syn::parse_str(path_str)?;
+    syn::parse2(respan(tokens, span))
+}
+
+/// Replaces the variant-derived placeholders in a concrete path template.
+///
+/// - `{Variant}` is replaced by the variant name as written (e.g. `SpotMargin`)
+/// - `{variant}` is replaced by the snake_case variant name (e.g. `spot_margin`)
+pub(crate) fn substitute_variant_placeholders(
+    template: &str,
+    variant_ident: &syn::Ident,
+) -> String {
+    let variant_name = variant_ident.to_string();
+    template
+        .replace("{Variant}", &variant_name)
+        .replace("{variant}", &variant_name.to_case(Case::Snake))
+}
+
+/// Enum-level options parsed from `#[concrete(...)]` attributes placed on the enum itself.
+#[derive(Default)]
+pub(crate) struct EnumOptions {
+    /// Path template shared by every variant, e.g. `crate::Exchange<{Variant}Market>`.
+    pub(crate) base: Option<syn::LitStr>,
+    /// Module the generated macros are re-exported from, e.g. `dispatch`.
+    pub(crate) macros_mod: Option<syn::Ident>,
+    /// Prefix generated macro names with the name of the defining crate.
+    pub(crate) prefix_crate_name: bool,
+    /// Object-safe trait implemented by every concrete type, used to generate a factory trait.
+    pub(crate) factory: Option<syn::Path>,
+    /// Public path that replaces `$crate` for `crate::` paths in generated macros.
+    pub(crate) crate_path: Option<syn::Path>,
+    /// Hide the generated macros, methods and types from rustdoc.
+    pub(crate) doc_hidden: bool,
+    /// Only generate the dispatch macros, the mapping fingerprint, variant indices and tags, and
+    /// what other options request, leaving out the methods and tables generated by default.
+    pub(crate) minimal: bool,
+    /// Generate the methods and tables generated by default, stated explicitly.
+    pub(crate) full: bool,
+    /// Every concrete type implements `Default`, used to generate `new_default`.
+    pub(crate) new_default: bool,
+    /// Generate the `CONCRETE_MANIFEST` JSON description of the mapping.
+    pub(crate) manifest: bool,
+    /// Generate the `CONCRETE_API` summary of the generated items, for snapshot tests.
+    pub(crate) api_snapshot: bool,
+    /// Generate an `{Enum}Set` bitset over the variants.
+    pub(crate) set: bool,
+    /// Generate the `Per{Enum}<T>` array holding a value per variant.
+    pub(crate) per_variant: bool,
+    /// Dispatch nested variants with a single match over the variants of the inner enums.
+    pub(crate) flatten: bool,
+    /// Generate `from_name` and a `FromStr` implementation over variant names and aliases.
+    pub(crate) from_name: bool,
+    /// Generate the `for_each_{enum}!` macro expanding a block once per variant.
+    pub(crate) for_each: bool,
+    /// Generate a zero-sized key type per variant and the `{Enum}Key` enum over them.
+    pub(crate) keys: bool,
+    /// Generate a `{Variant}Kind` marker type per variant and the `{Enum}Family` trait.
+    pub(crate) family: bool,
+    /// Generate the `{Enum}Wasm` numeric enum exported with `#[wasm_bindgen]`.
+    pub(crate) wasm: bool,
+    /// Implement axum's `FromRequestParts` and `TryFrom<&HeaderValue>` for the enum.
+    pub(crate) web: bool,
+    /// Path parameter the extractor reads, the snake_case name of the enum by default.
+    pub(crate) web_param: Option<syn::LitStr>,
+    /// Generate `encode` and `decode` methods packing the variant's tag into a `u16`.
+    pub(crate) encode: bool,
+    /// Implement `serde::Serialize` and `serde::Deserialize` through the `u16` encoding.
+    pub(crate) encode_serde: bool,
+    /// Implement a `{Enum}ConcreteOf<const V: usize>` trait, or the given one, on the enum for the
+    /// index of each variant.
+    pub(crate) concrete_of: bool,
+    /// Trait implemented instead of a generated `{Enum}ConcreteOf`, e.g. `my_lib::ConcreteOf`.
+    pub(crate) concrete_of_trait: Option<syn::Path>,
+    /// Generate a `Debug` implementation printing each variant with its concrete type path.
+    pub(crate) debug: bool,
+    /// Traits implemented when every config type implements them, e.g. `derive(Clone, Debug)`.
+    pub(crate) derives: Vec<syn::Ident>,
+    /// Generate the `check_all` method and the `{Enum}Probe` trait it runs.
+    pub(crate) check_all: bool,
+    /// Bound on the concrete types a probe receives, e.g. `crate::HealthCheck`.
+    pub(crate) probe_bound: Option<syn::Path>,
+    /// Generate the `{Enum}ConcreteTypes` tuple and the `{Enum}TypeVisitor` trait.
+    pub(crate) type_list: bool,
+    /// Bound on the concrete types a type visitor receives, e.g. `crate::ExchangeApi`.
+    pub(crate) visitor_bound: Option<syn::Path>,
+    /// Also generate a `{macro}_relative` flavor resolving `crate::` paths in the calling crate.
+    pub(crate) relative_macro: bool,
+    /// Concrete types are partially applied, the remaining generic arguments are supplied at
+    /// the call site with `Type<Args> => ...`.
+    pub(crate) partial: bool,
+    /// Traits implemented on the instance enum by forwarding to the held value, declared with
+    /// `#[concrete_delegate]`.
+    pub(crate) delegate_traits: Vec<syn::Path>,
+    /// Generate `from_named_value`, building config variants from a name and a JSON value.
+    pub(crate) from_named_value: bool,
+    /// Generate the `{Enum}Handle` token and the `{Enum}VTable` it points to.
+    pub(crate) handle: bool,
+    /// Generate the `@failover` dispatch rule and the `{Enum}FailoverError` it returns.
+    pub(crate) failover: bool,
+    /// Most block expansions a combined matcher including the enum may make per call site.
+    pub(crate) max_combinations: Option<syn::LitInt>,
+    /// Generate a `#[cfg(test)]` module checking the invariants of the mapping.
+    pub(crate) selftest: bool,
+    /// Generate `Either`-based accessors and closure dispatch for a two-variant config enum.
+    pub(crate) either: bool,
+    /// Generate the `{Enum}Tagged<T>` wrapper and a `tagged` method in dispatch blocks.
+    pub(crate) tagged: bool,
+    /// Generate the `compare_{enum}_configs!` macro dispatching on two config values at once.
+    pub(crate) compare: bool,
+    /// Generate the `fallback_{enum}_configs!` macro trying a primary config, then a secondary.
+    pub(crate) fallback: bool,
+    /// Unwrap the `Box` and `Option` config fields of every variant when binding them.
+    pub(crate) unwrap: bool,
+    /// User macros invoked once per variant with its name, concrete type and config type, e.g.
+    /// `my_macros::per_variant`.
+    pub(crate) emit_with: Vec<syn::Path>,
+    /// Template of the key returned by `routing_key`, e.g. `exch.{snake}.events`.
+    pub(crate) routing: Option<syn::LitStr>,
+    /// Generate the `as_str` and `name` methods returning the variant's name.
+    pub(crate) as_str: bool,
+    /// Case the names returned by `as_str` are converted to, e.g. `kebab`.
+    pub(crate) as_str_case: Option<syn::LitStr>,
+    /// Generate the `build_info` function summarizing the compiled mapping.
+    pub(crate) build_info: bool,
+}
+
+impl EnumOptions {
+    /// Returns `#[doc(hidden)]` when generated items should be hidden from rustdoc.
+    pub(crate) fn doc_hidden_attr(&self) -> Option<proc_macro2::TokenStream> {
+        self.doc_hidden.then(|| quote! { #[doc(hidden)] })
+    }
+}
+
+/// Keys accepted by the enum-level `#[concrete(...)]` attribute.
+pub(crate) const ENUM_OPTION_KEYS: &[&str] = &[
+    "base",
+    "macros_mod",
+    "prefix_crate_name",
+    "factory",
+    "crate_path",
+    "doc_hidden",
+    "minimal",
+    "full",
+    "new_default",
+    "manifest",
+    "api_snapshot",
+    "set",
+    "per_variant",
+    "flatten",
+    "from_name",
+    "from_named_value",
+    "for_each",
+    "keys",
+    "family",
+    "encode",
+    "web",
+    "wasm",
+    "concrete_of",
+    "debug",
+    "handle",
+    "failover",
+    "max_combinations",
+    "selftest",
+    "either",
+    "tagged",
+    "compare",
+    "fallback",
+    "unwrap",
+    "routing",
+    "as_str",
+    "build_info",
+    "emit_with",
+    "delegate_trait",
+    "partial",
+    "relative_macro",
+    "check_all",
+    "type_list",
+    "derive",
+];
+
+/// Keys accepted by the variant-level `#[concrete(...)]` attribute.
+pub(crate) const VARIANT_OPTION_KEYS: &[&str] = &[
+    "ty",
+    "trait",
+    "constructor",
+    "tag",
+    "feature",
+    "cargo_feature",
+    "nested",
+    "nested_extern",
+    "alias",
+    "non_send",
+    "priority",
+    "weight",
+    "secret",
+    "config",
+    "unwrap",
+];
+
+/// Returns the error for an unknown key in `attribute`, listing the valid `keys` and suggesting
+/// the closest one when the key looks like a typo of it.
+pub(crate) fn unknown_option_error(
+    meta: &syn::meta::ParseNestedMeta,
+    attribute: &str,
+    keys: &[&str],
+) -> syn::Error {
+    let path = &meta.path;
+    let key = source_string(quote! { #path });
+    let suggestion = closest_key(&key, keys)
+        .map(|closest| format!(" (did you mean `{}`?)", closest))
+        .unwrap_or_default();
+    let expected = match keys.split_last() {
+        Some((last, [])) => format!("`{}`", last),
+        Some((last, rest)) => format!(
+            "one of {} or `{}`",
+            rest.iter()
+                .map(|key| format!("`{}`", key))
+                .collect::<Vec<_>>()
+                .join(", "),
+            last
+        ),
+        None => String::from("no options"),
+    };
+    meta.error(format!(
+        "unsupported {} option `{}`{}, expected {}",
+        attribute, key, suggestion, expected
+    ))
+}
+
+/// Returns the key closest to `key`, if it is near enough to be a likely typo.
+///
+/// Keys are compared ignoring case and underscores, so `macrosMod` and `macrosmod` both suggest
+/// `macros_mod`, and otherwise by edit distance, allowing one edit per three characters.
+pub(crate) fn closest_key<'a>(key: &str, keys: &[&'a str]) -> Option<&'a str> {
+    fn normalize(key: &str) -> Vec<char> {
+        key.chars()
+            .filter(|c| *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    }
+
+    fn edit_distance(a: &[char], b: &[char]) -> usize {
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        for (i, a_char) in a.iter().enumerate() {
+            let mut current = vec![i + 1];
+            for (j, b_char) in b.iter().enumerate() {
+                let substitution = previous[j] + usize::from(a_char != b_char);
+                current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+            }
+            previous = current;
+        }
+        previous[b.len()]
+    }
+
+    let key = normalize(key);
+    let max_distance = key.len() / 3;
+    keys.iter()
+        .map(|candidate| (edit_distance(&key, &normalize(candidate)), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Returns the case named by `#[concrete(as_str = "...")]`, or `None` if it isn't supported.
+pub(crate) fn name_case(case: &syn::LitStr) -> Option<Case<'static>> {
+    match case.value().as_str() {
+        "snake" => Some(Case::Snake),
+        "kebab" => Some(Case::Kebab),
+        "camel" => Some(Case::Camel),
+        "pascal" => Some(Case::Pascal),
+        "screaming_snake" => Some(Case::UpperSnake),
+        "lower" => Some(Case::Flat),
+        _ => None,
+    }
+}
+
+/// Parses the enum-level `#[concrete(...)]` attributes.
+///
+/// Name-value `#[concrete = "..."]` attributes are ignored here, as they only have meaning on
+/// variants.
+pub(crate) fn parse_enum_options(attrs: &[Attribute]) -> syn::Result<EnumOptions> {
+    let mut options = EnumOptions::default();
+
+    for attr in attrs {
+        if !attr.path().is_ident("concrete") || !matches!(attr.meta, Meta::List(_)) {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("base") {
+                options.base = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("macros_mod") {
+                let module: syn::LitStr = meta.value()?.parse()?;
+                options.macros_mod = Some(module.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("prefix_crate_name") {
+                options.prefix_crate_name = true;
+                Ok(())
+            } else if meta.path.is_ident("factory") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                options.factory = Some(path.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("crate_path") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                options.crate_path = Some(path.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("doc_hidden") {
+                options.doc_hidden = true;
+                Ok(())
+            } else if meta.path.is_ident("minimal") || meta.path.is_ident("full") {
+                if meta.path.is_ident("minimal") {
+                    options.minimal = true;
+                } else {
+                    options.full = true;
+                }
+                if options.minimal && options.full {
+                    return Err(meta.error("`minimal` and `full` can't be combined"));
+                }
+                Ok(())
+            } else if meta.path.is_ident("new_default") {
+                options.new_default = true;
+                Ok(())
+            } else if meta.path.is_ident("manifest") {
+                options.manifest = true;
+                Ok(())
+            } else if meta.path.is_ident("api_snapshot") {
+                options.api_snapshot = true;
+                Ok(())
+            } else if meta.path.is_ident("set") {
+                options.set = true;
+                Ok(())
+            } else if meta.path.is_ident("per_variant") {
+                options.per_variant = true;
+                Ok(())
+            } else if meta.path.is_ident("flatten") {
+                options.flatten = true;
+                Ok(())
+            } else if meta.path.is_ident("from_name") {
+                options.from_name = true;
+                Ok(())
+            } else if meta.path.is_ident("keys") {
+                options.keys = true;
+                Ok(())
+            } else if meta.path.is_ident("family") {
+                options.family = true;
+                Ok(())
+            } else if meta.path.is_ident("encode") {
+                options.encode = true;
+                if meta.input.peek(syn::Token![=]) {
+                    let format: syn::LitStr = meta.value()?.parse()?;
+                    if format.value() != "serde" {
+                        return Err(syn::Error::new_spanned(
+                            format,
+                            "unsupported `encode` format, expected `serde`",
+                        ));
+                    }
+                    options.encode_serde = true;
+                }
+                Ok(())
+            } else if meta.path.is_ident("web") {
+                options.web = true;
+                if meta.input.peek(syn::Token![=]) {
+                    options.web_param = Some(meta.value()?.parse()?);
+                }
+                Ok(())
+            } else if meta.path.is_ident("wasm") {
+                options.wasm = true;
+                Ok(())
+            } else if meta.path.is_ident("concrete_of") {
+                options.concrete_of = true;
+                if meta.input.peek(syn::Token![=]) {
+                    let path: syn::LitStr = meta.value()?.parse()?;
+                    options.concrete_of_trait = Some(path.parse()?);
+                }
+                Ok(())
+            } else if meta.path.is_ident("debug") {
+                options.debug = true;
+                Ok(())
+            } else if meta.path.is_ident("from_named_value") {
+                options.from_named_value = true;
+                Ok(())
+            } else if meta.path.is_ident("handle") {
+                options.handle = true;
+                Ok(())
+            } else if meta.path.is_ident("failover") {
+                options.failover = true;
+                Ok(())
+            } else if meta.path.is_ident("max_combinations") {
+                let max_combinations: syn::LitInt = meta.value()?.parse()?;
+                max_combinations.base10_parse::<usize>()?;
+                options.max_combinations = Some(max_combinations);
+                Ok(())
+            } else if meta.path.is_ident("selftest") {
+                options.selftest = true;
+                Ok(())
+            } else if meta.path.is_ident("either") {
+                options.either = true;
+                Ok(())
+            } else if meta.path.is_ident("tagged") {
+                options.tagged = true;
+                Ok(())
+            } else if meta.path.is_ident("compare") {
+                options.compare = true;
+                Ok(())
+            } else if meta.path.is_ident("fallback") {
+                options.fallback = true;
+                Ok(())
+            } else if meta.path.is_ident("unwrap") {
+                options.unwrap = true;
+                Ok(())
+            } else if meta.path.is_ident("as_str") {
+                options.as_str = true;
+                if meta.input.peek(syn::Token![=]) {
+                    let case: syn::LitStr = meta.value()?.parse()?;
+                    if name_case(&case).is_none() {
+                        return Err(syn::Error::new_spanned(
+                            case,
+                            "unsupported `as_str` case, expected `snake`, `kebab`, `camel`, \
+                             `pascal`, `screaming_snake` or `lower`",
+                        ));
+                    }
+                    options.as_str_case = Some(case);
+                }
+                Ok(())
+            } else if meta.path.is_ident("build_info") {
+                options.build_info = true;
+                Ok(())
+            } else if meta.path.is_ident("routing") {
+                options.routing = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("emit_with") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                options.emit_with.push(path.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("delegate_trait") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                options.delegate_traits.push(path.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("for_each") {
+                options.for_each = true;
+                Ok(())
+            } else if meta.path.is_ident("partial") {
+                options.partial = true;
+                Ok(())
+            } else if meta.path.is_ident("relative_macro") {
+                options.relative_macro = true;
+                Ok(())
+            } else if meta.path.is_ident("check_all") {
+                options.check_all = true;
+                if meta.input.peek(syn::Token![=]) {
+                    let bound: syn::LitStr = meta.value()?.parse()?;
+                    options.probe_bound = Some(bound.parse()?);
+                }
+                Ok(())
+            } else if meta.path.is_ident("type_list") {
+                options.type_list = true;
+                if meta.input.peek(syn::Token![=]) {
+                    let bound: syn::LitStr = meta.value()?.parse()?;
+                    options.visitor_bound = Some(bound.parse()?);
+                }
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|derive| match derive.path.get_ident() {
+                    Some(derive_trait)
+                        if derive_trait == "Clone"
+                            || derive_trait == "Debug"
+                            || derive_trait == "Serialize" =>
+                    {
+                        options.derives.push(derive_trait.clone());
+                        Ok(())
+                    }
+                    _ => Err(derive.error(
+                        "unsupported trait in `derive`, expected `Clone`, `Debug` or `Serialize`",
+                    )),
+                })
+            } else {
+                Err(unknown_option_error(
+                    &meta,
+                    "enum-level `concrete`",
+                    ENUM_OPTION_KEYS,
+                ))
+            }
+        })?;
+    }
+
+    if let (true, Some(crate_path)) = (options.relative_macro, &options.crate_path) {
+        return Err(syn::Error::new_spanned(
+            crate_path,
+            "`relative_macro` can't be combined with `crate_path`, which already fixes the root of \
+             `crate::` paths",
+        ));
+    }
+
+    Ok(options)
+}
+
+/// Variant-level options parsed from `#[concrete(...)]` attributes placed on a variant.
+#[derive(Default)]
+pub(crate) struct VariantOptions {
+    /// Concrete type path, the structured alternative to `#[concrete = "..."]`.
+    pub(crate) ty: Option<syn::LitStr>,
+    /// Trait the concrete type is used through, e.g. `crate::ExchangeApi`.
+    pub(crate) trait_path: Option<syn::Path>,
+    /// Name of the concrete type's constructor, e.g. `try_new`.
+    pub(crate) constructor: Option<syn::Ident>,
+    /// Stable numeric tag identifying the variant.
+    pub(crate) tag: Option<syn::LitInt>,
+    /// Cargo feature the concrete type is gated behind, e.g. `binance`.
+    pub(crate) feature: Option<syn::LitStr>,
+    /// The variant's `///` doc comment, forwarded to the generated docs and the manifest.
+    pub(crate) doc: Option<String>,
+    /// Whether the variant's single field is another `Concrete` enum to dispatch on recursively.
+    pub(crate) nested: bool,
+    /// Path of the inner enum's dispatch macro, e.g. `crate::okx::dispatch::okx_account`, when it
+    /// isn't reachable by its default name.
+    pub(crate) nested_macro: Option<syn::Path>,
+    /// The inner enum is defined in another crate, named by the first segment of its path, whose
+    /// root exports its dispatch macro under the default name.
+    pub(crate) nested_extern: bool,
+    /// Extra names the variant is parsed from by `from_name`, besides its own.
+    pub(crate) aliases: Vec<syn::LitStr>,
+    /// The concrete type isn't `Send`, so the variant is left out of the `{Enum}Send` subset.
+    pub(crate) non_send: bool,
+    /// Position of the variant in failover order, lowest first.
+    pub(crate) priority: Option<syn::LitInt>,
+    /// Relative weight of the variant in weighted random selection.
+    pub(crate) weight: Option<syn::LitInt>,
+    /// The config is sensitive, e.g. holds API keys, and is redacted from generated `Debug` and
+    /// `Serialize` output.
+    pub(crate) secret: bool,
+    /// Concrete config type held by a `Box<dyn Trait>` field, downcast to before binding.
+    pub(crate) config: Option<syn::Type>,
+    /// Unwrap the variant's `Box` or `Option` config field when binding it.
+    pub(crate) unwrap: bool,
+    /// Concrete types of a `#[concrete_all = "..."]` cluster, run in turn by the `@all` and
+    /// `@fold` forms. Empty for variants mapped to a single type.
+    pub(crate) cluster: Vec<syn::Path>,
+}
+
+/// Parses the variant-level `#[concrete(...)]` attributes, e.g.
+/// `#[concrete(ty = "crate::Binance", trait = "crate::ExchangeApi", constructor = "new", tag = 3)]`.
+///
+/// Name-value `#[concrete = "..."]` attributes are handled by [`extract_concrete_type_path`].
+pub(crate) fn parse_variant_options(attrs: &[Attribute]) -> syn::Result<VariantOptions> {
+    let mut options = VariantOptions::default();
+    let mut doc_lines = Vec::new();
+
+    for attr in attrs {
+        if attr.path().is_ident("doc") {
+            if let Meta::NameValue(syn::MetaNameValue {
+                value:
+                    Expr::Lit(syn::ExprLit {
+                        lit: Lit::Str(line),
+                        ..
+                    }),
+                ..
+            }) = &attr.meta
+            {
+                let line = line.value();
+                doc_lines.push(line.strip_prefix(' ').unwrap_or(&line).to_string());
+                continue;
+            }
+        }
+
+        let Meta::List(list) = &attr.meta else {
+            continue;
+        };
+        // `#[concrete("...")]` is reported by `extract_concrete_type_path`
+        if !attr.path().is_ident("concrete") || list.parse_args::<syn::LitStr>().is_ok() {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ty") {
+                options.ty = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("trait") {
+                let path: syn::LitStr = meta.value()?.parse()?;
+                options.trait_path = Some(path.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("constructor") {
+                let constructor: syn::LitStr = meta.value()?.parse()?;
+                options.constructor = Some(constructor.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("tag") {
+                let tag: syn::LitInt = meta.value()?.parse()?;
+                tag.base10_parse::<u64>()?;
+                options.tag = Some(tag);
+                Ok(())
+            } else if meta.path.is_ident("feature") || meta.path.is_ident("cargo_feature") {
+                if options.feature.is_some() {
+                    return Err(meta.error("the variant's feature is already set"));
+                }
+                options.feature = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("nested") {
+                options.nested = true;
+                if meta.input.peek(syn::Token![=]) {
+                    let path: syn::LitStr = meta.value()?.parse()?;
+                    options.nested_macro = Some(path.parse()?);
+                }
+                Ok(())
+            } else if meta.path.is_ident("nested_extern") {
+                options.nested = true;
+                options.nested_extern = true;
+                Ok(())
+            } else if meta.path.is_ident("alias") {
+                options.aliases.push(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("non_send") {
+                options.non_send = true;
+                Ok(())
+            } else if meta.path.is_ident("secret") {
+                options.secret = true;
+                Ok(())
+            } else if meta.path.is_ident("unwrap") {
+                options.unwrap = true;
+                Ok(())
+            } else if meta.path.is_ident("config") {
+                let config: syn::LitStr = meta.value()?.parse()?;
+                options.config = Some(config.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("priority") {
+                let priority: syn::LitInt = meta.value()?.parse()?;
+                priority.base10_parse::<i64>()?;
+                options.priority = Some(priority);
+                Ok(())
+            } else if meta.path.is_ident("weight") {
+                let weight: syn::LitInt = meta.value()?.parse()?;
+                weight.base10_parse::<u32>()?;
+                options.weight = Some(weight);
+                Ok(())
+            } else {
+                Err(unknown_option_error(
+                    &meta,
+                    "variant-level `concrete`",
+                    VARIANT_OPTION_KEYS,
+                ))
+            }
+        })?;
+    }
+
+    let doc = doc_lines.join("\n").trim().to_string();
+    options.doc = (!doc.is_empty()).then_some(doc);
+
+    Ok(options)
+}
+
+/// A subset of the variants declared with
+/// `#[concrete_subset(name = "SpotExchange", variants = "Binance, Okx")]`.
+pub(crate) struct SubsetOptions {
+    /// Name of the generated subset enum.
+    pub(crate) name: syn::Ident,
+    /// The `variants` list, for error spans.
+    pub(crate) variants_lit: syn::LitStr,
+    /// Variants of the full enum kept in the subset, in the order listed.
+    pub(crate) variants: Vec<syn::Ident>,
+    /// Traits derived on the subset enum, e.g. `derive(Clone, Copy)`.
+    pub(crate) derives: Vec<syn::Path>,
+}
+
+/// Parses the `#[concrete_subset(...)]` attributes placed on a `Concrete` enum.
+pub(crate) fn parse_subset_options(attrs: &[Attribute]) -> syn::Result<Vec<SubsetOptions>> {
+    let mut subsets = Vec::new();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("concrete_subset"))
+    {
+        let mut name = None;
+        let mut variants_lit: Option<syn::LitStr> = None;
+        let mut derives = Vec::new();
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                name = Some(lit.parse::<syn::Ident>()?);
+                Ok(())
+            } else if meta.path.is_ident("variants") {
+                variants_lit = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("derive") {
+                meta.parse_nested_meta(|derive| {
+                    derives.push(derive.path);
+                    Ok(())
+                })
+            } else {
+                Err(unknown_option_error(
+                    &meta,
+                    "`concrete_subset`",
+                    &["name", "variants", "derive"],
+                ))
+            }
+        })?;
+
+        let (Some(name), Some(variants_lit)) = (name, variants_lit) else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`concrete_subset` requires `name = \"...\"` and `variants = \"A, B\"`",
+            ));
+        };
+        let variants = variants_lit
+            .parse_with(
+                syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated,
+            )?
+            .into_iter()
+            .collect::<Vec<_>>();
+        if variants.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &variants_lit,
+                "`concrete_subset` must list at least one variant",
+            ));
+        }
+        subsets.push(SubsetOptions {
+            name,
+            variants_lit,
+            variants,
+            derives,
+        });
+    }
+    Ok(subsets)
+}
+
+/// Returns the error for a `ConcreteConfig` variant whose fields can't hold its config, spanning
+/// the offending fields and listing what was found.
+pub(crate) fn invalid_config_fields_error(
+    variant_name: &syn::Ident,
+    fields: &Fields,
+) -> syn::Error {
+    let supported = "`ConcreteConfig` variants are either unit variants or hold their config in \
+                     exactly one unnamed field";
+    match fields {
+        Fields::Named(named) => {
+            let field_names = named
+                .named
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+                .map(|ident| format!("`{}`", ident))
+                .collect::<Vec<_>>()
+                .join(", ");
+            syn::Error::new_spanned(
+                named,
+                format!(
+                    "variant `{}` has named fields {}, but {}; move them into a config struct, \
+                     e.g. `{}({}Config)`",
+                    variant_name, field_names, supported, variant_name, variant_name
+                ),
+            )
+        }
+        Fields::Unnamed(unnamed) if unnamed.unnamed.is_empty() => syn::Error::new_spanned(
+            unnamed,
+            format!(
+                "variant `{}` has no fields, but {}; make it a unit variant, e.g. `{}`",
+                variant_name, supported, variant_name
+            ),
+        ),
+        Fields::Unnamed(unnamed) => {
+            let field_types: Vec<_> = unnamed
+                .unnamed
+                .iter()
+                .map(|field| {
+                    let ty = &field.ty;
+                    source_string(quote! { #ty }).replace("& ", "&")
+                })
+                .collect();
+            let found = field_types
+                .iter()
+                .map(|ty| format!("`{}`", ty))
+                .collect::<Vec<_>>()
+                .join(", ");
+            // Span the fields past the first, which is the one a valid variant would keep
+            let extra_fields: Vec<_> = unnamed.unnamed.iter().skip(1).collect();
+            syn::Error::new_spanned(
+                quote! { #(#extra_fields),* },
+                format!(
+                    "variant `{}` has {} unnamed fields ({}), but {}; group them into a tuple, \
+                     e.g. `{}(({}))`, or a config struct",
+                    variant_name,
+                    field_types.len(),
+                    found,
+                    supported,
+                    variant_name,
+                    field_types.join(", "),
+                ),
+            )
+        }
+        Fields::Unit => syn::Error::new_spanned(variant_name, supported),
+    }
+}
+
+/// Resolves the concrete type path for a variant.
+///
+/// An explicit `#[concrete = "..."]` or `#[concrete(ty = "...")]` on the variant takes
+/// precedence. Otherwise the enum-level `base` template is used. In all cases variant
+/// placeholders are substituted first.
+///
+/// Nested variants map to the type of the inner enum they hold.
+pub(crate) fn resolve_concrete_type_path(
+    variant: &syn::Variant,
+    options: &EnumOptions,
+    variant_options: &VariantOptions,
+) -> syn::Result<syn::Path> {
+    let legacy_path = extract_concrete_type_path(&variant.attrs, &variant.ident)?;
+    if variant_options.nested {
+        return nested_enum_path(variant, legacy_path.is_some(), variant_options);
+    }
+    match (legacy_path, &variant_options.ty) {
+        (Some(_), Some(ty)) => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "Enum variant `{}` sets its concrete type with both #[concrete = \"...\"] and `ty`",
+                    variant.ident
+                ),
+            ));
+        }
+        (Some(path), None) => return Ok(path),
+        (None, Some(ty)) => {
+            let path_str = substitute_variant_placeholders(&ty.value(), &variant.ident);
+            return parse_path_at(&path_str, ty.span()).map_err(|_| {
+                syn::Error::new_spanned(ty, format!("`{}` is not a valid type path", path_str))
+            });
+        }
+        (None, None) => {}
+    }
+
+    if let Some(base) = &options.base {
+        // Spanned at the variant, which has no attribute of its own
+        let path_str = substitute_variant_placeholders(&base.value(), &variant.ident);
+        return parse_path_at(&path_str, variant.ident.span()).map_err(|_| {
+            syn::Error::new_spanned(
+                base,
+                format!(
+                    "`base` does not produce a valid path for variant `{}`: `{}`",
+                    variant.ident, path_str
+                ),
+            )
+        });
+    }
+
+    // Variant is missing the #[concrete = "..."] attribute
+    Err(syn::Error::new_spanned(
+        &variant.ident,
+        format!(
+            "Enum variant `{}` is missing the #[concrete = \"...\"] attribute (or `ty` option)",
+            variant.ident
+        ),
+    ))
+}
+
+/// Returns the path of the inner enum held by a `#[concrete(nested)]` variant.
+pub(crate) fn nested_enum_path(
+    variant: &syn::Variant,
+    has_legacy_path: bool,
+    variant_options: &VariantOptions,
+) -> syn::Result<syn::Path> {
+    if has_legacy_path || variant_options.ty.is_some() {
+        return Err(syn::Error::new_spanned(
+            &variant.ident,
+            format!(
+                "Nested variant `{}` takes its concrete types from the inner enum and can't set one itself",
+                variant.ident
+            ),
+        ));
+    }
+    if let Some(feature) = &variant_options.feature {
+        return Err(syn::Error::new_spanned(
+            feature,
+            "`feature` is not supported on nested variants, gate the inner enum's variants instead",
+        ));
+    }
+
+    let path = match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => match &fields.unnamed[0].ty {
+            syn::Type::Path(type_path) if type_path.qself.is_none() => type_path.path.clone(),
+            ty => {
+                return Err(syn::Error::new_spanned(
+                    ty,
+                    "the field of a nested variant must be a `Concrete` enum",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "Nested variant `{}` must have exactly one unnamed field holding a `Concrete` enum",
+                    variant.ident
+                ),
+            ));
+        }
+    };
+    if variant_options.nested_extern {
+        if variant_options.nested_macro.is_some() {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                "`nested_extern` finds the inner macro at the root of the inner enum's crate, and \
+                 can't be combined with `nested = \"...\"`",
+            ));
+        }
+        let local = path.segments.first().is_some_and(|segment| {
+            ["crate", "self", "super"]
+                .iter()
+                .any(|keyword| segment.ident == keyword)
+        });
+        if path.segments.len() < 2 || local {
+            return Err(syn::Error::new_spanned(
+                &path,
+                "`nested_extern` needs the path of an enum in another crate, starting with the \
+                 crate's name, e.g. `dex::DexExchange`",
+            ));
+        }
+    }
+    Ok(path)
+}
+
+/// How the config field of a `ConcreteConfig` variant is bound in the generated code.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConfigField<'a> {
+    /// Unit variant, bound to `()`.
+    Unit,
+    /// Single field, bound as-is.
+    Plain(&'a syn::Type),
+    /// `&'a T` field, bound as-is. `config()` returns the referenced `T`.
+    Borrowed(&'a syn::Type),
+    /// `Box<T>` field marked `unwrap`, unboxed when bound, or reborrowed when the enum is
+    /// matched by reference.
+    Boxed(&'a syn::Type),
+    /// `Option<T>` field marked `unwrap`, bound to `T::default()` (or the `else` block) when
+    /// `None`.
+    Optional(&'a syn::Type),
+    /// `Box<dyn Trait>` field declared with `#[concrete(config = "T")]`, downcast to `T` and
+    /// unboxed when bound. `Trait` must have `Any` as a supertrait.
+    Erased(&'a syn::Type),
+}
+
+impl<'a> ConfigField<'a> {
+    /// Classifies the type of a single config field. `Box` and `Option` fields are only
+    /// unwrapped when `unwrap` is set, and are otherwise bound as-is.
+    ///
+    /// `Box<dyn Trait>` is treated as a plain field since a trait object can't be moved out of
+    /// its box.
+    pub(crate) fn from_type(ty: &'a syn::Type, unwrap: bool) -> Self {
+        match (ty, wrapper_inner_type(ty)) {
+            (syn::Type::Reference(reference), _) => Self::Borrowed(&reference.elem),
+            (_, Some(("Box", inner))) if unwrap && !matches!(inner, syn::Type::TraitObject(_)) => {
+                Self::Boxed(inner)
+            }
+            (_, Some(("Option", inner))) if unwrap => Self::Optional(inner),
+            _ => Self::Plain(ty),
+        }
+    }
+
+    /// Returns the type `config()` erases to `dyn Any`, looking through references, boxes and
+    /// options.
+    pub(crate) fn erased_type(&self) -> Option<&'a syn::Type> {
+        match self {
+            Self::Unit => None,
+            Self::Plain(ty) | Self::Borrowed(ty) | Self::Boxed(ty) | Self::Erased(ty) => Some(ty),
+            Self::Optional(syn::Type::Reference(reference)) => Some(&reference.elem),
+            Self::Optional(ty) => Some(ty),
+        }
+    }
+}
+
+/// Returns the wrapper name and inner type of a `Box<T>` or `Option<T>` type.
+pub(crate) fn wrapper_inner_type(ty: &syn::Type) -> Option<(&'static str, &syn::Type)> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let wrapper = ["Box", "Option"]
+        .into_iter()
+        .find(|wrapper| segment.ident == wrapper)?;
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(angle) if angle.args.len() == 1 => {
+            match angle.args.first()? {
+                syn::GenericArgument::Type(inner) => Some((wrapper, inner)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Rejects the enum options `Concrete` doesn't support, and those `partial` can't be combined
+/// with.
+pub(crate) fn check_concrete_options(
+    type_name: &syn::Ident,
+    options: &EnumOptions,
+) -> syn::Result<()> {
+    if let Some(derive_trait) = options.derives.first() {
+        return Err(syn::Error::new_spanned(
+            derive_trait,
+            "`derive` is only supported by `ConcreteConfig`, use `#[derive(...)]` on this enum instead",
+        ));
+    }
+    if options.from_named_value {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`from_named_value` is only supported by `ConcreteConfig`, use `from_name` to parse \
+             variants without config",
+        ));
+    }
+    if options.either {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`either` is only supported by `ConcreteConfig`, as it borrows the variants' configs",
+        ));
+    }
+    if options.compare {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`compare` is only supported by `ConcreteConfig`, as it binds the configs of two \
+             values",
+        ));
+    }
+    if options.fallback {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`fallback` is only supported by `ConcreteConfig`, use `failover` to try kind enum \
+             variants in turn",
+        ));
+    }
+    if options.unwrap {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`unwrap` is only supported by `ConcreteConfig`, as it unwraps the variants' configs",
+        ));
+    }
+
+    if let (Some(trait_path), false) = (options.delegate_traits.first(), options.new_default) {
+        return Err(syn::Error::new_spanned(
+            trait_path,
+            "`delegate_trait` requires `new_default`, which generates the instance enum",
+        ));
+    }
+
+    // Partially applied concrete types can't be named without the call site's arguments
+    if options.partial {
+        let complete_only = [
+            ("factory", options.factory.is_some()),
+            ("new_default", options.new_default),
+            ("keys", options.keys),
+            ("family", options.family),
+            ("for_each", options.for_each),
+            ("concrete_of", options.concrete_of),
+            ("check_all", options.check_all),
+            ("type_list", options.type_list),
+        ];
+        if let Some((option, _)) = complete_only.iter().find(|(_, enabled)| *enabled) {
+            return Err(syn::Error::new_spanned(
+                type_name,
+                format!(
+                    "`{}` needs complete concrete types and can't be combined with `partial`",
+                    option
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the variants of a `Concrete` enum, resolving the concrete type each maps to.
+pub(crate) fn parse_concrete_variants<'a>(
+    data_enum: &'a syn::DataEnum,
+    options: &EnumOptions,
+) -> syn::Result<Vec<(&'a syn::Ident, syn::Path, VariantOptions)>> {
+    let mut variant_mappings = Vec::new();
+
+    for variant in &data_enum.variants {
+        let variant_options = parse_variant_options(&variant.attrs)?;
+        if variant_options.secret {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                "`secret` is only supported by `ConcreteConfig`, as it redacts the variant's config",
+            ));
+        }
+        if let Some(config) = &variant_options.config {
+            return Err(syn::Error::new_spanned(
+                config,
+                "`config` is only supported by `ConcreteConfig`, as it downcasts the variant's \
+                 config",
+            ));
+        }
+        if variant_options.unwrap {
+            return Err(syn::Error::new_spanned(
+                &variant.ident,
+                "`unwrap` is only supported by `ConcreteConfig`, as it unwraps the variant's config",
+            ));
+        }
+        // Cluster variants stand in for their first concrete type wherever a single one is needed
+        let cluster = extract_concrete_cluster(&variant.attrs, &variant.ident)?;
+        if let Some(cluster) = cluster {
+            let single_type = extract_concrete_type_path(&variant.attrs, &variant.ident)?.is_some()
+                || variant_options.ty.is_some();
+            let error = if single_type || variant_options.nested {
+                Some(format!(
+                    "Enum variant `{}` maps to the cluster of #[concrete_all = \"...\"], and can't \
+                     also set a single concrete type or be nested",
+                    variant.ident
+                ))
+            } else if options.partial {
+                Some(
+                    "`concrete_all` needs complete concrete types and can't be combined with \
+                     `partial`"
+                        .to_string(),
+                )
+            } else {
+                None
+            };
+            if let Some(message) = error {
+                return Err(syn::Error::new_spanned(&variant.ident, message));
+            }
+            let lead = cluster[0].clone();
+            variant_mappings.push((
+                &variant.ident,
+                lead,
+                VariantOptions {
+                    cluster,
+                    ..variant_options
+                },
+            ));
+            continue;
+        }
+        let concrete_type = resolve_concrete_type_path(variant, options, &variant_options)?;
+        variant_mappings.push((&variant.ident, concrete_type, variant_options));
+    }
+
+    Ok(variant_mappings)
+}
+
+/// Rejects the enum options only `Concrete` supports.
+pub(crate) fn check_config_options(
+    type_name: &syn::Ident,
+    options: &EnumOptions,
+) -> syn::Result<()> {
+    if let Some(factory_trait) = &options.factory {
+        return Err(syn::Error::new_spanned(
+            factory_trait,
+            "`factory` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.new_default {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`new_default` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.set {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`set` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.per_variant {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`per_variant` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.flatten {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`flatten` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.from_name {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`from_name` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.web {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`web` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if let Some(trait_path) = options.delegate_traits.first() {
+        return Err(syn::Error::new_spanned(
+            trait_path,
+            "`delegate_trait` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.handle {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`handle` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.failover {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`failover` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.selftest {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`selftest` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.partial {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`partial` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.for_each {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`for_each` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.keys {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`keys` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.family {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`family` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.tagged {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`tagged` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.as_str {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`as_str` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.type_list {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`type_list` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.concrete_of {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`concrete_of` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.encode {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`encode` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.wasm {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`wasm` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.max_combinations.is_some() {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`max_combinations` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.debug {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`debug` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+    if options.check_all {
+        return Err(syn::Error::new_spanned(
+            type_name,
+            "`check_all` is only supported by `Concrete`, derive it on the kind enum instead",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Parses the variants of a `ConcreteConfig` enum, resolving the concrete type each maps to and
+/// how its config field is bound.
+pub(crate) fn parse_config_variants<'a>(
+    data_enum: &'a syn::DataEnum,
+    options: &EnumOptions,
+) -> syn::Result<Vec<(&'a syn::Ident, syn::Path, ConfigField<'a>, VariantOptions)>> {
+    let mut variant_mappings = Vec::new();
+
+    for variant in &data_enum.variants {
+        let variant_name = &variant.ident;
+        let variant_options = parse_variant_options(&variant.attrs)?;
+        if variant_options.nested {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                "`nested` is only supported by `Concrete`",
+            ));
+        }
+        if let Some(weight) = &variant_options.weight {
+            return Err(syn::Error::new_spanned(
+                weight,
+                "`weight` is only supported by `Concrete`, derive it on the kind enum instead",
+            ));
+        }
+        if let Some(attr) = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("concrete_all"))
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`concrete_all` is only supported by `Concrete`, as a variant holds the config of \
+                 a single concrete type",
+            ));
+        }
+        if let Some(priority) = &variant_options.priority {
+            return Err(syn::Error::new_spanned(
+                priority,
+                "`priority` is only supported by `Concrete`, derive it on the kind enum instead",
+            ));
+        }
+        if variant_options.non_send {
+            return Err(syn::Error::new_spanned(
+                variant_name,
+                "`non_send` is only supported by `Concrete`, as it selects the variants of the \
+                 `Send` factory",
+            ));
+        }
+
+        // Resolve the concrete type path from the variant or enum-level attributes
+        match resolve_concrete_type_path(variant, options, &variant_options) {
+            Ok(concrete_type) => {
+                // Check variant field type - now accepting both unit variants and single-field variants
+                match &variant.fields {
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        // Variant with config data, possibly wrapped in a Box or Option
+                        let field_type = &fields.unnamed[0].ty;
+                        let config_field =
+                            match (&variant_options.config, wrapper_inner_type(field_type)) {
+                                (None, _) => ConfigField::from_type(
+                                    field_type,
+                                    options.unwrap || variant_options.unwrap,
+                                ),
+                                (Some(_), Some(("Box", syn::Type::TraitObject(_)))) => {
+                                    ConfigField::Erased(field_type)
+                                }
+                                (Some(_), _) => {
+                                    return Err(syn::Error::new_spanned(
+                                        field_type,
+                                        "`config` requires the variant's field to be a \
+                                     `Box<dyn Trait>`, with `Any` as a supertrait of `Trait`",
+                                    ));
+                                }
+                            };
+                        variant_mappings.push((
+                            variant_name,
+                            concrete_type,
+                            config_field,
+                            variant_options,
+                        ));
+                    }
+                    Fields::Unit => {
+                        if let Some(config) = &variant_options.config {
+                            return Err(syn::Error::new_spanned(
+                                config,
+                                "`config` requires the variant's field to be a `Box<dyn Trait>`, \
+                                 but it is a unit variant",
+                            ));
+                        }
+                        // Unit variant (no config data)
+                        variant_mappings.push((
+                            variant_name,
+                            concrete_type,
+                            ConfigField::Unit,
+                            variant_options,
+                        ));
+                    }
+                    fields => {
+                        return Err(invalid_config_fields_error(variant_name, fields));
+                    }
+                }
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(variant_mappings)
+}
@@ -0,0 +1,207 @@
+//! Rewriting of concrete type paths for use in generated `macro_rules!` macros.
+
+use quote::quote;
+
+use crate::options::EnumOptions;
+
+/// Returns the tokens that `crate::` paths are rewritten to in generated macros.
+///
+/// This is `$crate` unless the enum names a public path with `#[concrete(crate_path = "...")]`,
+/// e.g. a facade crate that re-exports the defining crate.
+pub(crate) fn macro_crate_root(options: &EnumOptions) -> proc_macro2::TokenStream {
+    match &options.crate_path {
+        Some(crate_path) => quote! { #crate_path },
+        None => quote! { $crate },
+    }
+}
+
+/// Transforms a path for use in generated macro code.
+///
+/// If the path starts with `crate::`, it transforms to `$crate::` (or the given `crate_root`)
+/// for proper macro hygiene. This allows the generated macro to work correctly both within
+/// the defining crate and from external crates.
+///
+/// This function also recursively transforms any `crate::` paths inside generic
+/// arguments (e.g., `Wrapper<crate::inner::Type>` becomes `Wrapper<$crate::inner::Type>`).
+///
+/// Paths that don't start with `crate::` are returned as-is (after processing their generics).
+pub(crate) fn transform_path_for_macro(
+    path: &syn::Path,
+    crate_root: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let starts_with_crate = path
+        .segments
+        .first()
+        .map(|s| s.ident == "crate")
+        .unwrap_or(false);
+
+    // Process each segment, transforming generic arguments recursively
+    let transformed_segments: Vec<proc_macro2::TokenStream> = path
+        .segments
+        .iter()
+        .enumerate()
+        .filter_map(|(i, segment)| {
+            // Skip the leading `crate` segment if present
+            if starts_with_crate && i == 0 {
+                return None;
+            }
+
+            let ident = &segment.ident;
+            let args = transform_path_arguments(&segment.arguments, crate_root);
+
+            Some(quote! { #ident #args })
+        })
+        .collect();
+
+    if starts_with_crate && !transformed_segments.is_empty() {
+        quote! { #crate_root :: #(#transformed_segments)::* }
+    } else if transformed_segments.is_empty() {
+        // Path was just `crate` with no following segments - unusual but handle it
+        quote! { #path }
+    } else {
+        quote! { #(#transformed_segments)::* }
+    }
+}
+
+/// Transform path arguments (generic parameters), recursively handling nested `crate::` paths.
+pub(crate) fn transform_path_arguments(
+    args: &syn::PathArguments,
+    crate_root: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match args {
+        syn::PathArguments::None => quote! {},
+        syn::PathArguments::AngleBracketed(angle) => {
+            let transformed_args: Vec<proc_macro2::TokenStream> = angle
+                .args
+                .iter()
+                .map(|arg| transform_generic_argument(arg, crate_root))
+                .collect();
+            quote! { < #(#transformed_args),* > }
+        }
+        syn::PathArguments::Parenthesized(paren) => {
+            let inputs: Vec<_> = paren
+                .inputs
+                .iter()
+                .map(|ty| transform_type(ty, crate_root))
+                .collect();
+            let output = match &paren.output {
+                syn::ReturnType::Default => quote! {},
+                syn::ReturnType::Type(arrow, ty) => {
+                    let transformed = transform_type(ty, crate_root);
+                    quote! { #arrow #transformed }
+                }
+            };
+            quote! { ( #(#inputs),* ) #output }
+        }
+    }
+}
+
+/// Transform a single generic argument, recursively handling `crate::` paths within.
+pub(crate) fn transform_generic_argument(
+    arg: &syn::GenericArgument,
+    crate_root: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match arg {
+        syn::GenericArgument::Type(ty) => transform_type(ty, crate_root),
+        syn::GenericArgument::Lifetime(lt) => quote! { #lt },
+        syn::GenericArgument::Const(expr) => quote! { #expr },
+        other => quote! { #other },
+    }
+}
+
+/// Transform a path like [`transform_path_for_macro`], appending `type_args` to the generic
+/// arguments of its last segment, after any the path already supplies.
+pub(crate) fn transform_path_with_type_args(
+    path: &syn::Path,
+    crate_root: &proc_macro2::TokenStream,
+    type_args: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut base = path.clone();
+    let existing_args = match base
+        .segments
+        .last_mut()
+        .map(|segment| std::mem::take(&mut segment.arguments))
+    {
+        Some(syn::PathArguments::AngleBracketed(angle)) => angle
+            .args
+            .iter()
+            .map(|arg| transform_generic_argument(arg, crate_root))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let base = transform_path_for_macro(&base, crate_root);
+    quote! { #base < #(#existing_args,)* #type_args > }
+}
+
+/// Transform a type, recursively handling `crate::` paths within.
+pub(crate) fn transform_type(
+    ty: &syn::Type,
+    crate_root: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match ty {
+        syn::Type::Path(type_path) => {
+            let transformed = transform_path_for_macro(&type_path.path, crate_root);
+            if let Some(qself) = &type_path.qself {
+                let qself_ty = transform_type(&qself.ty, crate_root);
+                quote! { < #qself_ty > :: #transformed }
+            } else {
+                transformed
+            }
+        }
+        syn::Type::Reference(ref_type) => {
+            let lifetime = &ref_type.lifetime;
+            let mutability = &ref_type.mutability;
+            let elem = transform_type(&ref_type.elem, crate_root);
+            quote! { & #lifetime #mutability #elem }
+        }
+        syn::Type::Tuple(tuple) => {
+            let elems: Vec<_> = tuple
+                .elems
+                .iter()
+                .map(|ty| transform_type(ty, crate_root))
+                .collect();
+            quote! { ( #(#elems),* ) }
+        }
+        syn::Type::Slice(slice) => {
+            let elem = transform_type(&slice.elem, crate_root);
+            quote! { [ #elem ] }
+        }
+        syn::Type::Array(array) => {
+            let elem = transform_type(&array.elem, crate_root);
+            let len = &array.len;
+            quote! { [ #elem ; #len ] }
+        }
+        syn::Type::Ptr(ptr) => {
+            let mutability = if ptr.mutability.is_some() {
+                quote! { mut }
+            } else {
+                quote! { const }
+            };
+            let elem = transform_type(&ptr.elem, crate_root);
+            quote! { * #mutability #elem }
+        }
+        // For other types, just quote them as-is
+        other => quote! { #other },
+    }
+}
+
+/// Renders a concrete type path as it would be written in source, e.g. `crate::exchanges::Binance`.
+///
+/// The path is rendered as written in the attribute, without the `$crate` transformation.
+pub(crate) fn concrete_path_string(path: &syn::Path) -> String {
+    source_string(quote! { #path })
+}
+
+/// Renders tokens as they would be written in source, removing the spaces `to_string` inserts
+/// around path separators, angle brackets and commas.
+pub(crate) fn source_string(tokens: proc_macro2::TokenStream) -> String {
+    tokens
+        .to_string()
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace(" < ", "<")
+        .replace("< ", "<")
+        .replace(" <", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+}
@@ -0,0 +1,53 @@
+use concrete_type::Concrete;
+use std::collections::HashMap;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct BinanceUs;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(as_str)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::BinanceUs"]
+    BinanceUs,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(minimal, as_str = "kebab")]
+enum Venue {
+    #[concrete = "exchanges::Binance"]
+    Spot,
+    #[concrete = "exchanges::BinanceUs"]
+    UsMargin,
+    #[concrete(nested)]
+    Routed(Exchange),
+}
+
+#[test]
+fn test_as_str() {
+    assert_eq!(Exchange::Binance.as_str(), "Binance");
+    assert_eq!(Exchange::BinanceUs.as_str(), "BinanceUs");
+    assert_eq!(Exchange::BinanceUs.name(), Exchange::BinanceUs.as_str());
+
+    // Usable in const contexts, and as a key
+    const KEY: &str = Exchange::BinanceUs.as_str();
+    let mut balances = HashMap::new();
+    balances.insert(KEY, 10);
+    assert_eq!(balances[Exchange::BinanceUs.name()], 10);
+}
+
+#[test]
+fn test_as_str_case() {
+    assert_eq!(Venue::Spot.as_str(), "spot");
+    assert_eq!(Venue::UsMargin.as_str(), "us-margin");
+    assert_eq!(Venue::Routed(Exchange::Binance).as_str(), "routed");
+
+    let name = venue!(Venue::UsMargin; V => {
+        let _ = (exchanges::Binance, exchanges::BinanceUs);
+        std::any::type_name::<V>()
+    });
+    assert!(name.ends_with("BinanceUs"));
+}
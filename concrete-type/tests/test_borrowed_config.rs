@@ -0,0 +1,85 @@
+use concrete_type::ConcreteConfig;
+use std::borrow::Cow;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+
+    #[derive(Debug, PartialEq)]
+    pub struct BinanceConfig {
+        pub api_key: String,
+    }
+}
+
+use exchanges::BinanceConfig;
+
+#[derive(ConcreteConfig, Clone, Copy)]
+enum ExchangeConfig<'a> {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(&'a BinanceConfig),
+    #[concrete = "crate::exchanges::Okx"]
+    #[concrete(unwrap)]
+    Okx(Option<&'a BinanceConfig>),
+}
+
+#[derive(ConcreteConfig)]
+enum LabelConfig<'a> {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(Cow<'a, str>),
+}
+
+fn api_key<'a>(config: ExchangeConfig<'a>) -> Option<(&'static str, &'a str)> {
+    exchange_config!(config; (Exchange, cfg) => {
+        Some((std::any::type_name::<Exchange>(), cfg.api_key.as_str()))
+    } else {
+        None
+    })
+}
+
+#[test]
+fn test_borrowed_config_binds_reference() {
+    let owned = BinanceConfig {
+        api_key: "key".to_string(),
+    };
+    let config = ExchangeConfig::Binance(&owned);
+    #[cfg(feature = "config-any")]
+    assert_eq!(
+        config.config().downcast_ref::<BinanceConfig>(),
+        Some(&owned)
+    );
+    assert_eq!(
+        api_key(config),
+        Some((std::any::type_name::<exchanges::Binance>(), "key"))
+    );
+
+    let config = ExchangeConfig::Okx(Some(&owned));
+    #[cfg(feature = "config-any")]
+    assert_eq!(
+        config.config().downcast_ref::<BinanceConfig>(),
+        Some(&owned)
+    );
+    assert_eq!(
+        api_key(config),
+        Some((std::any::type_name::<exchanges::Okx>(), "key"))
+    );
+}
+
+#[test]
+fn test_borrowed_config_without_data() {
+    let config = ExchangeConfig::Okx(None);
+    #[cfg(feature = "config-any")]
+    assert!(config.config().is::<()>());
+    assert_eq!(api_key(config), None);
+}
+
+#[test]
+fn test_lifetime_in_owned_config_requires_static() {
+    let config = LabelConfig::Binance(Cow::Borrowed("label"));
+    #[cfg(feature = "config-any")]
+    assert_eq!(
+        config.config().downcast_ref::<Cow<'static, str>>(),
+        Some(&Cow::Borrowed("label"))
+    );
+    let label = label_config!(config; (_Exchange, cfg) => { cfg.into_owned() });
+    assert_eq!(label, "label");
+}
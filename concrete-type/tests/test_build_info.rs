@@ -0,0 +1,64 @@
+// `binance` stands in for a backend feature that is declared but disabled, which an integration
+// test can't declare for itself
+#![allow(unexpected_cfgs)]
+
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct OkxMargin;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(build_info)]
+enum Exchange {
+    #[concrete(ty = "exchanges::Binance", tag = 1, feature = "binance")]
+    Binance,
+    #[concrete(ty = "exchanges::Okx", tag = 2)]
+    Okx,
+    #[concrete_all = "exchanges::Okx, exchanges::OkxMargin"]
+    OkxFamily,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(minimal, build_info)]
+enum ExchangeConfig {
+    #[concrete = "exchanges::Okx"]
+    Okx(u32),
+}
+
+#[test]
+fn test_build_info() {
+    let expected = format!(
+        "Exchange mapping of concrete-type {}\n  \
+         Binance -> exchanges::Binance, tag 1, feature binance disabled\n  \
+         Okx -> exchanges::Okx, tag 2\n  \
+         OkxFamily -> exchanges::Okx + exchanges::OkxMargin",
+        env!("CARGO_PKG_VERSION")
+    );
+    assert_eq!(Exchange::build_info(), expected);
+
+    // Computed at compile time
+    const INFO: &str = Exchange::build_info();
+    assert_eq!(INFO, Exchange::build_info());
+}
+
+#[test]
+fn test_config_build_info() {
+    assert_eq!(
+        ExchangeConfig::build_info(),
+        format!(
+            "ExchangeConfig mapping of concrete-type {}\n  Okx -> exchanges::Okx",
+            env!("CARGO_PKG_VERSION")
+        )
+    );
+
+    let okx = exchange!(Exchange::Okx; E => { std::any::type_name::<E>() });
+    let config = exchange_config!(ExchangeConfig::Okx(1); (E, config) => {
+        let _ = (exchanges::Binance, exchanges::Okx, exchanges::OkxMargin);
+        (std::any::type_name::<E>(), config)
+    });
+    assert_eq!(config, (okx, 1));
+    let _ = [Exchange::Binance, Exchange::OkxFamily];
+}
@@ -0,0 +1,70 @@
+// The generated macros end with a catch-all arm turning malformed invocations into a
+// `compile_error!`. These check that well-formed invocations never fall through to it.
+
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub trait Exchange {
+        fn name() -> &'static str;
+    }
+
+    pub struct Binance;
+    pub struct Okx;
+
+    impl Exchange for Binance {
+        fn name() -> &'static str {
+            "binance"
+        }
+    }
+
+    impl Exchange for Okx {
+        fn name() -> &'static str {
+            "okx"
+        }
+    }
+}
+
+use exchanges::Exchange as _;
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(u32),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_well_formed_dispatch_skips_catch_all() {
+    let exchanges = [Exchange::Binance, Exchange::Okx];
+
+    // Any expression is accepted as the value, however many tokens it spans
+    assert_eq!(exchange!(exchanges[1]; E => { E::name() }), "okx");
+    assert_eq!(
+        exchange!({ let index = 0; exchanges[index] }; E => { E::name() }),
+        "binance"
+    );
+    assert_eq!(
+        exchange!(exchanges.iter().copied().last().unwrap(); E => { E::name() }),
+        "okx"
+    );
+}
+
+#[test]
+fn test_well_formed_config_dispatch_skips_catch_all() {
+    let configs = [ExchangeConfig::Binance(7), ExchangeConfig::Okx];
+
+    let names: Vec<String> = configs
+        .into_iter()
+        .map(|config| exchange_config!(config; (E, cfg) => { format!("{}:{:?}", E::name(), cfg) }))
+        .collect();
+    assert_eq!(names, ["binance:7", "okx:()"]);
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "async")]
+
+use concrete_type::Concrete;
+use std::cell::RefCell;
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+trait HealthCheck {
+    const NAME: &'static str;
+    const HEALTHY: bool;
+}
+
+impl HealthCheck for exchanges::Binance {
+    const NAME: &'static str = "binance";
+    const HEALTHY: bool = true;
+}
+
+impl HealthCheck for exchanges::Okx {
+    const NAME: &'static str = "okx";
+    const HEALTHY: bool = false;
+}
+
+impl HealthCheck for exchanges::Kraken {
+    const NAME: &'static str = "kraken";
+    const HEALTHY: bool = true;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(check_all = "HealthCheck")]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+    #[concrete = "exchanges::Kraken"]
+    Kraken,
+}
+
+/// Yields once before completing, recording the order probes are polled in
+struct Probe {
+    log: RefCell<Vec<String>>,
+}
+
+impl ExchangeProbe for Probe {
+    type Error = String;
+
+    async fn probe<T: HealthCheck + 'static>(&self, variant: Exchange) -> Result<(), String> {
+        self.log.borrow_mut().push(format!("start {}", T::NAME));
+        YieldOnce(false).await;
+        self.log.borrow_mut().push(format!("end {:?}", variant));
+        if T::HEALTHY {
+            Ok(())
+        } else {
+            Err(format!("{} is unreachable", T::NAME))
+        }
+    }
+}
+
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn test_check_all_probes_every_variant() {
+    let probe = Probe {
+        log: RefCell::new(Vec::new()),
+    };
+    let results = block_on(Exchange::check_all(&probe));
+
+    assert_eq!(
+        results,
+        vec![
+            (Exchange::Binance, Ok(())),
+            (Exchange::Okx, Err(String::from("okx is unreachable"))),
+            (Exchange::Kraken, Ok(())),
+        ]
+    );
+}
+
+#[test]
+fn test_check_all_runs_probes_concurrently() {
+    let probe = Probe {
+        log: RefCell::new(Vec::new()),
+    };
+    block_on(Exchange::check_all(&probe));
+
+    // Every probe starts before any of them completes
+    assert_eq!(
+        probe.log.into_inner(),
+        [
+            "start binance",
+            "start okx",
+            "start kraken",
+            "end Binance",
+            "end Okx",
+            "end Kraken"
+        ]
+    );
+}
@@ -0,0 +1,118 @@
+use concrete_type::Concrete;
+
+trait Venue {
+    fn name() -> &'static str;
+}
+
+mod venues {
+    use super::Venue;
+
+    pub struct BinanceSpot;
+    pub struct BinanceMargin;
+    pub struct BinanceFutures;
+    pub struct Okx;
+
+    impl Venue for BinanceSpot {
+        fn name() -> &'static str {
+            "binance-spot"
+        }
+    }
+
+    impl Venue for BinanceMargin {
+        fn name() -> &'static str {
+            "binance-margin"
+        }
+    }
+
+    impl Venue for BinanceFutures {
+        fn name() -> &'static str {
+            "binance-futures"
+        }
+    }
+
+    impl Venue for Okx {
+        fn name() -> &'static str {
+            "okx"
+        }
+    }
+
+    pub struct Spot;
+    pub struct Margin;
+
+    pub struct Kraken<A, B>(std::marker::PhantomData<(A, B)>);
+
+    impl<A, B> Venue for Kraken<A, B> {
+        fn name() -> &'static str {
+            "kraken"
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete_all = "venues::BinanceSpot, venues::BinanceMargin, venues::BinanceFutures"]
+    Binance,
+    #[concrete = "venues::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Broker {
+    // Commas within generic arguments don't split the list, and a trailing comma is allowed
+    #[concrete_all = "venues::Kraken<venues::Spot, venues::Margin>, venues::{Variant},"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Route {
+    #[concrete_all = "venues::Okx, venues::BinanceSpot"]
+    Split,
+    #[concrete(nested)]
+    Via(Exchange),
+}
+
+#[test]
+fn test_all_collects_per_type() {
+    let names = exchange!(@all Exchange::Binance; V => { V::name() });
+    assert_eq!(names, ["binance-spot", "binance-margin", "binance-futures"]);
+
+    let names = exchange!(@all Exchange::Okx; V => { V::name() });
+    assert_eq!(names, ["okx"]);
+}
+
+#[test]
+fn test_fold() {
+    let joined = exchange!(@fold Exchange::Binance, String::new(); (acc, V) => {
+        if acc.is_empty() { V::name().to_string() } else { acc + "," + V::name() }
+    });
+    assert_eq!(joined, "binance-spot,binance-margin,binance-futures");
+
+    let count = exchange!(@fold Exchange::Okx, 0; (count, V) => { count + V::name().len() });
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn test_cluster_lead_type() {
+    // Outside `@all` and `@fold`, a cluster stands in for its first concrete type
+    let name = exchange!(Exchange::Binance; V => { V::name() });
+    assert_eq!(name, "binance-spot");
+    assert_eq!(
+        Exchange::Binance.concrete_type_name(),
+        "venues::BinanceSpot"
+    );
+}
+
+#[test]
+fn test_nested_runs_inner_dispatch_once() {
+    let names = route!(@all Route::Split; V => { V::name() });
+    assert_eq!(names, ["okx", "binance-spot"]);
+
+    let names = route!(@all Route::Via(Exchange::Okx); V => { V::name() });
+    assert_eq!(names, ["okx"]);
+}
+
+#[test]
+fn test_cluster_paths_with_generic_arguments() {
+    let names = broker!(@all Broker::Okx; V => { V::name() });
+    assert_eq!(names, ["kraken", "okx"]);
+}
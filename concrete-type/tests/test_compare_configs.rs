@@ -0,0 +1,94 @@
+use concrete_type::ConcreteConfig;
+use std::any::type_name;
+
+mod exchanges {
+    pub trait ExchangeApi {
+        type Config;
+
+        fn fee_bps(config: &Self::Config) -> u32;
+    }
+
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+
+    pub struct BinanceConfig {
+        pub vip_level: u32,
+    }
+
+    impl ExchangeApi for Binance {
+        type Config = BinanceConfig;
+
+        fn fee_bps(config: &BinanceConfig) -> u32 {
+            10 - config.vip_level
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        type Config = u32;
+
+        fn fee_bps(config: &u32) -> u32 {
+            *config
+        }
+    }
+
+    impl ExchangeApi for Kraken {
+        type Config = ();
+
+        fn fee_bps(_config: &()) -> u32 {
+            16
+        }
+    }
+}
+
+use exchanges::{BinanceConfig, ExchangeApi};
+
+#[derive(ConcreteConfig)]
+#[concrete(unwrap, compare)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(Box<BinanceConfig>),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(Option<u32>),
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+fn fee_spread(buy: ExchangeConfig, sell: ExchangeConfig) -> i64 {
+    compare_exchange_configs!(buy, sell; (Buy, buy_config), (Sell, sell_config) => {
+        i64::from(Sell::fee_bps(&sell_config)) - i64::from(Buy::fee_bps(&buy_config))
+    })
+}
+
+#[test]
+fn test_compare_binds_both_configs() {
+    let binance = || ExchangeConfig::Binance(Box::new(BinanceConfig { vip_level: 2 }));
+    assert_eq!(fee_spread(binance(), ExchangeConfig::Okx(Some(5))), -3);
+    assert_eq!(fee_spread(ExchangeConfig::Okx(Some(5)), binance()), 3);
+    assert_eq!(fee_spread(ExchangeConfig::Kraken, binance()), -8);
+    // A missing optional config defaults, as with `exchange_config!`
+    assert_eq!(
+        fee_spread(ExchangeConfig::Okx(None), ExchangeConfig::Kraken),
+        16
+    );
+}
+
+#[test]
+fn test_compare_aliases_both_types() {
+    let names = compare_exchange_configs!(
+        ExchangeConfig::Kraken, ExchangeConfig::Okx(Some(1));
+        (A, _a), (B, _b) => { (type_name::<A>(), type_name::<B>()) }
+    );
+    assert_eq!(
+        names,
+        (
+            type_name::<exchanges::Kraken>(),
+            type_name::<exchanges::Okx>()
+        )
+    );
+
+    // Comparing a value against itself sees the same type on both sides
+    let same = compare_exchange_configs!(ExchangeConfig::Kraken, ExchangeConfig::Kraken;
+        (A, _a), (B, _b) => { type_name::<A>() == type_name::<B>() });
+    assert!(same);
+}
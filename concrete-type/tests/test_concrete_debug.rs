@@ -0,0 +1,33 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(debug)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_debug_includes_concrete_path() {
+    assert_eq!(
+        format!("{:?}", Exchange::Binance),
+        "Binance → crate::exchanges::Binance"
+    );
+    assert_eq!(format!("{:?}", Exchange::Okx), "Okx → exchanges::Okx");
+}
+
+#[test]
+fn test_debug_in_containers() {
+    assert_eq!(
+        format!("{:?}", Some(Exchange::Okx)),
+        "Some(Okx → exchanges::Okx)"
+    );
+    let _ = (exchanges::Binance, exchanges::Okx);
+}
@@ -0,0 +1,66 @@
+use concrete_type::{Concrete, ConcreteConfig, ConcreteDisplay};
+
+mod exchanges {
+    use std::marker::PhantomData;
+
+    pub struct Binance;
+    pub struct BinanceConfig;
+    pub struct KrakenSpotServer;
+    pub struct Kraken<Server> {
+        pub _phantom: PhantomData<Server>,
+    }
+}
+
+#[derive(Concrete, ConcreteDisplay)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Kraken<crate::exchanges::KrakenSpotServer>"]
+    Kraken,
+}
+
+#[derive(ConcreteConfig, ConcreteDisplay)]
+#[concrete(base = "crate::exchanges::{Variant}")]
+enum ExchangeConfig {
+    Binance(exchanges::BinanceConfig),
+    #[concrete = "crate::exchanges::Kraken<crate::exchanges::KrakenSpotServer>"]
+    Kraken,
+}
+
+#[test]
+fn test_display_shows_variant_and_path() {
+    assert_eq!(
+        Exchange::Binance.to_string(),
+        "Binance (crate::exchanges::Binance)"
+    );
+    assert_eq!(
+        Exchange::Kraken.to_string(),
+        "Kraken (crate::exchanges::Kraken<crate::exchanges::KrakenSpotServer>)"
+    );
+}
+
+#[test]
+fn test_display_on_config_enum() {
+    assert_eq!(
+        ExchangeConfig::Binance(exchanges::BinanceConfig).to_string(),
+        "Binance (crate::exchanges::Binance)"
+    );
+    assert_eq!(
+        format!("{}", ExchangeConfig::Kraken),
+        "Kraken (crate::exchanges::Kraken<crate::exchanges::KrakenSpotServer>)"
+    );
+}
+
+#[test]
+fn test_display_matches_dispatched_type() {
+    let exchange = Exchange::Kraken;
+    let name = exchange!(exchange; E => { std::any::type_name::<E>() });
+    assert!(name.ends_with("Kraken<test_concrete_display::exchanges::KrakenSpotServer>"));
+    assert!(exchange.to_string().ends_with("KrakenSpotServer>)"));
+
+    let config = ExchangeConfig::Binance(exchanges::BinanceConfig);
+    let display = config.to_string();
+    let name = exchange_config!(config; (E, _cfg) => { std::any::type_name::<E>() });
+    assert!(name.ends_with("exchanges::Binance"));
+    assert!(display.ends_with("exchanges::Binance)"));
+}
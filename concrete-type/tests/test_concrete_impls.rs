@@ -0,0 +1,104 @@
+// Feature-gated variants use a feature an integration test can't declare for itself
+#![allow(unexpected_cfgs)]
+
+use concrete_type::{Concrete, concrete_impls};
+use std::marker::PhantomData;
+
+pub trait ExchangeApi {
+    const NAME: &'static str;
+}
+
+pub trait Fees {
+    const BASIS_POINTS: u64;
+}
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+
+    impl crate::ExchangeApi for Binance {
+        const NAME: &'static str = "binance";
+    }
+
+    impl crate::ExchangeApi for Okx {
+        const NAME: &'static str = "okx";
+    }
+
+    impl crate::Fees for Binance {
+        const BASIS_POINTS: u64 = 10;
+    }
+
+    impl crate::Fees for Okx {
+        const BASIS_POINTS: u64 = 8;
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    // Shares the impl of `Binance`
+    #[concrete = "crate::exchanges::Binance"]
+    BinanceUs,
+    // Disabled, so it gets no impl
+    #[concrete = "crate::exchanges::Missing"]
+    #[concrete(feature = "missing")]
+    Missing,
+}
+
+pub struct TradingSystem<E, const N: usize> {
+    orders: [u64; N],
+    exchange: PhantomData<E>,
+}
+
+#[concrete_impls(Exchange)]
+impl<E: ExchangeApi, const N: usize> TradingSystem<E, N>
+where
+    E: Fees,
+{
+    fn new(orders: [u64; N]) -> Self {
+        Self {
+            orders,
+            exchange: PhantomData,
+        }
+    }
+
+    fn summary(&self) -> (&'static str, u64) {
+        let notional: u64 = self.orders.iter().sum();
+        (E::NAME, notional * E::BASIS_POINTS / 10_000)
+    }
+}
+
+pub trait Venue {
+    fn venue() -> &'static str;
+}
+
+#[concrete_impls(Exchange)]
+impl<E> Venue for TradingSystem<E, 1> {
+    fn venue() -> &'static str {
+        std::any::type_name::<E>()
+    }
+}
+
+#[test]
+fn test_impls_instantiated_per_concrete_type() {
+    let binance = TradingSystem::<exchanges::Binance, 2>::new([10_000, 20_000]);
+    assert_eq!(binance.summary(), ("binance", 30));
+
+    let okx = TradingSystem::<exchanges::Okx, 1>::new([10_000]);
+    assert_eq!(okx.summary(), ("okx", 8));
+
+    assert_eq!(
+        <TradingSystem<exchanges::Okx, 1> as Venue>::venue(),
+        std::any::type_name::<exchanges::Okx>()
+    );
+
+    let name = exchange!(@variant BinanceUs; E => { TradingSystem::<E, 1>::venue() });
+    assert_eq!(name, std::any::type_name::<exchanges::Binance>());
+    for exchange in [Exchange::Binance, Exchange::Okx, Exchange::BinanceUs] {
+        assert_eq!(exchange.required_feature(), None);
+    }
+    assert_eq!(Exchange::Missing.required_feature(), Some("missing"));
+}
@@ -0,0 +1,66 @@
+use concrete_type::Concrete;
+use std::any::TypeId;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+mod strategies {
+    pub struct Momentum;
+}
+
+// Stands in for a trait defined by a library layered on top of several enums
+mod layered {
+    use std::any::TypeId;
+
+    pub trait ConcreteOf<const V: usize> {
+        type Ty;
+    }
+
+    pub fn concrete_type_id<E: ConcreteOf<V>, const V: usize>() -> TypeId
+    where
+        E::Ty: 'static,
+    {
+        TypeId::of::<E::Ty>()
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(concrete_of)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(concrete_of = "crate::layered::ConcreteOf")]
+enum Strategy {
+    #[concrete = "crate::strategies::Momentum"]
+    Momentum,
+}
+
+#[test]
+fn test_generated_concrete_of() {
+    type Okx = ExchangeConcrete<{ Exchange::Okx.variant_index() }>;
+    assert_eq!(TypeId::of::<Okx>(), TypeId::of::<exchanges::Okx>());
+    assert_eq!(
+        TypeId::of::<<Exchange as ExchangeConcreteOf<0>>::Ty>(),
+        Exchange::Binance.concrete_type_id()
+    );
+    exchange!(Exchange::Okx; E => { let _ = std::any::type_name::<E>(); });
+}
+
+#[test]
+fn test_given_concrete_of() {
+    assert_eq!(
+        layered::concrete_type_id::<Strategy, { Strategy::Momentum.variant_index() }>(),
+        TypeId::of::<strategies::Momentum>()
+    );
+    assert_eq!(
+        TypeId::of::<StrategyConcrete<0>>(),
+        Strategy::Momentum.concrete_type_id()
+    );
+}
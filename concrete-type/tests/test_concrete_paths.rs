@@ -0,0 +1,86 @@
+use concrete_type::{Concrete, ConcreteConfig};
+use std::any::type_name;
+mod markets {
+    use std::marker::PhantomData;
+
+    pub struct SpotMarket;
+    pub struct FuturesMarket;
+    pub struct OptionsMarket;
+
+    pub struct Exchange<Market> {
+        pub _phantom: PhantomData<Market>,
+    }
+
+    pub struct Custom;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(base = "crate::markets::Exchange<crate::markets::{Variant}Market>")]
+enum Market {
+    Spot,
+    Futures,
+    #[concrete = "crate::markets::Custom"]
+    Options,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(base = "crate::markets::{Variant}Market")]
+enum MarketConfig {
+    Spot(u32),
+    Futures,
+}
+
+#[test]
+fn test_base_substitutes_variant_name() {
+    let name = market!(Market::Spot; M => { type_name::<M>() });
+    assert_eq!(name, type_name::<markets::Exchange<markets::SpotMarket>>());
+
+    let name = market!(Market::Futures; M => { type_name::<M>() });
+    assert_eq!(
+        name,
+        type_name::<markets::Exchange<markets::FuturesMarket>>()
+    );
+}
+
+#[test]
+fn test_variant_attribute_overrides_base() {
+    let name = market!(Market::Options; M => { type_name::<M>() });
+    assert_eq!(name, type_name::<markets::Custom>());
+    assert_ne!(name, type_name::<markets::OptionsMarket>());
+}
+
+#[test]
+fn test_base_with_concrete_config() {
+    let config = MarketConfig::Spot(5);
+    let name = market_config!(config; (M, _cfg) => { type_name::<M>() });
+    assert_eq!(name, type_name::<markets::SpotMarket>());
+
+    let name = market_config!(MarketConfig::Futures; (M, _cfg) => { type_name::<M>() });
+    assert_eq!(name, type_name::<markets::FuturesMarket>());
+}
+
+mod venues {
+    pub mod binance_spot {
+        pub struct BinanceSpot;
+    }
+    pub mod okx {
+        pub struct Okx;
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Venue {
+    #[concrete = "crate::venues::{variant}::{Variant}"]
+    BinanceSpot,
+    #[concrete = "crate::venues::{variant}::{Variant}"]
+    Okx,
+}
+
+#[test]
+fn test_placeholders_in_variant_attribute() {
+    let name = venue!(Venue::BinanceSpot; V => { type_name::<V>() });
+    assert_eq!(name, type_name::<venues::binance_spot::BinanceSpot>());
+
+    let name = venue!(Venue::Okx; V => { type_name::<V>() });
+    assert_eq!(name, type_name::<venues::okx::Okx>());
+}
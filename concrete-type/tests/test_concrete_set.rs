@@ -0,0 +1,66 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Debug, Clone, Copy, PartialEq)]
+#[concrete(set)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+#[test]
+fn test_insert_contains_remove() {
+    let mut set = ExchangeSet::new();
+    assert!(set.is_empty());
+
+    assert!(set.insert(Exchange::Okx));
+    assert!(!set.insert(Exchange::Okx));
+    assert!(set.contains(&Exchange::Okx));
+    assert!(!set.contains(&Exchange::Binance));
+    assert_eq!(set.len(), 1);
+
+    assert!(set.remove(&Exchange::Okx));
+    assert!(!set.remove(&Exchange::Okx));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_iter_in_declaration_order() {
+    let set: ExchangeSet = [Exchange::Kraken, Exchange::Binance].into_iter().collect();
+    assert_eq!(
+        set.iter().collect::<Vec<_>>(),
+        vec![Exchange::Binance, Exchange::Kraken]
+    );
+
+    let mut extended = set;
+    extended.extend([Exchange::Okx]);
+    assert_eq!(extended.len(), 3);
+}
+
+#[test]
+fn test_filter_before_dispatch() {
+    let enabled: ExchangeSet = [Exchange::Binance, Exchange::Kraken].into_iter().collect();
+    let names: Vec<_> = [Exchange::Binance, Exchange::Okx, Exchange::Kraken]
+        .into_iter()
+        .filter(|exchange| exchange.in_set(&enabled))
+        .map(|exchange| exchange!(exchange; E => { std::any::type_name::<E>() }))
+        .collect();
+
+    assert_eq!(
+        names,
+        vec![
+            std::any::type_name::<exchanges::Binance>(),
+            std::any::type_name::<exchanges::Kraken>()
+        ]
+    );
+    assert_eq!(ExchangeSet::default(), ExchangeSet::new());
+}
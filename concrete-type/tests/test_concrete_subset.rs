@@ -0,0 +1,63 @@
+use concrete_type::Concrete;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete_subset(
+    name = "SpotExchange",
+    variants = "Binance, Okx",
+    derive(Clone, Copy, Debug, PartialEq)
+)]
+#[concrete_subset(name = "KrakenOnly", variants = "Kraken")]
+enum Exchange {
+    /// The largest venue
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", tag = 2)]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+#[test]
+fn test_subset_dispatches_like_full_enum() {
+    let name = spot_exchange!(SpotExchange::Okx; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Okx>());
+    assert_eq!(
+        SpotExchange::Binance.concrete_type_name(),
+        Exchange::BINANCE_TYPE_NAME
+    );
+    assert_eq!(SpotExchange::VARIANT_COUNT, 2);
+
+    let name = kraken_only!(KrakenOnly::Kraken; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Kraken>());
+}
+
+#[test]
+fn test_subset_conversions() {
+    assert_eq!(
+        SpotExchange::try_from(Exchange::Binance),
+        Ok(SpotExchange::Binance)
+    );
+    assert_eq!(
+        SpotExchange::try_from(Exchange::Kraken),
+        Err(Exchange::Kraken)
+    );
+    assert_eq!(Exchange::from(SpotExchange::Okx), Exchange::Okx);
+    assert!(matches!(
+        KrakenOnly::try_from(Exchange::Kraken),
+        Ok(KrakenOnly::Kraken)
+    ));
+
+    for exchange in [Exchange::Binance, Exchange::Okx, Exchange::Kraken] {
+        let full = exchange!(exchange; E => { type_name::<E>() });
+        if let Ok(spot) = SpotExchange::try_from(exchange) {
+            assert_eq!(spot_exchange!(spot; E => { type_name::<E>() }), full);
+        }
+    }
+}
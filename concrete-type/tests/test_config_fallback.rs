@@ -0,0 +1,110 @@
+use concrete_type::ConcreteConfig;
+
+trait Exchange {
+    type Config;
+    fn fetch_price(config: &Self::Config, symbol: &str) -> Result<u64, String>;
+}
+
+mod exchanges {
+    use super::Exchange;
+
+    pub struct Binance;
+    pub struct Okx;
+
+    pub struct BinanceConfig {
+        pub reachable: bool,
+    }
+
+    pub struct OkxConfig {
+        pub price: u64,
+    }
+
+    impl Exchange for Binance {
+        type Config = BinanceConfig;
+
+        fn fetch_price(config: &BinanceConfig, symbol: &str) -> Result<u64, String> {
+            if config.reachable {
+                Ok(100)
+            } else {
+                Err(format!("binance: {symbol} unreachable"))
+            }
+        }
+    }
+
+    impl Exchange for Okx {
+        type Config = OkxConfig;
+
+        fn fetch_price(config: &OkxConfig, symbol: &str) -> Result<u64, String> {
+            match config.price {
+                0 => Err(format!("okx: no {symbol} quote")),
+                price => Ok(price),
+            }
+        }
+    }
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(fallback)]
+enum ExchangeConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(exchanges::BinanceConfig),
+    #[concrete = "exchanges::Okx"]
+    Okx(exchanges::OkxConfig),
+}
+
+fn binance(reachable: bool) -> ExchangeConfig {
+    ExchangeConfig::Binance(exchanges::BinanceConfig { reachable })
+}
+
+fn okx(price: u64) -> ExchangeConfig {
+    ExchangeConfig::Okx(exchanges::OkxConfig { price })
+}
+
+fn fetch(
+    primary: ExchangeConfig,
+    secondary: ExchangeConfig,
+) -> Result<ExchangeConfigFallback<u64>, ExchangeConfigFallbackError<String>> {
+    fallback_exchange_configs!(primary, secondary; (E, config) => {
+        E::fetch_price(&config, "BTC")
+    })
+}
+
+#[test]
+fn test_primary_succeeds() {
+    let price = fetch(binance(true), okx(99)).unwrap();
+    assert_eq!(price, ExchangeConfigFallback::Primary(100));
+    assert!(!price.is_secondary());
+}
+
+#[test]
+fn test_secondary_after_primary_fails() {
+    let price = fetch(binance(false), okx(99)).unwrap();
+    assert_eq!(price, ExchangeConfigFallback::Secondary(99));
+    assert!(price.is_secondary());
+    assert_eq!(*price.value(), 99);
+    assert_eq!(price.into_value(), 99);
+}
+
+#[test]
+fn test_secondary_not_dispatched_on_success() {
+    let mut dispatched = 0;
+    let result = fallback_exchange_configs!(okx(42), binance(true); (E, config) => {
+        dispatched += 1;
+        E::fetch_price(&config, "ETH")
+    });
+    assert_eq!(result.unwrap(), ExchangeConfigFallback::Primary(42));
+    assert_eq!(dispatched, 1);
+}
+
+#[test]
+fn test_both_fail() {
+    let error = fetch(binance(false), okx(0)).unwrap_err();
+    assert_eq!(error.primary(), "binance: BTC unreachable");
+    assert_eq!(error.secondary(), "okx: no BTC quote");
+    assert_eq!(
+        error.to_string(),
+        "primary failed: binance: BTC unreachable; secondary failed: okx: no BTC quote"
+    );
+    let (primary, secondary) = error.into_errors();
+    assert!(primary.starts_with("binance") && secondary.starts_with("okx"));
+}
@@ -0,0 +1,89 @@
+use concrete_type::ConcreteConfig;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Default)]
+struct Credentials {
+    api_key: String,
+    secret: String,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(unwrap)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(Credentials),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(Box<Credentials>),
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken(Option<Credentials>),
+}
+
+fn credentials(api_key: &str) -> Credentials {
+    Credentials {
+        api_key: api_key.to_string(),
+        secret: String::from("secret"),
+    }
+}
+
+#[test]
+fn test_config_destructured_in_binding() {
+    let config = ExchangeConfig::Binance(credentials("binance-key"));
+    let api_key = exchange_config!(config; (_E, Credentials { api_key, .. }) => { api_key });
+    assert_eq!(api_key, "binance-key");
+
+    // Boxed configs are unboxed before the pattern applies
+    let config = ExchangeConfig::Okx(Box::new(credentials("okx-key")));
+    let (api_key, secret) = exchange_config!(config; (_E, Credentials { api_key, secret }) => {
+        (api_key, secret)
+    });
+    assert_eq!((api_key.as_str(), secret.as_str()), ("okx-key", "secret"));
+
+    // Missing optional configs default before the pattern applies
+    let config = ExchangeConfig::Kraken(None);
+    let api_key = exchange_config!(config; (_E, Credentials { api_key, .. }) => { api_key });
+    assert_eq!(api_key, "");
+
+    let config = ExchangeConfig::Kraken(Some(credentials("kraken-key")));
+    let api_key = exchange_config!(config; (_E, Credentials { api_key, .. }) => {
+        api_key
+    } else {
+        String::from("none")
+    });
+    assert_eq!(api_key, "kraken-key");
+}
+
+#[test]
+fn test_config_ignored_with_wildcard() {
+    let config = ExchangeConfig::Kraken(None);
+    let name = exchange_config!(config; (E, _) => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Kraken>());
+    let _ = (exchanges::Binance, exchanges::Okx);
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(compare)]
+enum MarketConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Spot((u32, u32)),
+    #[concrete = "crate::exchanges::Okx"]
+    Margin((u32, u32)),
+}
+
+#[test]
+fn test_tuple_configs_destructured() {
+    let spread = |config: MarketConfig| market_config!(config; (_M, (bid, ask)) => { ask - bid });
+    assert_eq!(spread(MarketConfig::Spot((100, 102))), 2);
+    assert_eq!(spread(MarketConfig::Margin((100, 105))), 5);
+
+    let total = compare_market_configs!(
+        MarketConfig::Spot((1, 2)), MarketConfig::Margin((3, 4));
+        (_A, (a_bid, a_ask)), (_B, (b_bid, b_ask)) => { a_bid + a_ask + b_bid + b_ask }
+    );
+    assert_eq!(total, 10);
+}
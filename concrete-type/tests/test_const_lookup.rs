@@ -0,0 +1,83 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(from_name)]
+enum Exchange {
+    #[concrete(ty = "exchanges::Binance", tag = 10, alias = "bnb")]
+    Binance,
+    #[concrete(ty = "exchanges::Okx", tag = 20)]
+    Okx,
+    #[concrete = "exchanges::Kraken"]
+    Kraken,
+}
+
+// A static configuration table resolved entirely at compile time
+static ROUTES: [(&str, Exchange); 2] = [
+    ("spot", Exchange::from_tag(10).unwrap()),
+    ("futures", Exchange::from_index(2).unwrap()),
+];
+
+#[test]
+fn test_const_lookups() {
+    assert_eq!(
+        ROUTES.map(|(_, exchange)| exchange),
+        [Exchange::Binance, Exchange::Kraken]
+    );
+
+    for (_, exchange) in ROUTES {
+        let name = exchange!(exchange; E => { std::any::type_name::<E>() });
+        assert!(name.contains("exchanges::"));
+    }
+}
+
+#[test]
+fn test_const_from_name() {
+    const MARGIN: Option<Exchange> = Exchange::from_name("Okx");
+    const ALIASED: Option<Exchange> = Exchange::from_name("bnb");
+    assert_eq!(MARGIN, Some(Exchange::Okx));
+    assert_eq!(ALIASED, Some(Exchange::Binance));
+    assert_eq!(Exchange::from_name("bn"), None);
+}
+
+#[test]
+fn test_tags() {
+    assert_eq!(Exchange::Binance.tag(), Some(10));
+    assert_eq!(Exchange::Kraken.tag(), None);
+    assert_eq!(Exchange::from_tag(20), Some(Exchange::Okx));
+    assert_eq!(Exchange::from_tag(0), None);
+
+    for exchange in [Exchange::Binance, Exchange::Okx] {
+        assert_eq!(exchange.tag().and_then(Exchange::from_tag), Some(exchange));
+    }
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete(ty = "exchanges::Binance", tag = 1)]
+    Binance(String),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_config_tags() {
+    assert_eq!(ExchangeConfig::Binance(String::new()).tag(), Some(1));
+    assert_eq!(ExchangeConfig::Okx.tag(), None);
+
+    for config in [
+        ExchangeConfig::Binance("key".to_string()),
+        ExchangeConfig::Okx,
+    ] {
+        if let ExchangeConfig::Binance(key) = &config {
+            assert_eq!(key, "key");
+        }
+        let name = exchange_config!(config; (E, _cfg) => { std::any::type_name::<E>() });
+        assert!(name.contains("exchanges::"));
+    }
+}
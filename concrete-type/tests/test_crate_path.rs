@@ -0,0 +1,37 @@
+// A real facade would use an absolute path such as `::facade`, which an integration test
+// can't name for itself
+#![allow(clippy::crate_in_macro_def)]
+
+use concrete_type::Concrete;
+use std::any::type_name;
+
+// The defining crate's own types, named by the `#[concrete]` paths
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+// Stands in for a facade crate re-exporting the defining crate's types under its own paths
+mod facade {
+    pub mod exchanges {
+        pub use crate::exchanges::{Binance, Okx};
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(crate_path = "crate::facade")]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_crate_path_replaces_crate_root() {
+    let name = exchange!(Exchange::Binance; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<facade::exchanges::Binance>());
+
+    let name = exchange!(Exchange::Okx; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Okx>());
+}
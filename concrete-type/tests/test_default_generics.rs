@@ -0,0 +1,87 @@
+use concrete_type::Concrete;
+use std::any::type_name;
+use std::marker::PhantomData;
+
+pub struct SystemClock;
+pub struct MockClock;
+
+mod exchanges {
+    use std::marker::PhantomData;
+
+    pub struct Binance<C = crate::SystemClock>(pub PhantomData<C>);
+    pub struct Okx<Q, C = crate::SystemClock>(pub PhantomData<(Q, C)>);
+
+    impl<C> Default for Binance<C> {
+        fn default() -> Self {
+            Binance(PhantomData)
+        }
+    }
+
+    impl<Q, C> Default for Okx<Q, C> {
+        fn default() -> Self {
+            Okx(PhantomData)
+        }
+    }
+}
+
+pub struct Usd;
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx<crate::Usd>"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(partial)]
+enum ClockedExchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+}
+
+#[test]
+fn test_alias_applies_defaulted_parameters() {
+    let name = exchange!(Exchange::Binance; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Binance<SystemClock>>());
+
+    let name = exchange!(Exchange::Okx; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Okx<Usd, SystemClock>>());
+}
+
+#[test]
+fn test_alias_is_constructible_without_spelling_defaults() {
+    let built = exchange!(Exchange::Binance; E => {
+        let instance: E = E::default();
+        std::mem::size_of_val(&instance)
+    });
+    assert_eq!(built, 0);
+}
+
+#[test]
+fn test_methods_see_defaulted_type() {
+    assert_eq!(
+        Exchange::Binance.concrete_type_id(),
+        std::any::TypeId::of::<exchanges::Binance<SystemClock>>()
+    );
+    assert!(
+        Exchange::Okx
+            .try_into_concrete::<exchanges::Okx<Usd, SystemClock>>()
+            .is_ok()
+    );
+    assert_eq!(
+        Exchange::Binance
+            .try_into_concrete::<exchanges::Binance<MockClock>>()
+            .err(),
+        Some(Exchange::Binance)
+    );
+}
+
+#[test]
+fn test_call_site_arguments_override_defaults() {
+    let name = clocked_exchange!(ClockedExchange::Binance; E<MockClock> => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Binance<MockClock>>());
+
+    let _ = exchanges::Okx::<Usd, MockClock>(PhantomData);
+}
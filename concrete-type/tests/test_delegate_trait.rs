@@ -0,0 +1,107 @@
+use concrete_type::{Concrete, concrete_delegate};
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+
+#[concrete_delegate]
+trait ExchangeApi {
+    fn name(&self) -> &'static str;
+
+    fn place_order(&mut self, order: (u64, u64)) -> u64;
+
+    fn into_orders(self) -> u64;
+
+    async fn ping(&self, attempts: u32) -> u32;
+
+    fn describe() -> &'static str
+    where
+        Self: Sized,
+    {
+        "exchange"
+    }
+}
+
+mod exchanges {
+    #[derive(Default)]
+    pub struct Binance {
+        pub orders: u64,
+    }
+
+    #[derive(Default)]
+    pub struct Okx;
+
+    impl crate::ExchangeApi for Binance {
+        fn name(&self) -> &'static str {
+            "binance"
+        }
+
+        fn place_order(&mut self, (price, quantity): (u64, u64)) -> u64 {
+            self.orders += 1;
+            price * quantity
+        }
+
+        fn into_orders(self) -> u64 {
+            self.orders
+        }
+
+        async fn ping(&self, attempts: u32) -> u32 {
+            attempts
+        }
+    }
+
+    impl crate::ExchangeApi for Okx {
+        fn name(&self) -> &'static str {
+            "okx"
+        }
+
+        fn place_order(&mut self, (price, _): (u64, u64)) -> u64 {
+            price
+        }
+
+        fn into_orders(self) -> u64 {
+            0
+        }
+
+        async fn ping(&self, attempts: u32) -> u32 {
+            attempts * 2
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(new_default, delegate_trait = "crate::ExchangeApi")]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+fn ready<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    match future
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+    {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future wasn't ready"),
+    }
+}
+
+#[test]
+fn test_instance_forwards_trait_methods() {
+    let mut binance = Exchange::Binance.new_default();
+    assert_eq!(binance.name(), "binance");
+    assert_eq!(binance.place_order((3, 4)), 12);
+    assert_eq!(binance.place_order((1, 1)), 1);
+    assert_eq!(ready(binance.ping(2)), 2);
+    assert_eq!(binance.into_orders(), 2);
+
+    let mut okx = Exchange::Okx.new_default();
+    assert_eq!(okx.name(), "okx");
+    assert_eq!(okx.place_order((3, 4)), 3);
+    assert_eq!(ready(okx.ping(2)), 4);
+    assert_eq!(okx.into_orders(), 0);
+
+    assert_eq!(<ExchangeInstance as ExchangeApi>::describe(), "exchange");
+}
@@ -0,0 +1,84 @@
+use concrete_type::Concrete;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+struct Timeout;
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("timed out")
+    }
+}
+
+impl Error for Timeout {}
+
+trait Venue {
+    fn price() -> Result<u64, Timeout>;
+}
+
+mod exchanges {
+    use super::{Timeout, Venue};
+
+    pub struct Binance;
+    pub struct Okx;
+
+    impl Venue for Binance {
+        fn price() -> Result<u64, Timeout> {
+            Ok(100)
+        }
+    }
+
+    impl Venue for Okx {
+        fn price() -> Result<u64, Timeout> {
+            Err(Timeout)
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_try_dispatch() {
+    let price = exchange!(@try Exchange::Binance; E => { E::price() });
+    assert_eq!(price.unwrap(), 100);
+
+    let error = exchange!(@try Exchange::Okx; E => { E::price() }).unwrap_err();
+    assert_eq!(error.variant(), "Okx");
+    assert_eq!(error.concrete_type(), "crate::exchanges::Okx");
+    assert_eq!(error.error(), &Timeout);
+    assert_eq!(
+        error.to_string(),
+        "Exchange::Okx (`crate::exchanges::Okx`): timed out"
+    );
+    assert!(error.source().is_some());
+    assert_eq!(error.into_error(), Timeout);
+}
+
+#[test]
+fn test_wrap_err() {
+    let error = Exchange::Binance.wrap_err("rate limited");
+    assert_eq!(error.variant(), "Binance");
+    assert_eq!(
+        error.to_string(),
+        "Exchange::Binance (`crate::exchanges::Binance`): rate limited"
+    );
+}
+
+// The question mark operator converts the wrapped error at the call site
+fn quote(exchange: Exchange) -> Result<u64, ExchangeDispatchError<Timeout>> {
+    let price = exchange!(@try exchange; E => { E::price() })?;
+    Ok(price * 2)
+}
+
+#[test]
+fn test_try_with_question_mark() {
+    assert_eq!(quote(Exchange::Binance).unwrap(), 200);
+    assert_eq!(quote(Exchange::Okx).unwrap_err().variant(), "Okx");
+}
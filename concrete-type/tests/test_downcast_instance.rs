@@ -0,0 +1,93 @@
+use concrete_type::{Concrete, ConcreteConfig};
+use std::any::Any;
+
+mod exchanges {
+    #[derive(Debug, PartialEq)]
+    pub struct Binance(pub u32);
+    #[derive(Debug, PartialEq)]
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(u32),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_downcast_instance_of_variant_type() {
+    let value: Box<dyn Any> = Box::new(exchanges::Binance(7));
+    let binance = Exchange::Binance
+        .downcast_instance::<exchanges::Binance>(value)
+        .unwrap();
+    assert_eq!(*binance, exchanges::Binance(7));
+}
+
+#[test]
+fn test_downcast_instance_to_other_variant_type() {
+    let value: Box<dyn Any> = Box::new(exchanges::Okx);
+    let error = Exchange::Binance
+        .downcast_instance::<exchanges::Okx>(value)
+        .unwrap_err();
+    assert_eq!(error.variant(), "Binance");
+    assert_eq!(error.expected_type(), "exchanges::Binance");
+    assert_eq!(
+        error.requested_type(),
+        Some(std::any::type_name::<exchanges::Okx>())
+    );
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "cannot downcast to `{}`, variant `Binance` maps to `exchanges::Binance`",
+            std::any::type_name::<exchanges::Okx>()
+        )
+    );
+
+    // The value is handed back untouched
+    assert!(error.into_value().downcast::<exchanges::Okx>().is_ok());
+}
+
+#[test]
+fn test_downcast_instance_of_wrong_value() {
+    let value: Box<dyn Any> = Box::new(exchanges::Okx);
+    let error = Exchange::Binance
+        .downcast_instance::<exchanges::Binance>(value)
+        .unwrap_err();
+    assert_eq!(error.requested_type(), None);
+    assert_eq!(
+        error.to_string(),
+        "value is not a `exchanges::Binance`, the concrete type of variant `Binance`"
+    );
+    let _ = Exchange::Okx;
+}
+
+#[test]
+fn test_config_downcast_instance() {
+    let config = ExchangeConfig::Binance(3);
+    let ExchangeConfig::Binance(id) = config else {
+        unreachable!()
+    };
+    let value: Box<dyn Any> = Box::new(exchanges::Binance(id));
+    assert!(
+        config
+            .downcast_instance::<exchanges::Binance>(value)
+            .is_ok()
+    );
+
+    let value: Box<dyn Any> = Box::new(exchanges::Binance(3));
+    let error = ExchangeConfig::Okx
+        .downcast_instance::<exchanges::Binance>(value)
+        .unwrap_err();
+    assert_eq!(error.variant(), "Okx");
+    assert_eq!(error.expected_type(), "exchanges::Okx");
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "either")]
+
+use concrete_type::ConcreteConfig;
+use either::Either;
+
+trait Backend {
+    fn connect(endpoint: &str) -> String;
+}
+
+mod backends {
+    use super::Backend;
+
+    pub struct Primary;
+    pub struct Fallback;
+
+    impl Backend for Primary {
+        fn connect(endpoint: &str) -> String {
+            format!("primary:{endpoint}")
+        }
+    }
+
+    impl Backend for Fallback {
+        fn connect(endpoint: &str) -> String {
+            format!("fallback:{endpoint}")
+        }
+    }
+}
+
+#[derive(ConcreteConfig, Debug, PartialEq)]
+#[concrete(either)]
+enum BackendConfig {
+    #[concrete = "backends::Primary"]
+    Primary(String),
+    #[concrete = "backends::Fallback"]
+    Fallback,
+}
+
+#[test]
+fn test_as_either() {
+    let mut primary = BackendConfig::Primary(String::from("db-1"));
+    assert_eq!(primary.as_either(), Either::Left(&String::from("db-1")));
+    assert_eq!(BackendConfig::Fallback.as_either(), Either::Right(&()));
+
+    if let Either::Left(endpoint) = primary.as_either_mut() {
+        endpoint.push_str(".internal");
+    }
+    assert_eq!(
+        primary.into_either(),
+        Either::Left(String::from("db-1.internal"))
+    );
+}
+
+#[test]
+fn test_either_dispatch() {
+    let connect = |config: BackendConfig| {
+        config.either(
+            |endpoint| backends::Primary::connect(&endpoint),
+            |()| backends::Fallback::connect("localhost"),
+        )
+    };
+    assert_eq!(
+        connect(BackendConfig::Primary(String::from("db-1"))),
+        "primary:db-1"
+    );
+    assert_eq!(connect(BackendConfig::Fallback), "fallback:localhost");
+}
+
+#[test]
+fn test_from_either() {
+    assert_eq!(
+        BackendConfig::from(Either::Left(String::from("db-1"))),
+        BackendConfig::Primary(String::from("db-1"))
+    );
+    assert_eq!(
+        BackendConfig::from(Either::Right(())),
+        BackendConfig::Fallback
+    );
+}
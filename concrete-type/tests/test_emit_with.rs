@@ -0,0 +1,104 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+/// Glue generated per backend by the hooks below
+trait Backend {
+    const VARIANT: &'static str;
+    type Config;
+}
+
+trait Listed {
+    fn listed() -> &'static str;
+}
+
+mod glue {
+    macro_rules! impl_backend {
+        ($variant:ident, $concrete:path, $config:ty) => {
+            impl $crate::Backend for $concrete {
+                const VARIANT: &'static str = stringify!($variant);
+                type Config = $config;
+            }
+        };
+    }
+
+    macro_rules! impl_listed {
+        ($variant:ident, $concrete:path, $config:ty) => {
+            impl $crate::Listed for $concrete {
+                fn listed() -> &'static str {
+                    concat!(stringify!($variant), ": ", stringify!($config))
+                }
+            }
+        };
+    }
+
+    pub(crate) use {impl_backend, impl_listed};
+}
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+
+    pub struct BinanceConfig {
+        pub region: String,
+    }
+}
+
+mod venues {
+    pub struct Spot;
+    pub struct Margin;
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(emit_with = "glue::impl_backend")]
+enum ExchangeConfig {
+    #[concrete(ty = "exchanges::Binance", unwrap)]
+    Binance(Box<exchanges::BinanceConfig>),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(emit_with = "glue::impl_listed")]
+enum Venue {
+    #[concrete_all = "venues::Spot, venues::Margin"]
+    Binance,
+    #[concrete = "exchanges::Kraken"]
+    Kraken,
+}
+
+fn region(config: &<exchanges::Binance as Backend>::Config) -> &str {
+    &config.region
+}
+
+/// Only compiles if the config bound in dispatch blocks is the hook's config type
+fn variant_of<B: Backend>(_config: B::Config) -> &'static str {
+    B::VARIANT
+}
+
+#[test]
+fn test_config_hooks() {
+    assert_eq!(<exchanges::Binance as Backend>::VARIANT, "Binance");
+    assert_eq!(<exchanges::Okx as Backend>::VARIANT, "Okx");
+
+    // The config type is the one bound in dispatch blocks, unboxed here
+    let config = exchanges::BinanceConfig {
+        region: String::from("eu"),
+    };
+    assert_eq!(region(&config), "eu");
+    let _: <exchanges::Okx as Backend>::Config = ();
+
+    let config = ExchangeConfig::Binance(Box::new(config));
+    let variant = exchange_config!(config; (E, config) => { variant_of::<E>(config) });
+    assert_eq!(variant, "Binance");
+    let variant = exchange_config!(ExchangeConfig::Okx; (E, config) => { variant_of::<E>(config) });
+    assert_eq!(variant, "Okx");
+}
+
+#[test]
+fn test_kind_hooks() {
+    // Every type of a cluster is handed to the hook
+    assert_eq!(venues::Spot::listed(), "Binance: ()");
+    assert_eq!(venues::Margin::listed(), "Binance: ()");
+    assert_eq!(exchanges::Kraken::listed(), "Kraken: ()");
+    let _ = [Venue::Binance, Venue::Kraken];
+}
@@ -0,0 +1,72 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+// Declaration order differs from tag order, the encoding follows the tags
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(encode)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Kraken", tag = 7)]
+    Kraken,
+    #[concrete(ty = "crate::exchanges::Binance", tag = 1)]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", tag = 2)]
+    Okx,
+}
+
+#[test]
+fn test_encode_decode() {
+    const KRAKEN: u16 = Exchange::Kraken.encode();
+    assert_eq!(KRAKEN, 7);
+    assert_eq!(Exchange::Binance.encode(), 1);
+
+    for exchange in [Exchange::Kraken, Exchange::Binance, Exchange::Okx] {
+        assert_eq!(Exchange::decode(exchange.encode()), Some(exchange));
+        exchange!(exchange; E => { let _ = std::any::type_name::<E>(); });
+    }
+    assert_eq!(Exchange::decode(0), None);
+    assert_eq!(Exchange::decode(3), None);
+}
+
+#[cfg(feature = "serde")]
+mod serde_encoding {
+    use super::exchanges;
+    use concrete_type::Concrete;
+
+    #[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+    #[concrete(encode = "serde")]
+    enum Venue {
+        #[concrete(ty = "exchanges::Binance", tag = 300)]
+        Binance,
+        #[concrete(ty = "exchanges::Okx", tag = 2)]
+        Okx,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Fill {
+        venue: Venue,
+        quantity: u32,
+    }
+
+    #[test]
+    fn test_serde_encoding() {
+        let fill = Fill {
+            venue: Venue::Binance,
+            quantity: 5,
+        };
+        let bytes = bincode::serialize(&fill).unwrap();
+        // The tag 300 is written as a little-endian u16
+        assert_eq!(bytes, [0x2c, 0x01, 5, 0, 0, 0]);
+        assert_eq!(bincode::deserialize::<Fill>(&bytes).unwrap(), fill);
+
+        assert_eq!(serde_json::to_string(&Venue::Okx).unwrap(), "2");
+        assert_eq!(serde_json::from_str::<Venue>("2").unwrap(), Venue::Okx);
+        let error = serde_json::from_str::<Venue>("7").unwrap_err().to_string();
+        assert!(error.contains("the tag of a `Venue` variant"), "{}", error);
+        venue!(Venue::Okx; V => { let _ = std::any::type_name::<V>(); });
+    }
+}
@@ -0,0 +1,126 @@
+use concrete_type::ConcreteConfig;
+use std::any::Any;
+
+/// Configs handed over by a plugin loader, erased behind a trait object
+trait PluginConfig: Any {
+    fn plugin(&self) -> &'static str;
+}
+
+trait Exchange {
+    type Config;
+    fn endpoint(config: &Self::Config) -> String;
+}
+
+mod exchanges {
+    use super::{Exchange, PluginConfig};
+
+    pub struct Binance;
+    pub struct Okx;
+
+    #[derive(Debug, PartialEq)]
+    pub struct BinanceConfig {
+        pub region: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct OkxConfig {
+        pub account: u32,
+    }
+
+    impl PluginConfig for BinanceConfig {
+        fn plugin(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    impl PluginConfig for OkxConfig {
+        fn plugin(&self) -> &'static str {
+            "okx"
+        }
+    }
+
+    impl Exchange for Binance {
+        type Config = BinanceConfig;
+
+        fn endpoint(config: &BinanceConfig) -> String {
+            format!("binance.{}", config.region)
+        }
+    }
+
+    impl Exchange for Okx {
+        type Config = OkxConfig;
+
+        fn endpoint(config: &OkxConfig) -> String {
+            format!("okx/{}", config.account)
+        }
+    }
+}
+
+use exchanges::{BinanceConfig, OkxConfig};
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete(ty = "exchanges::Binance", config = "exchanges::BinanceConfig")]
+    Binance(Box<dyn PluginConfig>),
+    #[concrete = "exchanges::Okx"]
+    Okx(OkxConfig),
+}
+
+fn binance(region: &str) -> ExchangeConfig {
+    ExchangeConfig::Binance(Box::new(BinanceConfig {
+        region: region.to_string(),
+    }))
+}
+
+#[test]
+fn test_erased_config_is_downcast() {
+    let endpoint = exchange_config!(binance("eu"); (E, config) => { E::endpoint(&config) });
+    assert_eq!(endpoint, "binance.eu");
+
+    let endpoint = exchange_config!(ExchangeConfig::Okx(OkxConfig { account: 7 }); (E, config) => {
+        E::endpoint(&config)
+    });
+    assert_eq!(endpoint, "okx/7");
+}
+
+#[test]
+fn test_erased_config_through_any() {
+    let config = binance("us");
+    #[cfg(feature = "config-any")]
+    assert_eq!(
+        config.config().downcast_ref::<BinanceConfig>(),
+        Some(&BinanceConfig {
+            region: String::from("us")
+        })
+    );
+    if let ExchangeConfig::Binance(erased) = &config {
+        assert_eq!(erased.plugin(), "binance");
+    }
+}
+
+#[test]
+fn test_checked_dispatch() {
+    let endpoint =
+        exchange_config!(@checked binance("ap"); (E, config) => { E::endpoint(&config) });
+    assert_eq!(endpoint, Ok(String::from("binance.ap")));
+
+    // A loader handing over the wrong config is reported instead of panicking
+    let wrong = ExchangeConfig::Binance(Box::new(OkxConfig { account: 1 }));
+    let error =
+        exchange_config!(@checked wrong; (E, config) => { E::endpoint(&config) }).unwrap_err();
+    assert_eq!(error.variant(), "Binance");
+    assert_eq!(error.expected(), "exchanges::BinanceConfig");
+    assert_eq!(
+        error.to_string(),
+        "`ExchangeConfig::Binance` holds a config that isn't a `exchanges::BinanceConfig`"
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "`ExchangeConfig::Binance` holds a config that isn't a `exchanges::BinanceConfig`"
+)]
+fn test_mismatched_config_panics() {
+    let wrong = ExchangeConfig::Binance(Box::new(OkxConfig { account: 1 }));
+    exchange_config!(wrong; (E, config) => { E::endpoint(&config) });
+}
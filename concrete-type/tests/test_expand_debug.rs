@@ -0,0 +1,53 @@
+#![cfg(feature = "expand-debug")]
+
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+// `BinanceUs` shares its concrete type with `Binance`, which gets it its own arm to trace
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Binance"]
+    BinanceUs,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(u32),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_traced_dispatch() {
+    let names: Vec<_> = [Exchange::Binance, Exchange::BinanceUs, Exchange::Okx]
+        .into_iter()
+        .map(|exchange| exchange!(exchange; E => { std::any::type_name::<E>() }))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            std::any::type_name::<exchanges::Binance>(),
+            std::any::type_name::<exchanges::Binance>(),
+            std::any::type_name::<exchanges::Okx>(),
+        ]
+    );
+
+    let configs: Vec<_> = [ExchangeConfig::Binance(3), ExchangeConfig::Okx]
+        .into_iter()
+        .map(|config| {
+            exchange_config!(config; (E, cfg) => {
+                format!("{} {:?}", std::any::type_name::<E>().rsplit("::").next().unwrap(), cfg)
+            })
+        })
+        .collect();
+    assert_eq!(configs, ["Binance 3", "Okx ()"]);
+}
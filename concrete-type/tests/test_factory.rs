@@ -0,0 +1,96 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub trait ExchangeApi {
+        type Config
+        where
+            Self: Sized;
+
+        fn new(config: Self::Config) -> Self
+        where
+            Self: Sized;
+
+        fn name(&self) -> String;
+    }
+
+    pub struct Binance {
+        pub api_key: String,
+    }
+
+    pub struct BinanceConfig {
+        pub api_key: String,
+    }
+
+    impl ExchangeApi for Binance {
+        type Config = BinanceConfig;
+
+        fn new(config: Self::Config) -> Self {
+            Self {
+                api_key: config.api_key,
+            }
+        }
+
+        fn name(&self) -> String {
+            format!("binance:{}", self.api_key)
+        }
+    }
+
+    pub struct Okx;
+
+    impl ExchangeApi for Okx {
+        type Config = ();
+
+        fn new(_: Self::Config) -> Self {
+            Self
+        }
+
+        fn name(&self) -> String {
+            "okx".to_string()
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(factory = "crate::exchanges::ExchangeApi")]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_factory_creates_concrete_type() {
+    let config = exchanges::BinanceConfig {
+        api_key: "key".to_string(),
+    };
+    let api = Exchange::Binance.create(Box::new(config)).unwrap();
+    assert_eq!(api.name(), "binance:key");
+
+    let api = Exchange::Okx.create(Box::new(())).unwrap();
+    assert_eq!(api.name(), "okx");
+}
+
+#[test]
+fn test_factory_rejects_mismatched_config() {
+    let error = match Exchange::Binance.create(Box::new(())) {
+        Ok(_) => panic!("expected a config mismatch"),
+        Err(error) => error,
+    };
+    assert_eq!(error.variant(), "Binance");
+    assert!(error.expected_config().ends_with("BinanceConfig"));
+    assert!(
+        error
+            .to_string()
+            .starts_with("invalid config for variant `Binance`")
+    );
+}
+
+#[test]
+fn test_factory_through_trait_object() {
+    let factory: &dyn ExchangeFactory = &Exchange::Okx;
+    assert_eq!(factory.create(Box::new(())).unwrap().name(), "okx");
+
+    let name = exchange!(Exchange::Okx; E => { std::any::type_name::<E>() });
+    assert!(name.ends_with("Okx"));
+}
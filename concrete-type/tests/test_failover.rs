@@ -0,0 +1,79 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub trait PriceSource {
+        fn price(symbol: &str) -> Result<u64, String>;
+    }
+
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+
+    impl PriceSource for Binance {
+        fn price(_symbol: &str) -> Result<u64, String> {
+            Err("rate limited".to_string())
+        }
+    }
+
+    impl PriceSource for Okx {
+        fn price(symbol: &str) -> Result<u64, String> {
+            match symbol {
+                "BTC" => Ok(100),
+                _ => Err(format!("unknown symbol {}", symbol)),
+            }
+        }
+    }
+
+    impl PriceSource for Kraken {
+        fn price(_symbol: &str) -> Result<u64, String> {
+            Ok(101)
+        }
+    }
+}
+
+use exchanges::PriceSource;
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq, Eq)]
+#[concrete(failover)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", priority = 0)]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", priority = 1)]
+    Okx,
+    #[concrete(ty = "crate::exchanges::Kraken", priority = 2)]
+    Kraken,
+}
+
+#[test]
+fn test_failover_returns_first_success() {
+    let symbol = "BTC";
+    let price = exchange!(@failover Exchange::iter_by_priority(); E => { E::price(symbol) });
+    assert_eq!(price.ok(), Some(100));
+
+    let price = exchange!(@failover [Exchange::Kraken, Exchange::Okx]; E => { E::price(symbol) });
+    assert_eq!(price.ok(), Some(101));
+}
+
+#[test]
+fn test_failover_aggregates_errors() {
+    let error = exchange!(@failover [Exchange::Binance, Exchange::Okx]; E => { E::price("ETH") })
+        .unwrap_err();
+    assert_eq!(
+        error.errors(),
+        [
+            (Exchange::Binance, "rate limited".to_string()),
+            (Exchange::Okx, "unknown symbol ETH".to_string()),
+        ]
+    );
+    assert_eq!(
+        error.to_string(),
+        "every `Exchange` variant failed: Binance: rate limited; Okx: unknown symbol ETH"
+    );
+    assert_eq!(
+        format!("{:?}", error),
+        "{\"Binance\": \"rate limited\", \"Okx\": \"unknown symbol ETH\"}"
+    );
+
+    let error = exchange!(@failover []; E => { E::price("BTC") }).unwrap_err();
+    assert!(error.into_errors().is_empty());
+}
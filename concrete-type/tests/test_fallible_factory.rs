@@ -0,0 +1,147 @@
+use concrete_type::Concrete;
+use std::error::Error;
+
+mod exchanges {
+    pub trait ExchangeApi {
+        type Config
+        where
+            Self: Sized;
+
+        fn new(config: Self::Config) -> Self
+        where
+            Self: Sized;
+
+        fn name(&self) -> String;
+    }
+
+    pub struct Binance {
+        pub api_key: String,
+    }
+
+    #[derive(Debug)]
+    pub struct InvalidKey;
+
+    impl std::fmt::Display for InvalidKey {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("api key must not be empty")
+        }
+    }
+
+    impl std::error::Error for InvalidKey {}
+
+    impl Binance {
+        pub fn try_new(api_key: String) -> Result<Self, InvalidKey> {
+            if api_key.is_empty() {
+                Err(InvalidKey)
+            } else {
+                Ok(Self { api_key })
+            }
+        }
+    }
+
+    impl ExchangeApi for Binance {
+        type Config = String;
+
+        fn new(api_key: Self::Config) -> Self {
+            Self { api_key }
+        }
+
+        fn name(&self) -> String {
+            format!("binance:{}", self.api_key)
+        }
+    }
+
+    pub struct Okx;
+
+    impl Okx {
+        pub fn connect(url: &'static str) -> Result<Self, String> {
+            if url.starts_with("wss://") {
+                Ok(Self)
+            } else {
+                Err(format!("`{}` is not a websocket url", url))
+            }
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        type Config = &'static str;
+
+        fn new(_: Self::Config) -> Self {
+            Self
+        }
+
+        fn name(&self) -> String {
+            "okx".to_string()
+        }
+    }
+
+    pub struct Kraken;
+
+    impl ExchangeApi for Kraken {
+        type Config = ();
+
+        fn new(_: Self::Config) -> Self {
+            Self
+        }
+
+        fn name(&self) -> String {
+            "kraken".to_string()
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(factory = "crate::exchanges::ExchangeApi")]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", constructor = "try_new")]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", constructor = "connect")]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+#[test]
+fn test_fallible_constructor_succeeds() {
+    let api = Exchange::Binance
+        .create(Box::new(String::from("key")))
+        .unwrap();
+    assert_eq!(api.name(), "binance:key");
+
+    let api = Exchange::Okx.create(Box::new("wss://okx")).unwrap();
+    assert_eq!(api.name(), "okx");
+
+    // Variants without a constructor still go through the trait's `new`
+    let api = Exchange::Kraken.create(Box::new(())).unwrap();
+    assert_eq!(api.name(), "kraken");
+}
+
+#[test]
+fn test_fallible_constructor_error_names_variant() {
+    let Err(error) = Exchange::Binance.create(Box::new(String::new())) else {
+        panic!("expected the constructor to fail");
+    };
+    assert_eq!(error.variant(), "Binance");
+    assert_eq!(
+        error.to_string(),
+        "failed to construct variant `Binance`: api key must not be empty"
+    );
+    assert!(error.source().unwrap().is::<exchanges::InvalidKey>());
+
+    let Err(error) = Exchange::Okx.create(Box::new("https://okx")) else {
+        panic!("expected the constructor to fail");
+    };
+    assert_eq!(
+        error.to_string(),
+        "failed to construct variant `Okx`: `https://okx` is not a websocket url"
+    );
+}
+
+#[test]
+fn test_fallible_factory_wraps_config_mismatch() {
+    let Err(error) = Exchange::Okx.create(Box::new(())) else {
+        panic!("expected a config mismatch");
+    };
+    assert!(matches!(error, ExchangeConstructError::Create(_)));
+    assert_eq!(error.variant(), "Okx");
+}
@@ -0,0 +1,74 @@
+use concrete_type::Concrete;
+use std::marker::PhantomData;
+
+mod exchanges {
+    pub trait Exchange {
+        fn name() -> &'static str;
+    }
+
+    pub struct Binance;
+    pub struct Okx;
+
+    impl Exchange for Binance {
+        fn name() -> &'static str {
+            "binance"
+        }
+    }
+
+    impl Exchange for Okx {
+        fn name() -> &'static str {
+            "okx"
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(family)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+// A system fixed to one backend at the type level, still able to report it at runtime
+struct TradingSystem<K: ExchangeFamily> {
+    kind: PhantomData<K>,
+}
+
+impl<K: ExchangeFamily> TradingSystem<K>
+where
+    K::Concrete: exchanges::Exchange,
+{
+    const EXCHANGE: Exchange = K::KIND;
+
+    fn name(&self) -> &'static str {
+        <K::Concrete as exchanges::Exchange>::name()
+    }
+}
+
+#[test]
+fn test_family_markers() {
+    let system = TradingSystem::<BinanceKind> { kind: PhantomData };
+    assert_eq!(system.name(), "binance");
+    assert_eq!(TradingSystem::<BinanceKind>::EXCHANGE, Exchange::Binance);
+    assert_eq!(TradingSystem::<OkxKind>::EXCHANGE, Exchange::Okx);
+
+    assert_eq!(Exchange::from(OkxKind), Exchange::Okx);
+    let okx = TradingSystem::<OkxKind> { kind: PhantomData };
+    assert_eq!(okx.name(), "okx");
+    assert_eq!(
+        exchange!(OkxKind.into(); E => { <E as exchanges::Exchange>::name() }),
+        "okx"
+    );
+}
+
+#[test]
+fn test_marker_try_from_enum() {
+    assert_eq!(BinanceKind::try_from(Exchange::Binance), Ok(BinanceKind));
+    assert_eq!(OkxKind::try_from(Exchange::Binance), Err(Exchange::Binance));
+
+    // Round trip from the type level to the value level and back
+    let exchange = Exchange::from(OkxKind);
+    assert_eq!(OkxKind::try_from(exchange), Ok(OkxKind));
+}
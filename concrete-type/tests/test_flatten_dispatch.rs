@@ -0,0 +1,89 @@
+use concrete_type::Concrete;
+
+mod venues {
+    pub struct Binance;
+
+    pub mod okx {
+        pub struct Spot;
+        pub struct Margin;
+    }
+}
+
+trait Venue {
+    const NAME: &'static str;
+}
+
+impl Venue for venues::Binance {
+    const NAME: &'static str = "binance";
+}
+
+impl Venue for venues::okx::Spot {
+    const NAME: &'static str = "spot";
+}
+
+impl Venue for venues::okx::Margin {
+    const NAME: &'static str = "margin";
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum OkxAccount {
+    #[concrete = "venues::okx::Spot"]
+    Spot,
+    #[concrete = "venues::okx::Spot"]
+    Unified,
+    #[concrete = "venues::okx::Margin"]
+    Margin,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum BybitAccount {
+    #[concrete = "venues::okx::Margin"]
+    Margin,
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Wallet {
+    #[concrete(nested)]
+    Okx(OkxAccount),
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(flatten)]
+enum Exchange {
+    #[concrete = "venues::Binance"]
+    Binance,
+    #[concrete(nested)]
+    Okx(OkxAccount),
+    #[concrete(nested)]
+    Bybit(BybitAccount),
+    #[concrete(nested)]
+    Wallet(Wallet),
+}
+
+fn venue_name(exchange: Exchange) -> &'static str {
+    exchange!(exchange; V => { V::NAME })
+}
+
+#[test]
+fn test_flattened_dispatch_covers_cross_product() {
+    assert_eq!(venue_name(Exchange::Binance), "binance");
+    assert_eq!(venue_name(Exchange::Okx(OkxAccount::Spot)), "spot");
+    assert_eq!(venue_name(Exchange::Okx(OkxAccount::Unified)), "spot");
+    assert_eq!(venue_name(Exchange::Okx(OkxAccount::Margin)), "margin");
+    assert_eq!(venue_name(Exchange::Bybit(BybitAccount::Margin)), "margin");
+}
+
+#[test]
+fn test_flattened_dispatch_keeps_deeper_nesting() {
+    let exchange = Exchange::Wallet(Wallet::Okx(OkxAccount::Margin));
+    assert_eq!(exchange!(&exchange; V => { V::NAME }), "margin");
+}
+
+#[test]
+fn test_flattened_guarded_dispatch() {
+    let exchange = Exchange::Okx(OkxAccount::Unified);
+    assert_eq!(
+        exchange!(exchange; V => { V::NAME } where |value| matches!(value, Exchange::Okx(_))),
+        Some("spot")
+    );
+}
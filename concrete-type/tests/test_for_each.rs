@@ -0,0 +1,80 @@
+// `dex` stands in for a backend feature that is declared but disabled
+#![allow(unexpected_cfgs)]
+
+use concrete_type::Concrete;
+use std::any::type_name;
+
+trait Backend {
+    const NAME: &'static str;
+}
+
+mod exchanges {
+    use super::Backend;
+
+    pub struct Binance;
+    pub struct Okx;
+    #[cfg(feature = "dex")]
+    pub struct Uniswap;
+
+    impl Backend for Binance {
+        const NAME: &'static str = "binance";
+    }
+
+    impl Backend for Okx {
+        const NAME: &'static str = "okx";
+    }
+
+    #[cfg(feature = "dex")]
+    impl Backend for Uniswap {
+        const NAME: &'static str = "uniswap";
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(for_each)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete(ty = "crate::exchanges::Uniswap", feature = "dex")]
+    Uniswap,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(for_each)]
+enum Venue {
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete(nested)]
+    Cex(Exchange),
+}
+
+#[test]
+fn test_for_each_variant() {
+    let mut registered = Vec::new();
+    for_each_exchange!(E => { registered.push(E::NAME); });
+    // The disabled `dex` variant is skipped
+    assert_eq!(registered, ["binance", "okx"]);
+}
+
+#[test]
+fn test_for_each_nested() {
+    let mut registered = Vec::new();
+    for_each_venue!(V => { registered.push(type_name::<V>()); });
+    assert_eq!(
+        registered,
+        [
+            type_name::<exchanges::Okx>(),
+            type_name::<exchanges::Binance>(),
+            type_name::<exchanges::Okx>(),
+        ]
+    );
+
+    // Every variant is constructed for dead code analysis
+    for exchange in [Exchange::Binance, Exchange::Okx, Exchange::Uniswap] {
+        let venue = Venue::Cex(exchange);
+        venue!(venue; V => { let _ = type_name::<V>(); });
+    }
+    venue!(Venue::Okx; V => { let _ = type_name::<V>(); });
+}
@@ -0,0 +1,162 @@
+use concrete_type::ConcreteConfig;
+use std::marker::PhantomData;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct BinanceConfig {
+    api_key: String,
+}
+
+/// Config that is neither `Clone` nor `Debug`
+struct OkxConfig {
+    _socket: PhantomData<*const ()>,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(derive(Clone, Debug))]
+enum ExchangeConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(BinanceConfig),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(derive(Clone, Debug))]
+enum MixedConfig {
+    #[concrete = "exchanges::Binance"]
+    Binance(BinanceConfig),
+    #[concrete = "exchanges::Okx"]
+    Okx(OkxConfig),
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(derive(Clone))]
+enum GenericConfig<C> {
+    #[concrete = "exchanges::Binance"]
+    Binance(C),
+}
+
+/// Tells whether `T` implements `Clone`, falling back to the trait method when it doesn't
+struct Probe<T>(PhantomData<T>);
+
+impl<T: Clone> Probe<T> {
+    fn is_clone(&self) -> bool {
+        true
+    }
+}
+
+trait NotClone {
+    fn is_clone(&self) -> bool {
+        false
+    }
+}
+
+impl<T> NotClone for Probe<T> {}
+
+#[test]
+fn test_derives_forwarded_when_configs_implement_them() {
+    let config = ExchangeConfig::Binance(BinanceConfig {
+        api_key: String::from("key"),
+    });
+    let cloned = config.clone();
+    assert_eq!(
+        format!("{:?}", cloned),
+        r#"Binance(BinanceConfig { api_key: "key" })"#
+    );
+    assert_eq!(format!("{:?}", ExchangeConfig::Okx), "Okx");
+
+    if let ExchangeConfig::Binance(cloned) = cloned {
+        assert_eq!(cloned.api_key, "key");
+    }
+}
+
+#[test]
+fn test_derives_skipped_when_a_config_does_not_implement_them() {
+    assert!(Probe::<ExchangeConfig>(PhantomData).is_clone());
+    assert!(!Probe::<MixedConfig>(PhantomData).is_clone());
+
+    let _ = MixedConfig::Binance(BinanceConfig {
+        api_key: String::new(),
+    });
+    let _ = MixedConfig::Okx(OkxConfig {
+        _socket: PhantomData,
+    });
+    let _ = (exchanges::Binance, exchanges::Okx);
+}
+
+#[test]
+fn test_derives_on_generic_config() {
+    assert!(Probe::<GenericConfig<u32>>(PhantomData).is_clone());
+    assert!(!Probe::<GenericConfig<OkxConfig>>(PhantomData).is_clone());
+
+    let GenericConfig::Binance(value) = GenericConfig::Binance(7u32).clone();
+    assert_eq!(value, 7);
+}
+
+/// API credentials, deliberately without a `Debug` implementation
+struct Credentials {
+    _api_key: String,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(derive(Debug))]
+enum SecretConfig {
+    #[concrete(ty = "exchanges::Binance", secret)]
+    Binance(Credentials),
+    #[concrete = "exchanges::Okx"]
+    Okx(BinanceConfig),
+}
+
+#[test]
+fn test_debug_redacts_secret_configs() {
+    let config = SecretConfig::Binance(Credentials {
+        _api_key: String::from("hunter2"),
+    });
+    assert_eq!(format!("{:?}", config), "Binance(<redacted>)");
+    // Only the formatting is redacted, the config itself is untouched
+    if let SecretConfig::Binance(credentials) = &config {
+        assert_eq!(credentials._api_key, "hunter2");
+    }
+
+    let config = SecretConfig::Okx(BinanceConfig {
+        api_key: String::from("public"),
+    });
+    assert_eq!(
+        format!("{:?}", config),
+        r#"Okx(BinanceConfig { api_key: "public" })"#
+    );
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(derive(Clone, Debug))]
+enum UncloneableSecretConfig {
+    #[concrete(ty = "exchanges::Binance", secret)]
+    Binance(Credentials),
+    #[concrete = "exchanges::Okx"]
+    Okx(BinanceConfig),
+}
+
+#[test]
+fn test_clone_still_requires_secret_configs() {
+    // `Clone` copies secret configs, so it's skipped, while the redacting `Debug` isn't
+    assert!(!Probe::<UncloneableSecretConfig>(PhantomData).is_clone());
+
+    let config = UncloneableSecretConfig::Binance(Credentials {
+        _api_key: String::from("hunter2"),
+    });
+    assert_eq!(format!("{:?}", config), "Binance(<redacted>)");
+    assert_eq!(
+        format!(
+            "{:?}",
+            UncloneableSecretConfig::Okx(BinanceConfig {
+                api_key: String::from("public"),
+            })
+        ),
+        r#"Okx(BinanceConfig { api_key: "public" })"#
+    );
+}
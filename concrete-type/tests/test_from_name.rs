@@ -0,0 +1,56 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Debug, PartialEq)]
+#[concrete(from_name)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    #[concrete(alias = "binance", alias = "bnb")]
+    Binance,
+    #[concrete(ty = "exchanges::Okx", alias = "okx")]
+    Okx,
+    #[concrete = "exchanges::Kraken"]
+    Kraken,
+}
+
+#[test]
+fn test_from_name_parses_names_and_aliases() {
+    assert_eq!(Exchange::from_name("Binance"), Some(Exchange::Binance));
+    assert_eq!(Exchange::from_name("binance"), Some(Exchange::Binance));
+    assert_eq!(Exchange::from_name("bnb"), Some(Exchange::Binance));
+    assert_eq!(Exchange::from_name("okx"), Some(Exchange::Okx));
+    assert_eq!(Exchange::from_name("Kraken"), Some(Exchange::Kraken));
+    assert_eq!(Exchange::from_name("kraken"), None);
+    assert_eq!(Exchange::from_name(""), None);
+}
+
+#[test]
+fn test_from_str_reports_unknown_name() {
+    assert_eq!("Okx".parse::<Exchange>(), Ok(Exchange::Okx));
+
+    let error = "bitmex".parse::<Exchange>().unwrap_err();
+    assert_eq!(error.name(), "bitmex");
+    assert_eq!(error.to_string(), "unknown `Exchange` name `bitmex`");
+}
+
+#[test]
+fn test_from_name_dispatch() {
+    let exchange = Exchange::from_name("bnb").unwrap();
+    let name = exchange!(exchange; E => { std::any::type_name::<E>() });
+    assert!(name.ends_with("exchanges::Binance"));
+}
+
+#[cfg(feature = "phf")]
+#[test]
+fn test_from_name_fast_agrees_with_from_name() {
+    for name in [
+        "Binance", "binance", "bnb", "okx", "Okx", "Kraken", "kraken", "",
+    ] {
+        assert_eq!(Exchange::from_name_fast(name), Exchange::from_name(name));
+    }
+}
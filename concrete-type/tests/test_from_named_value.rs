@@ -0,0 +1,76 @@
+#![cfg(feature = "serde")]
+
+use concrete_type::ConcreteConfig;
+use serde::Deserialize;
+use serde_json::json;
+use std::error::Error;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct BinanceConfig {
+    api_key: String,
+    testnet: bool,
+}
+
+#[derive(ConcreteConfig, Debug, PartialEq)]
+#[concrete(from_named_value)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(alias = "binance")]
+    Binance(BinanceConfig),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(Option<u32>),
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+#[test]
+fn test_from_named_value_builds_variant() {
+    let config =
+        ExchangeConfig::from_named_value("binance", json!({ "api_key": "key", "testnet": true }))
+            .unwrap();
+    assert_eq!(
+        config,
+        ExchangeConfig::Binance(BinanceConfig {
+            api_key: "key".to_string(),
+            testnet: true,
+        })
+    );
+
+    let config = ExchangeConfig::from_named_value("Okx", json!(5)).unwrap();
+    assert_eq!(config, ExchangeConfig::Okx(Some(5)));
+    let config = ExchangeConfig::from_named_value("Kraken", json!(null)).unwrap();
+    assert_eq!(config, ExchangeConfig::Kraken);
+
+    for config in [config, ExchangeConfig::Okx(None)] {
+        let name = exchange_config!(config; (E, _cfg) => { std::any::type_name::<E>() });
+        assert!(name.contains("exchanges::"));
+    }
+}
+
+#[test]
+fn test_from_named_value_errors() {
+    let error = ExchangeConfig::from_named_value("Bitmex", json!({})).unwrap_err();
+    assert_eq!(error.to_string(), "unknown `ExchangeConfig` kind `Bitmex`");
+    assert!(error.source().is_none());
+
+    let error = ExchangeConfig::from_named_value("Binance", json!({ "api_key": 1 })).unwrap_err();
+    assert!(matches!(
+        error,
+        ExchangeConfigNamedValueError::InvalidValue {
+            variant: "Binance",
+            ..
+        }
+    ));
+    assert!(
+        error
+            .to_string()
+            .starts_with("invalid config for `ExchangeConfig::Binance`: ")
+    );
+    assert!(error.source().is_some());
+}
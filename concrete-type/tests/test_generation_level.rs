@@ -0,0 +1,77 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+// Methods generated by default that `minimal` leaves out, implemented here instead: with the
+// default level, they would conflict with the generated ones
+trait DefaultMethods {
+    fn concrete_type_name(&self) -> &'static str {
+        "not generated"
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(minimal, set)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", tag = 4)]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+impl DefaultMethods for Exchange {}
+
+#[derive(ConcreteConfig)]
+#[concrete(minimal)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(u32),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+impl DefaultMethods for ExchangeConfig {}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(full)]
+enum Market {
+    #[concrete = "crate::exchanges::Okx"]
+    Spot,
+}
+
+#[test]
+fn test_minimal_generation() {
+    assert_eq!(Exchange::Okx.concrete_type_name(), "not generated");
+    assert_eq!(ExchangeConfig::Okx.concrete_type_name(), "not generated");
+
+    // The fingerprint is generated at every level
+    assert_eq!(
+        Exchange::mapping_fingerprint(),
+        ExchangeConfig::mapping_fingerprint()
+    );
+
+    // Indices and tags are kept, along with what other options request
+    assert_eq!(Exchange::Okx.variant_index(), 1);
+    assert_eq!(Exchange::Binance.tag(), Some(4));
+    let set: ExchangeSet = [Exchange::Okx].into_iter().collect();
+    assert!(Exchange::Okx.in_set(&set));
+
+    let name = exchange!(Exchange::Binance; E => { std::any::type_name::<E>() });
+    assert!(name.ends_with("Binance"));
+    let config = exchange_config!(ExchangeConfig::Binance(7); (E, cfg) => {
+        let _ = std::any::type_name::<E>();
+        format!("{:?}", cfg)
+    });
+    assert_eq!(config, "7");
+    exchange_config!(ExchangeConfig::Okx; (E, _cfg) => { let _ = std::any::type_name::<E>(); });
+}
+
+#[test]
+fn test_full_generation() {
+    assert_eq!(Market::Spot.concrete_type_name(), "crate::exchanges::Okx");
+    assert_ne!(Market::mapping_fingerprint(), 0);
+    market!(Market::Spot; M => { let _ = std::any::type_name::<M>(); });
+}
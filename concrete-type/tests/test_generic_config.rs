@@ -0,0 +1,97 @@
+use concrete_type::ConcreteConfig;
+use std::fmt::Debug;
+
+mod exchanges {
+    pub trait Clock: Copy + Default {
+        fn now(&self) -> u64;
+    }
+
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct FixedClock;
+
+    impl Clock for FixedClock {
+        fn now(&self) -> u64 {
+            42
+        }
+    }
+
+    pub trait ExchangeApi {
+        type Config;
+
+        fn new(config: Self::Config) -> Self;
+        fn timestamp(&self) -> u64;
+    }
+
+    #[derive(Default)]
+    pub struct BinanceConfig<C> {
+        pub clock: C,
+    }
+
+    pub struct Binance<C>(C);
+
+    impl<C: Clock> ExchangeApi for Binance<C> {
+        type Config = BinanceConfig<C>;
+
+        fn new(config: Self::Config) -> Self {
+            Self(config.clock)
+        }
+
+        fn timestamp(&self) -> u64 {
+            self.0.now()
+        }
+    }
+
+    pub struct Okx;
+
+    impl ExchangeApi for Okx {
+        type Config = ();
+
+        fn new(_: Self::Config) -> Self {
+            Self
+        }
+
+        fn timestamp(&self) -> u64 {
+            0
+        }
+    }
+}
+
+use exchanges::{BinanceConfig, Clock, ExchangeApi, FixedClock};
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig<C: Clock>
+where
+    C: Debug,
+{
+    #[concrete = "crate::exchanges::Binance<crate::exchanges::FixedClock>"]
+    Binance(BinanceConfig<C>),
+    #[concrete = "crate::exchanges::Binance<crate::exchanges::FixedClock>"]
+    #[concrete(unwrap)]
+    BinanceDefault(Option<BinanceConfig<C>>),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+fn timestamp(config: ExchangeConfig<FixedClock>) -> u64 {
+    exchange_config!(config; (Exchange, cfg) => {
+        Exchange::new(cfg).timestamp()
+    })
+}
+
+#[test]
+fn test_generic_config_dispatch() {
+    let config = ExchangeConfig::Binance(BinanceConfig { clock: FixedClock });
+    #[cfg(feature = "config-any")]
+    assert!(config.config().is::<BinanceConfig<FixedClock>>());
+    assert_eq!(timestamp(config), 42);
+    assert_eq!(timestamp(ExchangeConfig::BinanceDefault(None)), 42);
+    assert_eq!(timestamp(ExchangeConfig::Okx), 0);
+}
+
+#[test]
+fn test_generic_config_fingerprint() {
+    assert_eq!(
+        ExchangeConfig::<FixedClock>::mapping_fingerprint(),
+        ExchangeConfig::<FixedClock>::mapping_fingerprint()
+    );
+}
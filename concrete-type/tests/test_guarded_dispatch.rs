@@ -0,0 +1,64 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub trait ExchangeApi {
+        const IN_MAINTENANCE: bool;
+
+        fn name() -> &'static str;
+    }
+
+    pub struct Binance;
+    pub struct Okx;
+
+    impl ExchangeApi for Binance {
+        const IN_MAINTENANCE: bool = false;
+
+        fn name() -> &'static str {
+            "binance"
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        const IN_MAINTENANCE: bool = true;
+
+        fn name() -> &'static str {
+            "okx"
+        }
+    }
+}
+
+use exchanges::ExchangeApi;
+
+#[derive(Concrete, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_guard_on_concrete_type() {
+    let name =
+        exchange!(Exchange::Binance; E => { E::name() } where |_exchange| !E::IN_MAINTENANCE);
+    assert_eq!(name, Some("binance"));
+
+    let name = exchange!(Exchange::Okx; E => { E::name() } where |_exchange| !E::IN_MAINTENANCE);
+    assert_eq!(name, None);
+}
+
+#[test]
+fn test_guard_on_value() {
+    let disabled = [Exchange::Binance];
+    for (exchange, expected) in [(Exchange::Binance, None), (Exchange::Okx, Some("okx"))] {
+        let name =
+            exchange!(exchange; E => { E::name() } where |exchange| !disabled.contains(exchange));
+        assert_eq!(name, expected);
+    }
+
+    // The guarded form borrows the value rather than consuming it
+    let exchange = Exchange::Okx;
+    let name = exchange!(exchange; E => { E::name() } where |value| *value == Exchange::Okx);
+    assert_eq!(name, Some("okx"));
+    assert!(exchange == Exchange::Okx);
+}
@@ -0,0 +1,115 @@
+use concrete_type::Concrete;
+use std::collections::HashSet;
+
+mod exchanges {
+    pub trait Exchange {
+        type Config
+        where
+            Self: Sized;
+
+        fn new(config: Self::Config) -> Self
+        where
+            Self: Sized;
+
+        fn name(&self) -> &'static str;
+    }
+
+    #[derive(Default)]
+    pub struct Binance;
+    #[derive(Default)]
+    pub struct Okx;
+
+    impl Exchange for Binance {
+        type Config = ();
+
+        fn new(_config: ()) -> Self {
+            Binance
+        }
+
+        fn name(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    impl Exchange for Okx {
+        type Config = u32;
+
+        fn new(_config: u32) -> Self {
+            Okx
+        }
+
+        fn name(&self) -> &'static str {
+            "okx"
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(handle, factory = "crate::exchanges::Exchange", new_default)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", tag = 7)]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+// Resolved at compile time
+const BINANCE: ExchangeHandle = Exchange::Binance.handle();
+
+#[test]
+fn test_handle_vtable() {
+    assert_eq!(BINANCE.name, "Binance");
+    assert_eq!(BINANCE.tag, Some(7));
+    assert_eq!(BINANCE.concrete_type_name, "crate::exchanges::Binance");
+    assert_eq!(*BINANCE.kind(), Exchange::Binance);
+
+    let okx = ExchangeHandle::from(Exchange::Okx);
+    assert_eq!(okx.tag, None);
+    assert_eq!(okx.vtable().kind, Exchange::Okx);
+    assert_eq!(format!("{:?}", okx), "ExchangeHandle(\"Okx\")");
+
+    assert_eq!(
+        std::mem::size_of::<Option<ExchangeHandle>>(),
+        std::mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn test_handle_constructors() {
+    let okx = Exchange::Okx.handle();
+    let exchange = (okx.create)(Box::new(5u32)).ok().unwrap();
+    assert_eq!(exchange.name(), "okx");
+    let error = (okx.create)(Box::new(())).err().unwrap();
+    assert_eq!(error.variant(), "Okx");
+
+    assert!(matches!(
+        (BINANCE.new_default)(),
+        ExchangeInstance::Binance(_)
+    ));
+    assert!(matches!((okx.new_default)(), ExchangeInstance::Okx(_)));
+}
+
+#[test]
+fn test_handle_equality() {
+    let handles: HashSet<_> = [Exchange::Binance, Exchange::Okx, Exchange::Binance]
+        .into_iter()
+        .map(ExchangeHandle::from)
+        .collect();
+    assert_eq!(handles.len(), 2);
+    assert!(handles.contains(&BINANCE));
+    assert_ne!(BINANCE, Exchange::Okx.handle());
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(handle)]
+enum Market {
+    #[concrete = "crate::exchanges::Okx"]
+    Spot,
+}
+
+#[test]
+fn test_handle_without_constructors() {
+    let handle = Market::Spot.handle();
+    assert_eq!(handle.name, "Spot");
+    assert!(matches!(handle.kind, Market::Spot));
+}
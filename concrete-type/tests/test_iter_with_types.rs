@@ -0,0 +1,53 @@
+// `binance` stands in for a backend feature that is declared but disabled
+#![allow(unexpected_cfgs)]
+
+use concrete_type::Concrete;
+use std::any::{TypeId, type_name};
+
+mod exchanges {
+    pub struct Coinbase;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Debug, Clone, Copy, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(feature = "binance")]
+    Binance,
+    #[concrete = "crate::exchanges::Coinbase"]
+    Coinbase,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_iter_with_types() {
+    let entries: Vec<_> = Exchange::iter_with_types().collect();
+    assert_eq!(entries.len(), 3);
+
+    assert_eq!(entries[0].0, Exchange::Binance);
+    assert_eq!(entries[0].2, Exchange::Binance.concrete_type_id());
+    assert!(entries[0].1.ends_with("__ExchangeBinanceUnsupported"));
+
+    assert_eq!(
+        entries[1],
+        (
+            Exchange::Coinbase,
+            type_name::<exchanges::Coinbase>(),
+            TypeId::of::<exchanges::Coinbase>()
+        )
+    );
+    assert_eq!(
+        entries[2],
+        (
+            Exchange::Okx,
+            type_name::<exchanges::Okx>(),
+            TypeId::of::<exchanges::Okx>()
+        )
+    );
+
+    // The dispatch macro declares its own stub for the disabled variant
+    for (exchange, name, _) in Exchange::iter_with_types().skip(1) {
+        assert_eq!(exchange!(exchange; E => { type_name::<E>() }), name);
+    }
+}
@@ -0,0 +1,74 @@
+use exchanges::Exchange;
+use std::any::type_name;
+use strategies::StrategyConfig;
+
+mod exchanges {
+    use concrete_type::Concrete;
+
+    pub struct Binance;
+    pub struct Okx;
+
+    #[derive(Concrete, Clone, Copy)]
+    #[concrete(macros_mod = "dispatch")]
+    pub enum Exchange {
+        #[concrete = "crate::exchanges::Binance"]
+        Binance,
+        #[concrete = "crate::exchanges::Okx"]
+        Okx,
+    }
+}
+
+mod strategies {
+    use concrete_type::ConcreteConfig;
+
+    pub struct StrategyA;
+
+    #[derive(ConcreteConfig)]
+    #[concrete(macros_mod = "dispatch")]
+    pub enum StrategyConfig {
+        #[concrete = "crate::strategies::StrategyA"]
+        StrategyA(u32),
+    }
+}
+
+#[test]
+fn test_macro_reexported_from_module() {
+    let exchange = Exchange::Okx;
+    let name = exchanges::dispatch::exchange!(exchange; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Okx>());
+
+    let exchange = Exchange::Binance;
+    let name = crate::exchanges::dispatch::exchange!(exchange; E => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Binance>());
+}
+
+#[test]
+fn test_config_macro_reexported_from_module() {
+    let config = StrategyConfig::StrategyA(3);
+    let (name, value) = strategies::dispatch::strategy_config!(config; (S, cfg) => {
+        (type_name::<S>(), cfg)
+    });
+    assert_eq!(name, type_name::<strategies::StrategyA>());
+    assert_eq!(value, 3);
+}
+
+mod venues {
+    use concrete_type::Concrete;
+
+    pub struct Kraken;
+
+    #[derive(Concrete, Clone, Copy)]
+    #[concrete(prefix_crate_name, doc_hidden)]
+    pub enum Venue {
+        #[concrete = "crate::venues::Kraken"]
+        Kraken,
+    }
+}
+
+#[test]
+fn test_macro_prefixed_with_crate_name() {
+    use venues::Venue;
+
+    let name = test_macros_mod_venue!(Venue::Kraken; V => { type_name::<V>() });
+    assert_eq!(name, type_name::<venues::Kraken>());
+}
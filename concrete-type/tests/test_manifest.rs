@@ -0,0 +1,66 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(manifest)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(manifest)]
+enum ExchangeConfig<'a> {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(Option<Vec<String>>),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(&'a String),
+}
+
+#[test]
+fn test_concrete_manifest() {
+    assert_eq!(
+        Exchange::CONCRETE_MANIFEST,
+        concat!(
+            r#"{"enum":"Exchange","variants":["#,
+            r#"{"variant":"Binance","concrete_type":"crate::exchanges::Binance","config_type":null,"tag":null,"trait":null,"constructor":null,"feature":null,"doc":null},"#,
+            r#"{"variant":"Okx","concrete_type":"crate::exchanges::Okx","config_type":null,"tag":null,"trait":null,"constructor":null,"feature":null,"doc":null}]}"#,
+        )
+    );
+
+    for (exchange, concrete_type) in [
+        (
+            Exchange::Binance,
+            std::any::type_name::<exchanges::Binance>(),
+        ),
+        (Exchange::Okx, std::any::type_name::<exchanges::Okx>()),
+    ] {
+        let name = exchange!(exchange; E => { std::any::type_name::<E>() });
+        assert_eq!(name, concrete_type);
+    }
+}
+
+#[test]
+fn test_concrete_config_manifest() {
+    assert_eq!(
+        ExchangeConfig::CONCRETE_MANIFEST,
+        concat!(
+            r#"{"enum":"ExchangeConfig","variants":["#,
+            r#"{"variant":"Binance","concrete_type":"crate::exchanges::Binance","config_type":"Option<Vec<String>>","tag":null,"trait":null,"constructor":null,"feature":null,"doc":null},"#,
+            r#"{"variant":"Okx","concrete_type":"crate::exchanges::Okx","config_type":"&'a String","tag":null,"trait":null,"constructor":null,"feature":null,"doc":null}]}"#,
+        )
+    );
+
+    let key = "key".to_string();
+    let name =
+        exchange_config!(ExchangeConfig::Okx(&key); (E, _cfg) => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Okx>());
+    let name = exchange_config!(ExchangeConfig::Binance(None); (E, _cfg) => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Binance>());
+}
@@ -0,0 +1,81 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Spot;
+    pub struct Market<T, U>(T, U);
+}
+
+#[derive(Concrete)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(Concrete)]
+enum ExchangeSwapped {
+    #[concrete = "crate::exchanges::Okx"]
+    Binance,
+    #[concrete = "crate::exchanges::Binance"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(base = "crate::exchanges::{Variant}")]
+enum ExchangeConfig {
+    Binance(u32),
+    Okx,
+}
+
+#[derive(Concrete)]
+enum Market {
+    #[concrete = "crate::exchanges::Market<crate::exchanges::Spot, u8>"]
+    Spot,
+}
+
+const FINGERPRINT: u64 = Exchange::mapping_fingerprint();
+
+#[test]
+fn test_fingerprint_is_stable() {
+    // Pinned so accidental changes to the hashing scheme are caught
+    assert_eq!(FINGERPRINT, Exchange::mapping_fingerprint());
+    assert_eq!(Exchange::mapping_fingerprint(), 0xd9ab_00cf_6d44_d43d);
+    // Hashes `crate::exchanges::Market<crate::exchanges::Spot, u8>`, independently of how the
+    // compiler renders the tokens
+    assert_eq!(Market::mapping_fingerprint(), 0xb452_ef66_53d6_ec6c);
+}
+
+#[test]
+fn test_fingerprint_tracks_mapping() {
+    assert_ne!(
+        Exchange::mapping_fingerprint(),
+        ExchangeSwapped::mapping_fingerprint()
+    );
+    assert_eq!(
+        Exchange::mapping_fingerprint(),
+        ExchangeConfig::mapping_fingerprint()
+    );
+}
+
+#[test]
+fn test_fingerprint_enums_dispatch_as_mapped() {
+    for (exchange, swapped) in [
+        (Exchange::Binance, ExchangeSwapped::Okx),
+        (Exchange::Okx, ExchangeSwapped::Binance),
+    ] {
+        let name = exchange!(exchange; E => { std::any::type_name::<E>() });
+        let swapped_name = exchange_swapped!(swapped; E => { std::any::type_name::<E>() });
+        assert_eq!(name, swapped_name);
+    }
+
+    for config in [ExchangeConfig::Binance(1), ExchangeConfig::Okx] {
+        let name = exchange_config!(config; (E, _cfg) => { std::any::type_name::<E>() });
+        assert!(name.contains("exchanges::"));
+    }
+
+    let name = market!(Market::Spot; M => { std::any::type_name::<M>() });
+    assert!(name.ends_with("Market<test_mapping_fingerprint::exchanges::Spot, u8>"));
+}
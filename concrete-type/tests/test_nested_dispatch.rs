@@ -0,0 +1,140 @@
+use concrete_type::Concrete;
+
+mod venues {
+    pub struct Binance;
+
+    pub mod okx {
+        pub struct Spot;
+        pub struct Margin;
+    }
+}
+
+trait Venue {
+    const NAME: &'static str;
+}
+
+impl Venue for venues::Binance {
+    const NAME: &'static str = "binance";
+}
+
+impl Venue for venues::okx::Spot {
+    const NAME: &'static str = "okx-spot";
+}
+
+impl Venue for venues::okx::Margin {
+    const NAME: &'static str = "okx-margin";
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum OkxAccount {
+    #[concrete = "venues::okx::Spot"]
+    Spot,
+    #[concrete = "venues::okx::Margin"]
+    Margin,
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Exchange {
+    #[concrete = "venues::Binance"]
+    Binance,
+    #[concrete(nested)]
+    Okx(OkxAccount),
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Account {
+    #[concrete(nested)]
+    Exchange(Exchange),
+}
+
+fn venue_name(exchange: Exchange) -> &'static str {
+    exchange!(exchange; V => { V::NAME })
+}
+
+#[test]
+fn test_nested_variant_dispatches_inner_enum() {
+    assert_eq!(venue_name(Exchange::Binance), "binance");
+    assert_eq!(venue_name(Exchange::Okx(OkxAccount::Spot)), "okx-spot");
+    assert_eq!(venue_name(Exchange::Okx(OkxAccount::Margin)), "okx-margin");
+}
+
+#[test]
+fn test_nested_dispatch_through_reference() {
+    let exchange = Exchange::Okx(OkxAccount::Margin);
+    let name = exchange!(&exchange; V => { V::NAME });
+    assert_eq!(name, "okx-margin");
+}
+
+#[test]
+fn test_nesting_is_recursive() {
+    let account = Account::Exchange(Exchange::Okx(OkxAccount::Spot));
+    assert_eq!(account!(account; V => { V::NAME }), "okx-spot");
+}
+
+#[test]
+fn test_nested_guarded_dispatch() {
+    let spot = Exchange::Okx(OkxAccount::Spot);
+    let is_okx = |exchange: &Exchange| matches!(exchange, Exchange::Okx(_));
+    assert_eq!(
+        exchange!(spot; V => { V::NAME } where |value| is_okx(value)),
+        Some("okx-spot")
+    );
+    assert_eq!(
+        exchange!(Exchange::Binance; V => { V::NAME } where |value| is_okx(value)),
+        None
+    );
+}
+
+#[test]
+fn test_nested_variant_falls_to_subset_else() {
+    let exchange = Exchange::Okx(OkxAccount::Spot);
+    let name = exchange!(exchange; [Binance]; V => { V::NAME } else { "other" });
+    assert_eq!(name, "other");
+}
+
+#[test]
+fn test_nested_variant_maps_to_inner_enum_type() {
+    assert_eq!(
+        Exchange::Okx(OkxAccount::Spot).concrete_type_id(),
+        std::any::TypeId::of::<OkxAccount>()
+    );
+}
+
+mod accounts {
+    use concrete_type::Concrete;
+
+    // Only reachable as `accounts::dispatch::test_nested_dispatch_margin_account!`
+    #[derive(Concrete, Clone, Copy)]
+    #[concrete(macros_mod = "dispatch", prefix_crate_name)]
+    pub enum MarginAccount {
+        #[concrete = "crate::venues::okx::Margin"]
+        Cross,
+        #[concrete = "crate::venues::Binance"]
+        Isolated,
+    }
+}
+
+// The inner enum itself must still be in scope where the outer macro is used
+use accounts::MarginAccount;
+
+#[derive(Concrete, Clone, Copy)]
+enum Market {
+    #[concrete = "venues::okx::Spot"]
+    Spot,
+    #[concrete(nested = "crate::accounts::dispatch::test_nested_dispatch_margin_account")]
+    Margin(MarginAccount),
+}
+
+#[test]
+fn test_nested_variant_dispatches_through_macro_path() {
+    let markets = [
+        Market::Spot,
+        Market::Margin(MarginAccount::Cross),
+        Market::Margin(MarginAccount::Isolated),
+    ];
+    let names: Vec<&str> = markets
+        .into_iter()
+        .map(|market| market!(market; V => { V::NAME }))
+        .collect();
+    assert_eq!(names, ["okx-spot", "okx-margin", "binance"]);
+}
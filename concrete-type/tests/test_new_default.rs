@@ -0,0 +1,44 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    #[derive(Debug, PartialEq)]
+    pub struct Binance {
+        pub api_key: String,
+    }
+
+    impl Default for Binance {
+        fn default() -> Self {
+            Self {
+                api_key: "default".to_string(),
+            }
+        }
+    }
+
+    #[derive(Debug, Default, PartialEq)]
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(new_default)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_new_default() {
+    match Exchange::Binance.new_default() {
+        ExchangeInstance::Binance(binance) => assert_eq!(binance.api_key, "default"),
+        ExchangeInstance::Okx(_) => panic!("expected a Binance instance"),
+    }
+
+    match Exchange::Okx.new_default() {
+        ExchangeInstance::Okx(okx) => assert_eq!(okx, exchanges::Okx),
+        ExchangeInstance::Binance(_) => panic!("expected an Okx instance"),
+    }
+
+    let name = exchange!(Exchange::Okx; E => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Okx>());
+}
@@ -0,0 +1,90 @@
+use concrete_type::Concrete;
+use std::thread;
+
+mod exchanges {
+    use std::rc::Rc;
+
+    pub trait ExchangeApi {
+        type Config
+        where
+            Self: Sized;
+
+        fn new(config: Self::Config) -> Self
+        where
+            Self: Sized;
+
+        fn name(&self) -> String;
+    }
+
+    pub struct Binance;
+
+    impl ExchangeApi for Binance {
+        type Config = ();
+
+        fn new(_: Self::Config) -> Self {
+            Self
+        }
+
+        fn name(&self) -> String {
+            "binance".to_string()
+        }
+    }
+
+    // Holds thread-local state, so it can't be sent to another thread
+    pub struct Local {
+        pub name: Rc<str>,
+    }
+
+    impl ExchangeApi for Local {
+        type Config = String;
+
+        fn new(config: Self::Config) -> Self {
+            Self {
+                name: config.into(),
+            }
+        }
+
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(factory = "crate::exchanges::ExchangeApi")]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Local", non_send)]
+    Local,
+}
+
+#[test]
+fn test_unrestricted_factory_creates_every_variant() {
+    let api = Exchange::Local
+        .create(Box::new("local".to_string()))
+        .unwrap();
+    assert_eq!(api.name(), "local");
+
+    let api = Exchange::Binance.create(Box::new(())).unwrap();
+    assert_eq!(api.name(), "binance");
+}
+
+#[test]
+fn test_send_factory_creates_send_variants() {
+    let exchange = ExchangeSend::try_from(Exchange::Binance).unwrap();
+    assert_eq!(Exchange::from(exchange), Exchange::Binance);
+    assert_eq!(
+        ExchangeSend::try_from(Exchange::Local),
+        Err(Exchange::Local)
+    );
+
+    let api = ExchangeSendFactory::create(&exchange, Box::new(())).unwrap();
+    let name = thread::spawn(move || api.name()).join().unwrap();
+    assert_eq!(name, "binance");
+
+    let error = ExchangeSendFactory::create(&exchange, Box::new(0u8))
+        .err()
+        .unwrap();
+    assert_eq!(error.variant(), "Binance");
+}
@@ -0,0 +1,69 @@
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(per_variant)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_per_variant_index() {
+    let mut requests: PerExchange<u32> = PerExchange::default();
+    requests[Exchange::Okx] += 2;
+    requests[Exchange::Binance] += 1;
+    requests[Exchange::Okx] += 1;
+
+    assert_eq!(requests[Exchange::Binance], 1);
+    assert_eq!(requests[Exchange::Okx], 3);
+    assert_eq!(requests.into_array(), [1, 3]);
+    assert_eq!(std::mem::size_of::<PerExchange<u32>>(), 2 * 4);
+}
+
+#[test]
+fn test_per_variant_from_fn() {
+    let names = PerExchange::from_fn(
+        |exchange| exchange!(exchange; E => { std::any::type_name::<E>().rsplit("::").next().unwrap() }),
+    );
+    assert_eq!(names[Exchange::Binance], "Binance");
+    assert_eq!(
+        names.iter().collect::<Vec<_>>(),
+        [(Exchange::Binance, &"Binance"), (Exchange::Okx, &"Okx")]
+    );
+
+    let mut limits = PerExchange::from_fn(|_| 10);
+    for (exchange, limit) in limits.iter_mut() {
+        if exchange == Exchange::Okx {
+            *limit = 20;
+        }
+    }
+    assert_eq!(
+        limits,
+        PerExchange::from_fn(|exchange| exchange.variant_index() * 10 + 10)
+    );
+}
+
+#[test]
+fn test_per_variant_try_from_fn() {
+    let mut calls = Vec::new();
+    let error = PerExchange::<u8>::try_from_fn(|exchange| {
+        calls.push(exchange);
+        match exchange {
+            Exchange::Binance => Err("offline"),
+            Exchange::Okx => Ok(1),
+        }
+    })
+    .unwrap_err();
+    assert_eq!(error, "offline");
+    assert_eq!(calls, [Exchange::Binance]);
+
+    let ports = PerExchange::try_from_fn(|exchange| Ok::<_, ()>(exchange.variant_index() as u16));
+    assert_eq!(ports.unwrap().into_array(), [0, 1]);
+}
@@ -0,0 +1,48 @@
+use concrete_type::Concrete;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq, Eq)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", priority = 20)]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", priority = -5)]
+    Okx,
+    #[concrete(ty = "crate::exchanges::Kraken", priority = 20)]
+    Kraken,
+}
+
+#[test]
+fn test_iter_by_priority() {
+    assert_eq!(
+        Exchange::iter_by_priority().collect::<Vec<_>>(),
+        [Exchange::Okx, Exchange::Binance, Exchange::Kraken]
+    );
+    assert_eq!(Exchange::Okx.priority(), -5);
+
+    // Failover tries the backends in priority order
+    let tried: Vec<_> = Exchange::iter_by_priority()
+        .map(|exchange| exchange!(exchange; E => { type_name::<E>() }))
+        .collect();
+    assert_eq!(tried[0], type_name::<exchanges::Okx>());
+}
+
+#[test]
+fn test_ord_follows_priority() {
+    let mut exchanges = vec![Exchange::Kraken, Exchange::Binance, Exchange::Okx];
+    exchanges.sort();
+    assert_eq!(
+        exchanges,
+        [Exchange::Okx, Exchange::Binance, Exchange::Kraken]
+    );
+    assert!(Exchange::Binance < Exchange::Kraken);
+    assert_eq!(
+        [Exchange::Binance, Exchange::Okx].iter().max(),
+        Some(&Exchange::Binance)
+    );
+}
@@ -0,0 +1,52 @@
+use concrete_type::Concrete;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(relative_macro)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+mod dispatched {
+    use concrete_type::Concrete;
+
+    #[derive(Concrete, Clone, Copy)]
+    #[concrete(relative_macro, macros_mod = "dispatch")]
+    pub enum Strategy {
+        #[concrete = "crate::exchanges::Binance"]
+        Momentum,
+    }
+}
+
+#[test]
+fn test_relative_macro_dispatches_like_default_flavor() {
+    for exchange in [Exchange::Binance, Exchange::Okx] {
+        let absolute = exchange!(exchange; E => { type_name::<E>() });
+        let relative = exchange_relative!(exchange; E => { type_name::<E>() });
+        assert_eq!(absolute, relative);
+    }
+
+    assert_eq!(
+        exchange_relative!(Exchange::Okx; E => { type_name::<E>() }),
+        type_name::<exchanges::Okx>()
+    );
+}
+
+#[test]
+fn test_relative_macro_is_reexported_from_macros_mod() {
+    use dispatched::Strategy;
+
+    let absolute = dispatched::dispatch::strategy!(Strategy::Momentum; S => { type_name::<S>() });
+    let relative =
+        dispatched::dispatch::strategy_relative!(Strategy::Momentum; S => { type_name::<S>() });
+    assert_eq!(absolute, relative);
+    assert_eq!(relative, type_name::<exchanges::Binance>());
+}
@@ -0,0 +1,53 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct BinanceUs;
+    pub struct CoinbasePro;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(routing = "exch.{snake}.events")]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::BinanceUs"]
+    BinanceUs,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(minimal, routing = "orders/{kebab}/{Variant}")]
+enum ExchangeConfig<C> {
+    #[concrete = "exchanges::BinanceUs"]
+    BinanceUs(C),
+    #[concrete = "exchanges::CoinbasePro"]
+    CoinbasePro,
+}
+
+#[test]
+fn test_routing_key() {
+    assert_eq!(Exchange::Binance.routing_key(), "exch.binance.events");
+    assert_eq!(Exchange::BinanceUs.routing_key(), "exch.binance_us.events");
+
+    // Usable in const contexts
+    const TOPIC: &str = Exchange::BinanceUs.routing_key();
+    assert_eq!(TOPIC, "exch.binance_us.events");
+
+    let topic = exchange!(Exchange::Binance; E => {
+        let _ = (exchanges::Binance, exchanges::BinanceUs, exchanges::CoinbasePro);
+        std::any::type_name::<E>()
+    });
+    assert!(topic.ends_with("Binance"));
+}
+
+#[test]
+fn test_config_routing_key() {
+    assert_eq!(
+        ExchangeConfig::BinanceUs(1_u8).routing_key(),
+        "orders/binance-us/BinanceUs"
+    );
+    assert_eq!(
+        ExchangeConfig::<u8>::CoinbasePro.routing_key(),
+        "orders/coinbase-pro/CoinbasePro"
+    );
+}
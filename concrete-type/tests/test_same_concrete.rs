@@ -0,0 +1,52 @@
+use concrete_type::{Concrete, ConcreteConfig};
+use std::any::TypeId;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Binance"]
+    BinanceUs,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(u32),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_same_concrete_as_within_enum() {
+    assert!(Exchange::Binance.same_concrete_as(&Exchange::BinanceUs));
+    assert!(!Exchange::Binance.same_concrete_as(&Exchange::Okx));
+    assert!(ExchangeConfig::Binance(1).same_concrete_as(&ExchangeConfig::Binance(2)));
+    assert!(!ExchangeConfig::Binance(1).same_concrete_as(&ExchangeConfig::Okx));
+}
+
+#[test]
+fn test_concrete_type_id_across_enums() {
+    assert_eq!(
+        Exchange::BinanceUs.concrete_type_id(),
+        ExchangeConfig::Binance(1).concrete_type_id()
+    );
+    assert_eq!(
+        ExchangeConfig::Okx.concrete_type_id(),
+        TypeId::of::<exchanges::Okx>()
+    );
+
+    for exchange in [Exchange::Binance, Exchange::BinanceUs, Exchange::Okx] {
+        let type_id = exchange!(exchange; E => { TypeId::of::<E>() });
+        assert_eq!(exchange.concrete_type_id(), type_id);
+    }
+    let type_id = exchange_config!(ExchangeConfig::Binance(1); (E, _cfg) => { TypeId::of::<E>() });
+    assert_eq!(type_id, TypeId::of::<exchanges::Binance>());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "serde")]
+
+use concrete_type::ConcreteConfig;
+use serde::Serialize;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Serialize)]
+struct Credentials {
+    api_key: String,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(derive(Debug, Serialize))]
+enum ExchangeConfig {
+    #[concrete(ty = "exchanges::Binance", secret)]
+    Binance(Credentials),
+    #[concrete = "exchanges::Okx"]
+    Okx(u32),
+    #[concrete = "exchanges::Kraken"]
+    Kraken,
+}
+
+/// Holds a secret config that must be persisted as is
+#[derive(Serialize)]
+struct Account {
+    #[serde(serialize_with = "ExchangeConfig::serialize_unredacted")]
+    exchange: ExchangeConfig,
+}
+
+fn binance() -> ExchangeConfig {
+    ExchangeConfig::Binance(Credentials {
+        api_key: String::from("hunter2"),
+    })
+}
+
+#[test]
+fn test_serialize_redacts_secret_configs() {
+    assert_eq!(
+        serde_json::to_string(&binance()).unwrap(),
+        r#"{"Binance":"<redacted>"}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&ExchangeConfig::Okx(3)).unwrap(),
+        r#"{"Okx":3}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&ExchangeConfig::Kraken).unwrap(),
+        r#""Kraken""#
+    );
+    assert!(!format!("{:?}", binance()).contains("hunter2"));
+    let _ = (exchanges::Binance, exchanges::Okx, exchanges::Kraken);
+}
+
+#[test]
+fn test_serialize_unredacted() {
+    let mut json = Vec::new();
+    binance()
+        .serialize_unredacted(&mut serde_json::Serializer::new(&mut json))
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(json).unwrap(),
+        r#"{"Binance":{"api_key":"hunter2"}}"#
+    );
+
+    let account = Account {
+        exchange: binance(),
+    };
+    assert_eq!(
+        serde_json::to_string(&account).unwrap(),
+        r#"{"exchange":{"Binance":{"api_key":"hunter2"}}}"#
+    );
+}
@@ -0,0 +1,38 @@
+// `binance` stands in for a backend feature that is declared but disabled
+#![allow(unexpected_cfgs)]
+
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Okx;
+}
+
+// The generated `exchange_selftest` module holds the tests checking this mapping
+#[derive(Concrete, Clone, Copy, Debug, PartialEq, Eq)]
+#[concrete(selftest, from_name, handle)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", tag = 3, priority = 1)]
+    #[concrete(feature = "binance", alias = "bnb")]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", tag = 1, priority = 0)]
+    Okx,
+    #[concrete(ty = "crate::exchanges::Okx", priority = 2)]
+    OkxUs,
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(selftest)]
+enum Market {
+    #[concrete = "crate::exchanges::Okx"]
+    Spot,
+}
+
+// The generated tests, e.g. `exchange_selftest::tags`, run alongside this one
+#[test]
+fn test_selftest_enums_dispatch() {
+    let exchange = exchange!(Exchange::from_name("okx").unwrap_or(Exchange::OkxUs); E => {
+        std::any::type_name::<E>()
+    });
+    assert!(exchange.ends_with("Okx"));
+    market!(Market::Spot; M => { let _ = std::any::type_name::<M>(); });
+}
@@ -0,0 +1,82 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub trait ExchangeApi {
+        fn name(&self) -> &'static str;
+    }
+
+    #[derive(Default)]
+    pub struct Binance;
+    #[derive(Default)]
+    pub struct Okx;
+
+    impl Binance {
+        pub fn try_new() -> Result<Self, String> {
+            Ok(Binance)
+        }
+    }
+
+    impl ExchangeApi for Binance {
+        fn name(&self) -> &'static str {
+            "binance"
+        }
+    }
+
+    impl ExchangeApi for Okx {
+        fn name(&self) -> &'static str {
+            "okx"
+        }
+    }
+}
+
+use exchanges::ExchangeApi;
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(manifest)]
+enum Exchange {
+    #[concrete(
+        ty = "crate::exchanges::Binance",
+        trait = "crate::exchanges::ExchangeApi",
+        constructor = "try_new",
+        tag = 3
+    )]
+    Binance,
+    // The legacy form keeps working alongside the structured one
+    #[concrete = "crate::exchanges::Okx"]
+    #[concrete(tag = 7)]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete(ty = "crate::exchanges::{Variant}")]
+    Binance(u32),
+    #[concrete(ty = "crate::exchanges::Okx", tag = 1)]
+    Okx,
+}
+
+#[test]
+fn test_structured_ty_dispatch() {
+    let name = exchange!(Exchange::Binance; E => { E::default().name() });
+    assert_eq!(name, "binance");
+    let name = exchange!(Exchange::Okx; E => { E::default().name() });
+    assert_eq!(name, "okx");
+
+    let name = exchange_config!(ExchangeConfig::Binance(5); (E, _cfg) => { E::default().name() });
+    assert_eq!(name, "binance");
+    let name = exchange_config!(ExchangeConfig::Okx; (E, _cfg) => { E::default().name() });
+    assert_eq!(name, "okx");
+}
+
+#[test]
+fn test_structured_options_in_manifest() {
+    assert_eq!(
+        Exchange::CONCRETE_MANIFEST,
+        concat!(
+            r#"{"enum":"Exchange","variants":["#,
+            r#"{"variant":"Binance","concrete_type":"crate::exchanges::Binance","config_type":null,"tag":3,"trait":"crate::exchanges::ExchangeApi","constructor":"try_new","feature":null,"doc":null},"#,
+            r#"{"variant":"Okx","concrete_type":"crate::exchanges::Okx","config_type":null,"tag":7,"trait":null,"constructor":null,"feature":null,"doc":null}]}"#,
+        )
+    );
+    assert!(exchanges::Binance::try_new().is_ok());
+}
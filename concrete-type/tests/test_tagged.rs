@@ -0,0 +1,99 @@
+use concrete_type::Concrete;
+
+trait Exchange {
+    fn new(endpoint: &str) -> Self;
+    fn endpoint(&self) -> &str;
+}
+
+mod exchanges {
+    use super::Exchange;
+
+    pub struct Binance(pub String);
+    pub struct Okx(pub String);
+
+    impl Exchange for Binance {
+        fn new(endpoint: &str) -> Self {
+            Binance(format!("binance:{endpoint}"))
+        }
+
+        fn endpoint(&self) -> &str {
+            &self.0
+        }
+    }
+
+    impl Exchange for Okx {
+        fn new(endpoint: &str) -> Self {
+            Okx(format!("okx:{endpoint}"))
+        }
+
+        fn endpoint(&self) -> &str {
+            &self.0
+        }
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq, Eq)]
+#[concrete(tagged, for_each)]
+enum ExchangeKind {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+    // Shares its concrete type with `Okx`, but is tagged as itself
+    #[concrete = "exchanges::Okx"]
+    OkxTestnet,
+}
+
+/// Kind enums that aren't `Clone` still get a wrapper, without the derived traits
+#[derive(Concrete)]
+#[concrete(tagged)]
+enum Region {
+    #[concrete = "u8"]
+    Eu,
+}
+
+#[test]
+fn test_tagged_in_dispatch() {
+    for kind in [
+        ExchangeKind::Binance,
+        ExchangeKind::Okx,
+        ExchangeKind::OkxTestnet,
+    ] {
+        let tagged = exchange_kind!(kind; E => {
+            E::new("eu").endpoint().to_string().tagged()
+        });
+        assert_eq!(tagged.kind(), &kind);
+        assert_eq!(tagged, ExchangeKindTagged::new(tagged.0.clone(), kind));
+    }
+
+    // The wrapper dereferences to the value
+    let tagged = exchange_kind!(ExchangeKind::OkxTestnet; E => {
+        E::new("eu").endpoint().to_string().tagged()
+    });
+    assert_eq!(tagged.1, ExchangeKind::OkxTestnet);
+    assert_eq!(tagged.as_str(), "okx:eu");
+}
+
+#[test]
+fn test_tagged_outside_dispatch() {
+    let tagged = exchange_kind!(@variant Binance; E => { E::new("us").tagged() });
+    assert_eq!(*tagged.kind(), ExchangeKind::Binance);
+
+    let mapped = tagged.map(|exchange| exchange.0.len());
+    assert_eq!(mapped, ExchangeKindTagged(10, ExchangeKind::Binance));
+    assert_eq!(mapped.into_inner(), 10);
+
+    let mut kinds = Vec::new();
+    for_each_exchange_kind!(E => { kinds.push(E::new("ap").tagged().1); });
+    assert_eq!(
+        kinds,
+        [
+            ExchangeKind::Binance,
+            ExchangeKind::Okx,
+            ExchangeKind::OkxTestnet
+        ]
+    );
+
+    let region = region!(Region::Eu; R => { R::default().tagged() });
+    assert!(matches!(region.kind(), Region::Eu));
+}
@@ -0,0 +1,69 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    #[derive(Debug, Default, PartialEq)]
+    pub struct Binance {
+        pub api_key: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct Okx {
+        pub passphrase: String,
+    }
+}
+
+#[derive(Concrete, Debug, Clone, Copy, PartialEq)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig, Debug, PartialEq)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(String),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_try_into_concrete() {
+    assert_eq!(
+        Exchange::Binance.try_into_concrete::<exchanges::Binance>(),
+        Ok(exchanges::Binance::default())
+    );
+    assert_eq!(
+        Exchange::Okx.try_into_concrete::<exchanges::Binance>(),
+        Err(Exchange::Okx)
+    );
+    assert_eq!(
+        ExchangeConfig::Binance("key".to_string()).try_into_concrete::<exchanges::Binance>(),
+        Ok(exchanges::Binance::default())
+    );
+}
+
+#[test]
+fn test_try_into_concrete_with() {
+    let okx = Exchange::Okx.try_into_concrete_with(|| exchanges::Okx {
+        passphrase: "secret".to_string(),
+    });
+    assert_eq!(
+        okx,
+        Ok(exchanges::Okx {
+            passphrase: "secret".to_string()
+        })
+    );
+
+    let config = ExchangeConfig::Binance("key".to_string());
+    let result = config.try_into_concrete_with(|| exchanges::Okx {
+        passphrase: "secret".to_string(),
+    });
+    assert_eq!(result, Err(ExchangeConfig::Binance("key".to_string())));
+
+    let name = exchange!(Exchange::Okx; E => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Okx>());
+    let name = exchange_config!(ExchangeConfig::Okx; (E, _cfg) => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Okx>());
+}
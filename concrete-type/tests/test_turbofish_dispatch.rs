@@ -0,0 +1,60 @@
+use concrete_type::Concrete;
+use std::any::type_name;
+use std::marker::PhantomData;
+
+pub struct SystemClock;
+pub struct MockClock;
+pub struct Usd;
+
+mod exchanges {
+    use std::marker::PhantomData;
+
+    pub struct Binance<C>(pub PhantomData<C>);
+    pub struct Okx<Q, C>(pub PhantomData<(Q, C)>);
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(partial)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    // Partially applied, the clock is appended after the quote currency
+    #[concrete = "crate::exchanges::Okx<crate::Usd>"]
+    Okx,
+}
+
+#[test]
+fn test_turbofish_appends_type_arguments() {
+    let name = exchange!(Exchange::Binance; E<MockClock> => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Binance<MockClock>>());
+
+    let name = exchange!(Exchange::Okx; E<SystemClock> => { type_name::<E>() });
+    assert_eq!(name, type_name::<exchanges::Okx<Usd, SystemClock>>());
+}
+
+trait Clocked {
+    fn with_clock() -> Self;
+}
+
+impl<C> Clocked for exchanges::Binance<C> {
+    fn with_clock() -> Self {
+        exchanges::Binance(PhantomData)
+    }
+}
+
+impl<Q, C> Clocked for exchanges::Okx<Q, C> {
+    fn with_clock() -> Self {
+        exchanges::Okx(PhantomData)
+    }
+}
+
+#[test]
+fn test_turbofish_alias_is_constructible() {
+    for exchange in [Exchange::Binance, Exchange::Okx] {
+        let size = exchange!(exchange; E<MockClock> => {
+            let instance = E::with_clock();
+            std::mem::size_of_val(&instance)
+        });
+        assert_eq!(size, 0);
+    }
+}
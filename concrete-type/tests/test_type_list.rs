@@ -0,0 +1,77 @@
+// `binance` stands in for a backend feature that is declared but disabled
+#![allow(unexpected_cfgs)]
+
+use concrete_type::Concrete;
+use std::any::TypeId;
+
+mod exchanges {
+    pub trait Exchange {
+        const NAME: &'static str;
+    }
+
+    pub struct Okx;
+    pub struct Kraken<M>(std::marker::PhantomData<M>);
+    pub struct Spot;
+
+    impl Exchange for Okx {
+        const NAME: &'static str = "okx";
+    }
+
+    impl<M: 'static> Exchange for Kraken<M> {
+        const NAME: &'static str = "kraken";
+    }
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(type_list = "crate::exchanges::Exchange")]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    #[concrete(feature = "binance")]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken<crate::exchanges::Spot>"]
+    Kraken,
+}
+
+struct Names(Vec<(Exchange, &'static str)>);
+
+impl ExchangeTypeVisitor for Names {
+    fn visit<T: exchanges::Exchange + 'static>(&mut self, variant: Exchange) {
+        self.0.push((variant, T::NAME));
+    }
+}
+
+#[test]
+fn test_concrete_types_tuple() {
+    type Enabled = (
+        <ExchangeConcreteTypes as Split>::Second,
+        <ExchangeConcreteTypes as Split>::Third,
+    );
+    assert_eq!(
+        TypeId::of::<Enabled>(),
+        TypeId::of::<(exchanges::Okx, exchanges::Kraken<exchanges::Spot>)>()
+    );
+    // The disabled variant is its stub in the tuple, and isn't visited
+    assert_eq!(Exchange::Binance.required_feature(), Some("binance"));
+}
+
+#[test]
+fn test_visit_concrete_types() {
+    let mut names = Names(Vec::new());
+    Exchange::visit_concrete_types(&mut names);
+    assert_eq!(
+        names.0,
+        [(Exchange::Okx, "okx"), (Exchange::Kraken, "kraken")]
+    );
+}
+
+trait Split {
+    type Second;
+    type Third;
+}
+
+impl<A, B, C> Split for (A, B, C) {
+    type Second = B;
+    type Third = C;
+}
@@ -0,0 +1,102 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    OkxSpot,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(String),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+// Usable without runtime initialization
+static EXCHANGE_TYPE_NAMES: [&str; 2] = [
+    Exchange::Binance.concrete_type_name(),
+    Exchange::OKX_SPOT_TYPE_NAME,
+];
+
+#[test]
+fn test_concrete_type_name() {
+    assert_eq!(
+        EXCHANGE_TYPE_NAMES,
+        ["crate::exchanges::Binance", "crate::exchanges::Okx"]
+    );
+    assert_eq!(Exchange::BINANCE_TYPE_NAME, "crate::exchanges::Binance");
+
+    assert_eq!(
+        ExchangeConfig::Binance(String::new()).concrete_type_name(),
+        ExchangeConfig::BINANCE_TYPE_NAME
+    );
+    assert_eq!(
+        ExchangeConfig::Okx.concrete_type_name(),
+        "crate::exchanges::Okx"
+    );
+
+    let name = exchange!(Exchange::OkxSpot; E => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Okx>());
+    let name = exchange_config!(ExchangeConfig::Okx; (E, _cfg) => { std::any::type_name::<E>() });
+    assert_eq!(name, std::any::type_name::<exchanges::Okx>());
+}
+
+mod markets {
+    pub struct Spot<Q>(pub std::marker::PhantomData<Q>);
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Market {
+    #[concrete = "crate::markets::Spot<crate::exchanges::Binance>"]
+    BinanceSpot,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_type_name_short() {
+    assert_eq!(Exchange::OkxSpot.type_name_short(), "Okx");
+    assert_eq!(Market::BinanceSpot.type_name_short(), "Spot");
+    assert_eq!(Market::Okx.type_name_short(), "Okx");
+    assert_eq!(
+        ExchangeConfig::Binance(String::new()).type_name_short(),
+        "Binance"
+    );
+
+    const SHORT: &str = Exchange::Binance.type_name_short();
+    assert_eq!(SHORT, "Binance");
+
+    let name = market!(@variant BinanceSpot; M => {
+        let _: M = markets::Spot(std::marker::PhantomData);
+        std::any::type_name::<M>()
+    });
+    assert!(name.ends_with("Spot<test_type_name::exchanges::Binance>"));
+}
+
+#[test]
+fn test_metric_labels() {
+    assert_eq!(
+        Exchange::OkxSpot.metric_labels(),
+        [("kind", "OkxSpot"), ("impl", "Okx")]
+    );
+    assert_eq!(
+        Market::BinanceSpot.metric_labels(),
+        [("kind", "BinanceSpot"), ("impl", "Spot")]
+    );
+    assert_eq!(
+        ExchangeConfig::Binance(String::new()).metric_labels(),
+        [("kind", "Binance"), ("impl", "Binance")]
+    );
+
+    const LABELS: [(&str, &str); 2] = Exchange::Binance.metric_labels();
+    assert_eq!(LABELS[1], ("impl", "Binance"));
+}
@@ -0,0 +1,58 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(manifest)]
+enum Exchange {
+    /// Largest spot venue.
+    ///
+    /// Supports "futures" too.
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[derive(ConcreteConfig)]
+#[concrete(manifest)]
+enum ExchangeConfig {
+    /// Binance with its API key
+    #[concrete = "exchanges::Binance"]
+    Binance(String),
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_concrete_manifest_forwards_variant_docs() {
+    let _ = [Exchange::Binance, Exchange::Okx];
+    assert_eq!(
+        Exchange::CONCRETE_MANIFEST,
+        concat!(
+            r#"{"enum":"Exchange","variants":["#,
+            r#"{"variant":"Binance","concrete_type":"exchanges::Binance","config_type":null,"tag":null,"trait":null,"constructor":null,"feature":null,"doc":"Largest spot venue.\n\nSupports \"futures\" too."},"#,
+            r#"{"variant":"Okx","concrete_type":"exchanges::Okx","config_type":null,"tag":null,"trait":null,"constructor":null,"feature":null,"doc":null}]}"#,
+        )
+    );
+}
+
+#[test]
+fn test_config_manifest_forwards_variant_docs() {
+    if let ExchangeConfig::Binance(key) = ExchangeConfig::Binance(String::from("key")) {
+        assert_eq!(key, "key");
+    }
+    let _ = (ExchangeConfig::Okx, exchanges::Binance, exchanges::Okx);
+
+    assert_eq!(
+        ExchangeConfig::CONCRETE_MANIFEST,
+        concat!(
+            r#"{"enum":"ExchangeConfig","variants":["#,
+            r#"{"variant":"Binance","concrete_type":"exchanges::Binance","config_type":"String","tag":null,"trait":null,"constructor":null,"feature":null,"doc":"Binance with its API key"},"#,
+            r#"{"variant":"Okx","concrete_type":"exchanges::Okx","config_type":null,"tag":null,"trait":null,"constructor":null,"feature":null,"doc":null}]}"#,
+        )
+    );
+}
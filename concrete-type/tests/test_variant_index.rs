@@ -0,0 +1,69 @@
+use concrete_type::{Concrete, ConcreteConfig};
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(set)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+#[derive(ConcreteConfig)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(String),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+// Parallel array indexed by `variant_index`
+static REQUEST_LIMITS: [u32; Exchange::VARIANT_COUNT] = [1200, 600, 300];
+
+#[test]
+fn test_variant_index_is_dense_declaration_order() {
+    assert_eq!(Exchange::VARIANT_COUNT, 3);
+    let variants = [Exchange::Binance, Exchange::Okx, Exchange::Kraken];
+    for (index, variant) in variants.into_iter().enumerate() {
+        assert_eq!(variant.variant_index(), index);
+        assert_eq!(Exchange::from_index(index), Some(variant));
+    }
+    assert_eq!(Exchange::from_index(Exchange::VARIANT_COUNT), None);
+    assert_eq!(REQUEST_LIMITS[Exchange::Kraken.variant_index()], 300);
+
+    const OKX: usize = Exchange::Okx.variant_index();
+    assert_eq!(OKX, 1);
+
+    assert_eq!(ExchangeConfig::VARIANT_COUNT, 3);
+    let configs = [
+        ExchangeConfig::Binance(String::new()),
+        ExchangeConfig::Okx,
+        ExchangeConfig::Kraken,
+    ];
+    for (index, config) in configs.into_iter().enumerate() {
+        assert_eq!(config.variant_index(), index);
+        let name = exchange_config!(config; (E, _cfg) => { std::any::type_name::<E>() });
+        assert!(name.contains("exchanges::"));
+    }
+}
+
+#[test]
+fn test_set_follows_variant_index() {
+    let set: ExchangeSet = [Exchange::Kraken, Exchange::Binance].into_iter().collect();
+    assert_eq!(
+        set.iter()
+            .map(|variant| variant.variant_index())
+            .collect::<Vec<_>>(),
+        [0, 2]
+    );
+}
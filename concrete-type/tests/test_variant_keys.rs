@@ -0,0 +1,78 @@
+use concrete_type::Concrete;
+use exchange_keys::Key;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+
+    pub trait Exchange {
+        const FEE_BPS: u32;
+    }
+
+    impl Exchange for Binance {
+        const FEE_BPS: u32 = 10;
+    }
+
+    impl Exchange for Okx {
+        const FEE_BPS: u32 = 8;
+    }
+}
+
+#[derive(Concrete, Clone, Copy)]
+#[concrete(keys)]
+enum Exchange {
+    #[concrete = "exchanges::Binance"]
+    Binance,
+    #[concrete = "exchanges::Okx"]
+    Okx,
+}
+
+/// A typemap-style container holding state per backend
+#[derive(Default)]
+struct States(HashMap<TypeId, Box<dyn Any>>);
+
+impl States {
+    fn insert<K: Key>(&mut self, _key: K, state: u32) {
+        self.0.insert(TypeId::of::<K>(), Box::new(state));
+    }
+
+    fn get<K: Key>(&self) -> Option<&u32> {
+        self.0.get(&TypeId::of::<K>())?.downcast_ref()
+    }
+}
+
+fn fee_bps<K>() -> u32
+where
+    K: Key,
+    K::Concrete: exchanges::Exchange,
+{
+    <K::Concrete as exchanges::Exchange>::FEE_BPS
+}
+
+#[test]
+fn test_key_of_variant() {
+    assert_eq!(
+        Exchange::Binance.key(),
+        ExchangeKey::Binance(exchange_keys::Binance)
+    );
+    assert_eq!(Exchange::Okx.key(), exchange_keys::Okx::key());
+    assert_eq!(exchange_keys::Okx::VARIANT, "Okx");
+}
+
+#[test]
+fn test_keys_store_per_backend_state() {
+    let mut states = States::default();
+    states.insert(exchange_keys::Binance, 1);
+    states.insert(exchange_keys::Okx, 2);
+
+    assert_eq!(states.get::<exchange_keys::Binance>(), Some(&1));
+    assert_eq!(states.get::<exchange_keys::Okx>(), Some(&2));
+}
+
+#[test]
+fn test_key_concrete_type() {
+    assert_eq!(fee_bps::<exchange_keys::Binance>(), 10);
+    assert_eq!(fee_bps::<exchange_keys::Okx>(), 8);
+}
@@ -0,0 +1,47 @@
+use concrete_type::Concrete;
+use std::any::type_name;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Clone, Copy)]
+enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+    #[concrete = "crate::exchanges::Kraken"]
+    Kraken,
+}
+
+fn supported_name(exchange: Exchange) -> Option<&'static str> {
+    exchange!(exchange; [Binance, Okx]; E => { Some(type_name::<E>()) } else { None })
+}
+
+#[test]
+fn test_listed_variants_dispatch() {
+    assert_eq!(
+        supported_name(Exchange::Binance),
+        Some(type_name::<exchanges::Binance>())
+    );
+    assert_eq!(
+        supported_name(Exchange::Okx),
+        Some(type_name::<exchanges::Okx>())
+    );
+}
+
+#[test]
+fn test_other_variants_use_else() {
+    assert_eq!(supported_name(Exchange::Kraken), None);
+}
+
+#[test]
+fn test_subset_covering_every_variant() {
+    let name = exchange!(Exchange::Kraken; [Binance, Okx, Kraken,]; E => { type_name::<E>() } else {
+        unreachable!()
+    });
+    assert_eq!(name, type_name::<exchanges::Kraken>());
+}
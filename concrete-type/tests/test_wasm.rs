@@ -0,0 +1,33 @@
+#![cfg(feature = "wasm")]
+
+use concrete_type::Concrete;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(wasm)]
+pub enum Exchange {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+    #[concrete = "crate::exchanges::Okx"]
+    Okx,
+}
+
+#[test]
+fn test_wasm_discriminants() {
+    assert_eq!(ExchangeWasm::Binance as u32, 0);
+    assert_eq!(ExchangeWasm::Okx as u32, 1);
+}
+
+#[test]
+fn test_wasm_conversions() {
+    for exchange in [Exchange::Binance, Exchange::Okx] {
+        let exported = ExchangeWasm::from(exchange);
+        assert_eq!(exported as usize, exchange.variant_index());
+        assert_eq!(Exchange::from(exported), exchange);
+        exchange!(exchange; E => { let _ = std::any::type_name::<E>(); });
+    }
+}
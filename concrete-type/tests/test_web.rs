@@ -0,0 +1,125 @@
+#![cfg(feature = "web")]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{HeaderValue, Request, StatusCode};
+use axum::routing::get;
+use concrete_type::Concrete;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use tower::ServiceExt;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(from_name, web)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", alias = "binance")]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", alias = "okx")]
+    Okx,
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+#[concrete(from_name, web = "venue")]
+enum Venue {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance,
+}
+
+// The handlers complete without waiting, so polling them once is enough
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("future is pending"),
+    }
+}
+
+fn get_body(app: &Router, uri: &str) -> (StatusCode, String) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = block_on(app.clone().oneshot(request)).unwrap();
+    let status = response.status();
+    let body = block_on(axum::body::to_bytes(response.into_body(), 1024)).unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/orders/{exchange}",
+            get(|exchange: Exchange| async move {
+                exchange!(exchange; E => { std::any::type_name::<E>().to_string() })
+            }),
+        )
+        .route(
+            "/venues/{venue}",
+            get(|venue: Venue| async move {
+                venue!(venue; V => { std::any::type_name::<V>().to_string() })
+            }),
+        )
+        .route(
+            "/misrouted/{id}",
+            get(|exchange: Exchange| async move { format!("{:?}", exchange) }),
+        )
+}
+
+#[test]
+fn test_path_extractor() {
+    let app = app();
+    let (status, body) = get_body(&app, "/orders/okx");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, std::any::type_name::<exchanges::Okx>());
+
+    let (status, body) = get_body(&app, "/orders/Binance");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, std::any::type_name::<exchanges::Binance>());
+
+    let (status, body) = get_body(&app, "/venues/Binance");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, std::any::type_name::<exchanges::Binance>());
+}
+
+#[test]
+fn test_unknown_name_rejection() {
+    let (status, body) = get_body(&app(), "/orders/kra%22ken");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        body,
+        r#"{"error":"unknown `Exchange` `kra\"ken`, expected one of: Binance, binance, Okx, okx","expected":["Binance","binance","Okx","okx"]}"#
+    );
+    assert_eq!(
+        ExchangeRejection::EXPECTED,
+        ["Binance", "binance", "Okx", "okx"]
+    );
+}
+
+#[test]
+fn test_missing_param_rejection() {
+    let (status, body) = get_body(&app(), "/misrouted/okx");
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(
+        body.contains("route has no `{exchange}` path parameter"),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn test_header_value() {
+    let header = HeaderValue::from_static("okx");
+    assert_eq!(Exchange::try_from(&header), Ok(Exchange::Okx));
+
+    let header = HeaderValue::from_static("kraken");
+    assert_eq!(
+        Exchange::try_from(&header),
+        Err(ExchangeRejection::UnknownName {
+            input: "kraken".to_string(),
+        })
+    );
+}
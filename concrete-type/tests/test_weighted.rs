@@ -0,0 +1,67 @@
+#![cfg(feature = "rand")]
+// `binance` stands in for a backend feature that is declared but disabled
+#![allow(unexpected_cfgs)]
+
+use concrete_type::Concrete;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+mod exchanges {
+    pub struct Binance;
+    pub struct Okx;
+    pub struct Kraken;
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Exchange {
+    #[concrete(ty = "crate::exchanges::Binance", weight = 3)]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", weight = 1)]
+    Okx,
+    #[concrete(ty = "crate::exchanges::Kraken", weight = 0)]
+    Kraken,
+}
+
+#[test]
+fn test_weight() {
+    const OKX_WEIGHT: u32 = Exchange::Okx.weight();
+    assert_eq!(OKX_WEIGHT, 1);
+    assert_eq!(Exchange::Binance.weight(), 3);
+    assert_eq!(Exchange::Kraken.weight(), 0);
+}
+
+#[test]
+fn test_choose_weighted() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut counts = [0usize; 3];
+    for _ in 0..4000 {
+        let exchange = Exchange::choose_weighted(&mut rng);
+        counts[exchange.variant_index()] += 1;
+        exchange!(exchange; E => { let _ = std::any::type_name::<E>(); });
+    }
+
+    assert_eq!(counts[Exchange::Kraken.variant_index()], 0);
+    let binance = counts[Exchange::Binance.variant_index()];
+    let okx = counts[Exchange::Okx.variant_index()];
+    assert_eq!(binance + okx, 4000);
+    assert!((2700..3300).contains(&binance), "{}", binance);
+}
+
+#[derive(Concrete, Clone, Copy, Debug, PartialEq)]
+enum Backend {
+    #[concrete(ty = "crate::exchanges::Binance", weight = 5, feature = "binance")]
+    Binance,
+    #[concrete(ty = "crate::exchanges::Okx", weight = 1)]
+    Okx,
+}
+
+#[test]
+fn test_disabled_variant_never_chosen() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..100 {
+        let backend = Backend::choose_weighted(&mut rng);
+        assert_eq!(backend, Backend::Okx);
+        backend!(backend; B => { let _ = std::any::type_name::<B>(); });
+    }
+    assert_eq!(Backend::Binance.weight(), 5);
+}
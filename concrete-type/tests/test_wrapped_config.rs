@@ -0,0 +1,153 @@
+use concrete_type::ConcreteConfig;
+
+mod exchanges {
+    pub trait ExchangeApi {
+        type Config;
+
+        fn new(config: Self::Config) -> Self;
+        fn name(&self) -> String;
+    }
+
+    pub struct Binance(String);
+
+    #[derive(Debug, Default, PartialEq)]
+    pub struct BinanceConfig {
+        pub api_key: String,
+    }
+
+    impl ExchangeApi for Binance {
+        type Config = BinanceConfig;
+
+        fn new(config: Self::Config) -> Self {
+            Self(config.api_key)
+        }
+
+        fn name(&self) -> String {
+            format!("binance:{}", self.0)
+        }
+    }
+
+    pub struct Okx(u32);
+
+    impl ExchangeApi for Okx {
+        type Config = u32;
+
+        fn new(config: Self::Config) -> Self {
+            Self(config)
+        }
+
+        fn name(&self) -> String {
+            format!("okx:{}", self.0)
+        }
+    }
+}
+
+use exchanges::{BinanceConfig, ExchangeApi};
+
+#[derive(ConcreteConfig)]
+#[concrete(unwrap)]
+enum ExchangeConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(Box<BinanceConfig>),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(Option<u32>),
+}
+
+#[test]
+fn test_boxed_config_is_unboxed() {
+    let config = ExchangeConfig::Binance(Box::new(BinanceConfig {
+        api_key: "key".to_string(),
+    }));
+    #[cfg(feature = "config-any")]
+    assert_eq!(
+        config.config().downcast_ref::<BinanceConfig>(),
+        Some(&BinanceConfig {
+            api_key: "key".to_string()
+        })
+    );
+
+    let name = exchange_config!(config; (Exchange, cfg) => { Exchange::new(cfg).name() });
+    assert_eq!(name, "binance:key");
+}
+
+#[test]
+fn test_optional_config_defaults_when_none() {
+    let name = exchange_config!(ExchangeConfig::Okx(Some(7)); (Exchange, cfg) => {
+        Exchange::new(cfg).name()
+    });
+    assert_eq!(name, "okx:7");
+
+    let config = ExchangeConfig::Okx(None);
+    #[cfg(feature = "config-any")]
+    assert!(config.config().is::<()>());
+    let name = exchange_config!(config; (Exchange, cfg) => { Exchange::new(cfg).name() });
+    assert_eq!(name, "okx:0");
+}
+
+#[test]
+fn test_optional_config_else_branch() {
+    let name = exchange_config!(ExchangeConfig::Okx(None); (Exchange, cfg) => {
+        Exchange::new(cfg).name()
+    } else {
+        "missing".to_string()
+    });
+    assert_eq!(name, "missing");
+
+    let name = exchange_config!(ExchangeConfig::Okx(Some(1)); (Exchange, cfg) => {
+        Exchange::new(cfg).name()
+    } else {
+        "missing".to_string()
+    });
+    assert_eq!(name, "okx:1");
+}
+
+#[test]
+fn test_boxed_config_borrowed_when_matched_by_reference() {
+    let mut config = ExchangeConfig::Binance(Box::new(BinanceConfig {
+        api_key: "key".to_string(),
+    }));
+    let bound = exchange_config!(&config; (_Exchange, cfg) => {
+        std::any::type_name_of_val(&cfg)
+    });
+    assert_eq!(bound, std::any::type_name::<&BinanceConfig>());
+
+    let bound = exchange_config!(&mut config; (_Exchange, cfg) => {
+        std::any::type_name_of_val(&cfg)
+    });
+    assert_eq!(bound, std::any::type_name::<&mut BinanceConfig>());
+
+    // The enum is still usable, as nothing was moved out of it
+    let name = exchange_config!(config; (Exchange, cfg) => { Exchange::new(cfg).name() });
+    assert_eq!(name, "binance:key");
+}
+
+/// Config without a `Default` implementation
+#[derive(Debug, PartialEq)]
+struct Endpoint(&'static str);
+
+// Without `unwrap`, wrapped configs are bound as declared
+#[derive(ConcreteConfig)]
+enum DeclaredConfig {
+    #[concrete = "crate::exchanges::Binance"]
+    Binance(Box<BinanceConfig>),
+    #[concrete = "crate::exchanges::Okx"]
+    Okx(Option<Endpoint>),
+}
+
+#[test]
+fn test_wrapped_configs_bound_as_declared_without_unwrap() {
+    let config = DeclaredConfig::Binance(Box::default());
+    let bound = declared_config!(&config; (_Exchange, cfg) => {
+        std::any::type_name_of_val(&cfg)
+    });
+    assert_eq!(bound, std::any::type_name::<&Box<BinanceConfig>>());
+
+    let config = DeclaredConfig::Okx(Some(Endpoint("ws")));
+    let bound = declared_config!(&config; (_Exchange, cfg) => {
+        std::any::type_name_of_val(&cfg)
+    });
+    assert_eq!(bound, std::any::type_name::<&Option<Endpoint>>());
+    if let DeclaredConfig::Okx(endpoint) = config {
+        assert_eq!(endpoint, Some(Endpoint("ws")));
+    }
+}